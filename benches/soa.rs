@@ -168,6 +168,53 @@ fn soa_big_do_work_100k(bencher: &mut Bencher) {
     })
 }
 
+fn soa_small_shuffled(size: usize) -> SmallVec {
+    // simple linear congruential generator, to get the same values every time
+    let mut state = 42_u64;
+    let mut vec = SmallVec::with_capacity(size);
+    for _ in 0..size {
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        vec.push(Small {
+            x: (state >> 11) as f64,
+            y: 0.2,
+            z: -2.3,
+        });
+    }
+    return vec;
+}
+
+fn soa_sort_by_1m(bencher: &mut Bencher) {
+    bencher.iter(||{
+        let mut vec = soa_small_shuffled(1_000_000);
+        vec.as_mut_slice().sort_by(|a, b| a.x.total_cmp(b.x));
+        vec
+    })
+}
+
+fn soa_sort_unstable_by_1m(bencher: &mut Bencher) {
+    bencher.iter(||{
+        let mut vec = soa_small_shuffled(1_000_000);
+        vec.as_mut_slice().sort_unstable_by(|a, b| a.x.total_cmp(b.x));
+        vec
+    })
+}
+
+fn soa_sort_by_cached_key_1m(bencher: &mut Bencher) {
+    bencher.iter(||{
+        let mut vec = soa_small_shuffled(1_000_000);
+        vec.as_mut_slice().sort_by_cached_key(|small| small.x.to_bits());
+        vec
+    })
+}
+
+fn soa_select_nth_unstable_by_1m(bencher: &mut Bencher) {
+    bencher.iter(||{
+        let mut vec = soa_small_shuffled(1_000_000);
+        vec.as_mut_slice().select_nth_unstable_by(500_000, |a, b| a.x.total_cmp(b.x));
+        vec
+    })
+}
+
 
 benchmark_group!(aos,
     aos_small_push, aos_big_push, aos_small_do_work_100k, aos_big_do_work_10k,
@@ -177,4 +224,8 @@ benchmark_group!(soa,
     soa_small_push, soa_big_push, soa_small_do_work_100k, soa_big_do_work_10k,
     soa_big_do_work_100k
 );
-benchmark_main!(soa, aos);
+benchmark_group!(sort,
+    soa_sort_by_1m, soa_sort_unstable_by_1m, soa_sort_by_cached_key_1m,
+    soa_select_nth_unstable_by_1m
+);
+benchmark_main!(soa, aos, sort);
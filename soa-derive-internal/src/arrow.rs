@@ -0,0 +1,165 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::input::Input;
+use crate::names;
+
+pub fn derive(input: &Input) -> TokenStream {
+    let krate = &input.attrs.crate_path;
+    let vec_name = &input.attrs.names.vec;
+
+    let (owned_impl_generics, owned_ty_generics, _) = input.generics.split_for_impl();
+    let where_predicates = input.where_predicates();
+
+    let fields_names = input.fields_names();
+    let marker = input.marker_init();
+    // the name of the Arrow column for each field
+    let columns_names = fields_names.iter().map(names::data_column_name).collect::<Vec<_>>();
+
+    let arrow_bounds = input.map_fields_nested_or(
+        |_, field_type| {
+            let vec_type = names::nested_type(field_type, names::vec_name, None);
+            quote! { for<'b> #vec_type: #krate::arrow::ArrowStructOfArray }
+        },
+        |_, field_type| quote! { for<'b> #field_type: #krate::arrow::ArrowColumn },
+    ).collect::<Vec<_>>();
+
+    let arrow_fields = input.map_fields_nested_or(
+        |_, field_type| {
+            let vec_type = names::nested_type(field_type, names::vec_name, None);
+            quote! {
+                #krate::arrow::arrow_schema::DataType::Struct(
+                    <#vec_type as #krate::arrow::ArrowStructOfArray>::arrow_fields()
+                ),
+                false
+            }
+        },
+        |_, field_type| quote! {
+            <#field_type as #krate::arrow::ArrowColumn>::data_type(),
+            <#field_type as #krate::arrow::ArrowColumn>::is_nullable()
+        },
+    ).zip(&columns_names).map(|(field, name)| quote! {
+        #krate::arrow::Field::new(#name, #field)
+    }).collect::<Vec<_>>();
+
+    let to_arrow_columns = input.map_fields_nested_or(
+        |ident, field_type| {
+            let vec_type = names::nested_type(field_type, names::vec_name, None);
+            quote! {
+                #krate::arrow::__private_struct_array(
+                    #krate::arrow::ArrowStructOfArray::to_arrow_columns(&self.#ident),
+                    <#vec_type as #krate::arrow::ArrowStructOfArray>::arrow_fields(),
+                )
+            }
+        },
+        |ident, field_type| quote! {
+            <#field_type as #krate::arrow::ArrowColumn>::to_array(&self.#ident)
+        },
+    ).collect::<Vec<_>>();
+
+    // the vector implements Drop, so we need to take the fields out of it
+    let into_arrow_columns = input.map_fields_nested_or(
+        |ident, field_type| {
+            let vec_type = names::nested_type(field_type, names::vec_name, None);
+            quote! {
+                #krate::arrow::__private_struct_array(
+                    #krate::arrow::ArrowStructOfArray::into_arrow_columns(::std::mem::take(&mut self.#ident)),
+                    <#vec_type as #krate::arrow::ArrowStructOfArray>::arrow_fields(),
+                )
+            }
+        },
+        |ident, field_type| quote! {
+            <#field_type as #krate::arrow::ArrowColumn>::into_array(::std::mem::take(&mut self.#ident))
+        },
+    ).collect::<Vec<_>>();
+
+    let read_columns = input.map_fields_nested_or(
+        |_, _| quote! { #krate::arrow::__private_read_struct_column },
+        |_, _| quote! { #krate::arrow::__private_read_column },
+    ).zip(&columns_names).map(|(read, name)| quote! {
+        #read(fields, &mut columns, #name)?
+    }).collect::<Vec<_>>();
+
+    return quote! {
+        impl #owned_impl_generics #krate::arrow::ArrowStructOfArray for #vec_name #owned_ty_generics
+        where
+            #( #where_predicates, )*
+            #( #arrow_bounds, )*
+        {
+            fn arrow_fields() -> #krate::arrow::arrow_schema::Fields {
+                #krate::arrow::arrow_schema::Fields::from(vec![
+                    #( #arrow_fields, )*
+                ])
+            }
+
+            fn to_arrow_columns(&self) -> Vec<#krate::arrow::arrow_array::ArrayRef> {
+                vec![
+                    #( #to_arrow_columns, )*
+                ]
+            }
+
+            fn into_arrow_columns(mut self) -> Vec<#krate::arrow::arrow_array::ArrayRef> {
+                vec![
+                    #( #into_arrow_columns, )*
+                ]
+            }
+
+            fn try_from_arrow_columns(
+                fields: &#krate::arrow::arrow_schema::Fields,
+                columns: Vec<#krate::arrow::arrow_array::ArrayRef>,
+            ) -> Result<Self, #krate::arrow::arrow_schema::ArrowError> {
+                let mut columns = columns.into_iter().map(Some).collect::<Vec<_>>();
+                Ok(#vec_name {
+                    #( #fields_names: #read_columns, )*
+                    #marker
+                })
+            }
+        }
+
+        #[allow(dead_code)]
+        impl #owned_impl_generics #vec_name #owned_ty_generics
+        where
+            #( #where_predicates, )*
+            #( #arrow_bounds, )*
+        {
+            /// Get the Arrow schema of the record batches created from this
+            /// vector, with one column for each field
+            pub fn arrow_schema() -> #krate::arrow::Schema {
+                #krate::arrow::Schema::new(<Self as #krate::arrow::ArrowStructOfArray>::arrow_fields())
+            }
+
+            /// Create an Arrow record batch containing a copy of the data in
+            /// this vector
+            pub fn to_record_batch(&self) -> #krate::arrow::RecordBatch {
+                let schema = ::std::sync::Arc::new(Self::arrow_schema());
+                let columns = #krate::arrow::ArrowStructOfArray::to_arrow_columns(self);
+                #krate::arrow::RecordBatch::try_new(schema, columns).expect("the columns should match the schema")
+            }
+
+            /// Convert this vector into an Arrow record batch. The columns of
+            /// primitive types are moved to the record batch without copying
+            /// the data.
+            pub fn into_record_batch(self) -> #krate::arrow::RecordBatch {
+                let schema = ::std::sync::Arc::new(Self::arrow_schema());
+                let columns = #krate::arrow::ArrowStructOfArray::into_arrow_columns(self);
+                #krate::arrow::RecordBatch::try_new(schema, columns).expect("the columns should match the schema")
+            }
+        }
+
+        impl #owned_impl_generics ::std::convert::TryFrom<#krate::arrow::RecordBatch> for #vec_name #owned_ty_generics
+        where
+            #( #where_predicates, )*
+            #( #arrow_bounds, )*
+        {
+            type Error = #krate::arrow::arrow_schema::ArrowError;
+
+            /// Create a vector from the columns of an Arrow record batch with
+            /// the same names as the fields. The data of primitive columns is
+            /// re-used without copy if the record batch is its only owner.
+            fn try_from(batch: #krate::arrow::RecordBatch) -> Result<Self, Self::Error> {
+                let (schema, columns, _) = batch.into_parts();
+                <Self as #krate::arrow::ArrowStructOfArray>::try_from_arrow_columns(schema.fields(), columns)
+            }
+        }
+    };
+}
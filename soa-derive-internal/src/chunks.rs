@@ -0,0 +1,378 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::input::Input;
+
+pub fn derive(input: &Input) -> TokenStream {
+    let visibility = &input.visibility;
+    let vec_name = &input.attrs.names.vec;
+    let slice_name = &input.attrs.names.slice;
+    let slice_mut_name = &input.attrs.names.slice_mut;
+    let chunks_name = &input.attrs.names.chunks;
+    let chunks_exact_name = &input.attrs.names.chunks_exact;
+    let chunks_mut_name = &input.attrs.names.chunks_mut;
+    let rchunks_name = &input.attrs.names.rchunks;
+    let windows_name = &input.attrs.names.windows;
+
+    let generics = input.generics_with_lifetime("'a");
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let ty_generics_anon = input.ty_generics_with_lifetime("'_");
+    let (owned_impl_generics, owned_ty_generics, owned_where_clause) = input.generics.split_for_impl();
+
+    let slice_name_str = format!("[{}]", input.name);
+    let slice_doc_url = format!("[`{0}`](struct.{0}.html)", slice_name);
+    let slice_mut_doc_url = format!("[`{0}`](struct.{0}.html)", slice_mut_name);
+
+    return quote! {
+        /// An iterator over
+        #[doc = #slice_doc_url]
+        /// in non-overlapping chunks, starting at the beginning of the slice.
+        #[allow(missing_debug_implementations)]
+        #visibility struct #chunks_name #impl_generics #where_clause {
+            slice: #slice_name #ty_generics,
+            chunk_size: usize,
+        }
+
+        impl #impl_generics Iterator for #chunks_name #ty_generics #where_clause {
+            type Item = #slice_name #ty_generics;
+
+            fn next(&mut self) -> Option<#slice_name #ty_generics> {
+                if self.slice.is_empty() {
+                    return None;
+                }
+
+                let size = ::std::cmp::min(self.slice.len(), self.chunk_size);
+                let (chunk, rest) = self.slice.split_at(size);
+                self.slice = rest;
+                Some(chunk)
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                let len = self.slice.len() / self.chunk_size + usize::from(self.slice.len() % self.chunk_size != 0);
+                (len, Some(len))
+            }
+        }
+
+        impl #impl_generics DoubleEndedIterator for #chunks_name #ty_generics #where_clause {
+            fn next_back(&mut self) -> Option<#slice_name #ty_generics> {
+                if self.slice.is_empty() {
+                    return None;
+                }
+
+                let len = self.slice.len();
+                let remainder = len % self.chunk_size;
+                let size = if remainder == 0 { self.chunk_size } else { remainder };
+                let (rest, chunk) = self.slice.split_at(len - size);
+                self.slice = rest;
+                Some(chunk)
+            }
+        }
+
+        impl #impl_generics ExactSizeIterator for #chunks_name #ty_generics #where_clause {}
+        impl #impl_generics ::std::iter::FusedIterator for #chunks_name #ty_generics #where_clause {}
+
+        /// An iterator over
+        #[doc = #slice_doc_url]
+        /// in non-overlapping chunks of exactly `chunk_size` elements,
+        /// starting at the beginning of the slice. The last elements which do
+        /// not fit in a chunk are available with `remainder()`.
+        #[allow(missing_debug_implementations)]
+        #visibility struct #chunks_exact_name #impl_generics #where_clause {
+            slice: #slice_name #ty_generics,
+            remainder: #slice_name #ty_generics,
+            chunk_size: usize,
+        }
+
+        #[allow(dead_code)]
+        impl #impl_generics #chunks_exact_name #ty_generics #where_clause {
+            /// Get the elements at the end of the slice which are not part of
+            /// any chunk, containing at most `chunk_size - 1` elements.
+            pub fn remainder(&self) -> #slice_name #ty_generics {
+                self.remainder
+            }
+        }
+
+        impl #impl_generics Iterator for #chunks_exact_name #ty_generics #where_clause {
+            type Item = #slice_name #ty_generics;
+
+            fn next(&mut self) -> Option<#slice_name #ty_generics> {
+                if self.slice.len() < self.chunk_size {
+                    return None;
+                }
+
+                let (chunk, rest) = self.slice.split_at(self.chunk_size);
+                self.slice = rest;
+                Some(chunk)
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                let len = self.slice.len() / self.chunk_size;
+                (len, Some(len))
+            }
+        }
+
+        impl #impl_generics DoubleEndedIterator for #chunks_exact_name #ty_generics #where_clause {
+            fn next_back(&mut self) -> Option<#slice_name #ty_generics> {
+                if self.slice.len() < self.chunk_size {
+                    return None;
+                }
+
+                let (rest, chunk) = self.slice.split_at(self.slice.len() - self.chunk_size);
+                self.slice = rest;
+                Some(chunk)
+            }
+        }
+
+        impl #impl_generics ExactSizeIterator for #chunks_exact_name #ty_generics #where_clause {}
+        impl #impl_generics ::std::iter::FusedIterator for #chunks_exact_name #ty_generics #where_clause {}
+
+        /// An iterator over
+        #[doc = #slice_doc_url]
+        /// in non-overlapping chunks, starting at the end of the slice.
+        #[allow(missing_debug_implementations)]
+        #visibility struct #rchunks_name #impl_generics #where_clause {
+            slice: #slice_name #ty_generics,
+            chunk_size: usize,
+        }
+
+        impl #impl_generics Iterator for #rchunks_name #ty_generics #where_clause {
+            type Item = #slice_name #ty_generics;
+
+            fn next(&mut self) -> Option<#slice_name #ty_generics> {
+                if self.slice.is_empty() {
+                    return None;
+                }
+
+                let len = self.slice.len();
+                let size = ::std::cmp::min(len, self.chunk_size);
+                let (rest, chunk) = self.slice.split_at(len - size);
+                self.slice = rest;
+                Some(chunk)
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                let len = self.slice.len() / self.chunk_size + usize::from(self.slice.len() % self.chunk_size != 0);
+                (len, Some(len))
+            }
+        }
+
+        impl #impl_generics DoubleEndedIterator for #rchunks_name #ty_generics #where_clause {
+            fn next_back(&mut self) -> Option<#slice_name #ty_generics> {
+                if self.slice.is_empty() {
+                    return None;
+                }
+
+                let remainder = self.slice.len() % self.chunk_size;
+                let size = if remainder == 0 { self.chunk_size } else { remainder };
+                let (chunk, rest) = self.slice.split_at(size);
+                self.slice = rest;
+                Some(chunk)
+            }
+        }
+
+        impl #impl_generics ExactSizeIterator for #rchunks_name #ty_generics #where_clause {}
+        impl #impl_generics ::std::iter::FusedIterator for #rchunks_name #ty_generics #where_clause {}
+
+        /// An iterator over overlapping sub-slices of
+        #[doc = #slice_doc_url]
+        /// with `size` elements.
+        #[allow(missing_debug_implementations)]
+        #visibility struct #windows_name #impl_generics #where_clause {
+            slice: #slice_name #ty_generics,
+            size: usize,
+        }
+
+        impl #impl_generics Iterator for #windows_name #ty_generics #where_clause {
+            type Item = #slice_name #ty_generics;
+
+            fn next(&mut self) -> Option<#slice_name #ty_generics> {
+                if self.size > self.slice.len() {
+                    return None;
+                }
+
+                let (window, _) = self.slice.split_at(self.size);
+                let (_, rest) = self.slice.split_at(1);
+                self.slice = rest;
+                Some(window)
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                let len = (self.slice.len() + 1).saturating_sub(self.size);
+                (len, Some(len))
+            }
+        }
+
+        impl #impl_generics DoubleEndedIterator for #windows_name #ty_generics #where_clause {
+            fn next_back(&mut self) -> Option<#slice_name #ty_generics> {
+                let len = self.slice.len();
+                if self.size > len {
+                    return None;
+                }
+
+                let (_, window) = self.slice.split_at(len - self.size);
+                let (rest, _) = self.slice.split_at(len - 1);
+                self.slice = rest;
+                Some(window)
+            }
+        }
+
+        impl #impl_generics ExactSizeIterator for #windows_name #ty_generics #where_clause {}
+        impl #impl_generics ::std::iter::FusedIterator for #windows_name #ty_generics #where_clause {}
+
+        /// An iterator over
+        #[doc = #slice_mut_doc_url]
+        /// in non-overlapping mutable chunks, starting at the beginning of
+        /// the slice.
+        #[allow(missing_debug_implementations)]
+        #visibility struct #chunks_mut_name #impl_generics #where_clause {
+            slice: #slice_mut_name #ty_generics,
+            chunk_size: usize,
+        }
+
+        impl #impl_generics Iterator for #chunks_mut_name #ty_generics #where_clause {
+            type Item = #slice_mut_name #ty_generics;
+
+            fn next(&mut self) -> Option<#slice_mut_name #ty_generics> {
+                if self.slice.is_empty() {
+                    return None;
+                }
+
+                let size = ::std::cmp::min(self.slice.len(), self.chunk_size);
+                let slice = ::std::mem::take(&mut self.slice);
+                let (chunk, rest) = slice.split_at_mut(size);
+                self.slice = rest;
+                Some(chunk)
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                let len = self.slice.len() / self.chunk_size + usize::from(self.slice.len() % self.chunk_size != 0);
+                (len, Some(len))
+            }
+        }
+
+        impl #impl_generics DoubleEndedIterator for #chunks_mut_name #ty_generics #where_clause {
+            fn next_back(&mut self) -> Option<#slice_mut_name #ty_generics> {
+                if self.slice.is_empty() {
+                    return None;
+                }
+
+                let len = self.slice.len();
+                let remainder = len % self.chunk_size;
+                let size = if remainder == 0 { self.chunk_size } else { remainder };
+                let slice = ::std::mem::take(&mut self.slice);
+                let (rest, chunk) = slice.split_at_mut(len - size);
+                self.slice = rest;
+                Some(chunk)
+            }
+        }
+
+        impl #impl_generics ExactSizeIterator for #chunks_mut_name #ty_generics #where_clause {}
+        impl #impl_generics ::std::iter::FusedIterator for #chunks_mut_name #ty_generics #where_clause {}
+
+        #[allow(dead_code)]
+        impl #impl_generics #slice_name #ty_generics #where_clause {
+            /// Similar to [`&
+            #[doc = #slice_name_str]
+            /// ::chunks()`](https://doc.rust-lang.org/std/primitive.slice.html#method.chunks).
+            pub fn chunks(&self, chunk_size: usize) -> #chunks_name #ty_generics {
+                assert!(chunk_size != 0, "chunk size must be non-zero");
+                #chunks_name {
+                    slice: *self,
+                    chunk_size: chunk_size,
+                }
+            }
+
+            /// Similar to [`&
+            #[doc = #slice_name_str]
+            /// ::chunks_exact()`](https://doc.rust-lang.org/std/primitive.slice.html#method.chunks_exact).
+            pub fn chunks_exact(&self, chunk_size: usize) -> #chunks_exact_name #ty_generics {
+                assert!(chunk_size != 0, "chunk size must be non-zero");
+                let len = self.len();
+                let (slice, remainder) = self.split_at(len - len % chunk_size);
+                #chunks_exact_name {
+                    slice: slice,
+                    remainder: remainder,
+                    chunk_size: chunk_size,
+                }
+            }
+
+            /// Similar to [`&
+            #[doc = #slice_name_str]
+            /// ::rchunks()`](https://doc.rust-lang.org/std/primitive.slice.html#method.rchunks).
+            pub fn rchunks(&self, chunk_size: usize) -> #rchunks_name #ty_generics {
+                assert!(chunk_size != 0, "chunk size must be non-zero");
+                #rchunks_name {
+                    slice: *self,
+                    chunk_size: chunk_size,
+                }
+            }
+
+            /// Similar to [`&
+            #[doc = #slice_name_str]
+            /// ::windows()`](https://doc.rust-lang.org/std/primitive.slice.html#method.windows).
+            pub fn windows(&self, size: usize) -> #windows_name #ty_generics {
+                assert!(size != 0, "window size must be non-zero");
+                #windows_name {
+                    slice: *self,
+                    size: size,
+                }
+            }
+        }
+
+        #[allow(dead_code)]
+        impl #impl_generics #slice_mut_name #ty_generics #where_clause {
+            /// Similar to [`&mut
+            #[doc = #slice_name_str]
+            /// ::chunks_mut()`](https://doc.rust-lang.org/std/primitive.slice.html#method.chunks_mut).
+            pub fn chunks_mut(&mut self, chunk_size: usize) -> #chunks_mut_name #ty_generics_anon {
+                assert!(chunk_size != 0, "chunk size must be non-zero");
+                #chunks_mut_name {
+                    slice: self.reborrow(),
+                    chunk_size: chunk_size,
+                }
+            }
+        }
+
+        #[allow(dead_code)]
+        impl #owned_impl_generics #vec_name #owned_ty_generics #owned_where_clause {
+            /// Similar to [`&
+            #[doc = #slice_name_str]
+            /// ::chunks()`](https://doc.rust-lang.org/std/primitive.slice.html#method.chunks).
+            pub fn chunks(&self, chunk_size: usize) -> #chunks_name #ty_generics_anon {
+                self.as_slice().chunks(chunk_size)
+            }
+
+            /// Similar to [`&
+            #[doc = #slice_name_str]
+            /// ::chunks_exact()`](https://doc.rust-lang.org/std/primitive.slice.html#method.chunks_exact).
+            pub fn chunks_exact(&self, chunk_size: usize) -> #chunks_exact_name #ty_generics_anon {
+                self.as_slice().chunks_exact(chunk_size)
+            }
+
+            /// Similar to [`&
+            #[doc = #slice_name_str]
+            /// ::rchunks()`](https://doc.rust-lang.org/std/primitive.slice.html#method.rchunks).
+            pub fn rchunks(&self, chunk_size: usize) -> #rchunks_name #ty_generics_anon {
+                self.as_slice().rchunks(chunk_size)
+            }
+
+            /// Similar to [`&
+            #[doc = #slice_name_str]
+            /// ::windows()`](https://doc.rust-lang.org/std/primitive.slice.html#method.windows).
+            pub fn windows(&self, size: usize) -> #windows_name #ty_generics_anon {
+                self.as_slice().windows(size)
+            }
+
+            /// Similar to [`&mut
+            #[doc = #slice_name_str]
+            /// ::chunks_mut()`](https://doc.rust-lang.org/std/primitive.slice.html#method.chunks_mut).
+            pub fn chunks_mut(&mut self, chunk_size: usize) -> #chunks_mut_name #ty_generics_anon {
+                assert!(chunk_size != 0, "chunk size must be non-zero");
+                #chunks_mut_name {
+                    slice: self.as_mut_slice(),
+                    chunk_size: chunk_size,
+                }
+            }
+        }
+    };
+}
@@ -0,0 +1,117 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::input::Input;
+use crate::names;
+
+pub fn derive(input: &Input) -> TokenStream {
+    let krate = &input.attrs.crate_path;
+    let visibility = &input.visibility;
+    let vec_name = &input.attrs.names.vec;
+    let slice_mut_name = &input.attrs.names.slice_mut;
+    let column_name = &input.attrs.names.column;
+
+    let generics = input.generics_with_lifetime("'a");
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let (owned_impl_generics, owned_ty_generics, _) = input.generics.split_for_impl();
+    let where_predicates = input.where_predicates();
+
+    let doc_url = format!("[`{0}`](struct.{0}.html)", input.name);
+    let vec_doc_url = format!("[`{0}`](struct.{0}.html)", vec_name);
+
+    let fields_names = input.fields_names();
+    let variants = fields_names.iter().map(names::column_variant_name).collect::<Vec<_>>();
+    let variants_docs = fields_names.iter().map(|field| {
+        let field = quote! { #field }.to_string();
+        format!("The `{}` column", field)
+    }).collect::<Vec<_>>();
+
+    let partial_ord_bounds = input.map_fields_nested_or(
+        |_, field_type| {
+            let field_ref_type = names::nested_type(field_type, names::ref_name, Some("'b"));
+            quote! { for<'b> #field_ref_type: PartialOrd }
+        },
+        |_, field_type| quote! { for<'b> #field_type: PartialOrd },
+    ).collect::<Vec<_>>();
+
+    let compare_column = input.map_fields_nested_or(
+        |ident, _| quote! {
+            #krate::__private_compare_column(&self.#ident.index(j), &self.#ident.index(k), key.order)
+        },
+        |ident, _| quote! {
+            #krate::__private_compare_column(&self.#ident[j], &self.#ident[k], key.order)
+        },
+    ).collect::<Vec<_>>();
+
+    return quote! {
+        /// Selector for one of the columns of a
+        #[doc = #vec_doc_url]
+        /// , used to build the specification given to `sort_by_columns`.
+        #[allow(dead_code)]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #visibility enum #column_name {
+            #(
+                #[doc = #variants_docs]
+                #variants,
+            )*
+        }
+
+        impl #column_name {
+            /// Sort this column in ascending order
+            pub fn asc(self) -> #krate::SortKey<#column_name> {
+                #krate::SortKey { column: self, order: #krate::SortOrder::Ascending }
+            }
+
+            /// Sort this column in descending order
+            pub fn desc(self) -> #krate::SortKey<#column_name> {
+                #krate::SortKey { column: self, order: #krate::SortOrder::Descending }
+            }
+        }
+
+        #[allow(dead_code)]
+        impl #impl_generics #slice_mut_name #ty_generics
+        where
+            #( #where_predicates, )*
+            #( #partial_ord_bounds, )*
+        {
+            /// Sort the elements of this slice in lexicographic order of the
+            /// given columns: elements are compared on the first column, then
+            /// on the second column for equal values in the first one, *etc.*
+            /// The sort is stable.
+            ///
+            /// The comparisons only touch the arrays of the given columns,
+            /// and the elements are moved once at the end. Values which can
+            /// not be compared to themselves (such as `NaN`) are placed after
+            /// all the other values, in both ascending and descending order.
+            ///
+            /// This method requires all the fields to implement `PartialOrd`,
+            /// including the ones which are not part of `columns`.
+            pub fn sort_by_columns(&mut self, columns: &[#krate::SortKey<#column_name>]) {
+                let mut indices: Vec<usize> = (0..self.len()).collect();
+                indices.sort_by(|&j, &k| {
+                    columns.iter().fold(::std::cmp::Ordering::Equal, |ordering, key| {
+                        ordering.then_with(|| match key.column {
+                            #( #column_name::#variants => #compare_column, )*
+                        })
+                    })
+                });
+                self.permute(&indices);
+            }
+        }
+
+        #[allow(dead_code)]
+        impl #owned_impl_generics #vec_name #owned_ty_generics
+        where
+            #( #where_predicates, )*
+            #( #partial_ord_bounds, )*
+        {
+            /// Sort the elements of this vector in lexicographic order of the
+            /// given columns, see `sort_by_columns` on the mutable slice of
+            #[doc = #doc_url]
+            /// .
+            pub fn sort_by_columns(&mut self, columns: &[#krate::SortKey<#column_name>]) {
+                self.as_mut_slice().sort_by_columns(columns);
+            }
+        }
+    };
+}
@@ -0,0 +1,611 @@
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::TokenStreamExt;
+use quote::{quote, ToTokens};
+use syn::{DataEnum, DeriveInput, Fields, Member, Variant};
+
+use crate::input::{Errors, ExtraAttributes};
+use crate::names;
+
+/// Names of the fields of the enum `Vec` type which are not used to store
+/// variants
+static RESERVED_FIELDS: &[&str] = &["kinds", "indices"];
+
+/// Options of `#[soa(...)]` which are accepted on structs, but do not apply to
+/// enums
+static STRUCT_ONLY_OPTIONS: &[&str] = &[
+    "slice", "slice_mut", "ptr", "ptr_mut", "iter_mut", "into_iter", "drain",
+    "splice", "extract_if", "chunks", "chunks_exact", "chunks_mut", "rchunks",
+    "windows", "column", "selection", "par_iter", "par_iter_mut", "arrow", "polars",
+];
+
+/// A single variant of the enum we are deriving
+struct EnumVariant<'a> {
+    /// The variant itself
+    variant: &'a Variant,
+    /// Name of the struct storing the fields of this variant, `None` for unit
+    /// variants
+    struct_name: Option<Ident>,
+    /// Name of the field storing this variant in the enum `Vec` type
+    field_name: Ident,
+    /// Names of the fields in this variant
+    fields_names: Vec<Member>,
+    /// Hygienic names to use for the fields of this variant when
+    /// destructuring it
+    fields_names_hygienic: Vec<Ident>,
+}
+
+impl<'a> EnumVariant<'a> {
+    fn new(name: &Ident, variant: &'a Variant) -> EnumVariant<'a> {
+        let struct_name = if variant.fields.is_empty() {
+            None
+        } else {
+            Some(names::variant_struct_name(name, &variant.ident))
+        };
+
+        let field_name = names::variant_field_name(&variant.ident);
+
+        let fields_names = variant.fields.iter().enumerate().map(|(i, field)| match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(i.into()),
+        }).collect();
+
+        let fields_names_hygienic = variant.fields.iter()
+            .enumerate()
+            .map(|(i, _)| Ident::new(&format!("___soa_derive_private_{}", i), Span::call_site()))
+            .collect();
+
+        EnumVariant { variant, struct_name, field_name, fields_names, fields_names_hygienic }
+    }
+
+    /// Get a pattern destructuring this variant of the enum `name` into the
+    /// hygienic fields names
+    fn pattern(&self, name: &Ident) -> TokenStream {
+        let ident = &self.variant.ident;
+        let fields_names = &self.fields_names;
+        let fields_names_hygienic = &self.fields_names_hygienic;
+        quote! {
+            #name::#ident { #(#fields_names: #fields_names_hygienic),* }
+        }
+    }
+}
+
+/// Generate the struct storing the fields of a variant, which is then used
+/// with the usual struct of array code.
+fn derive_variant_struct(input: &DeriveInput, variant: &EnumVariant, crate_path: &syn::Path) -> syn::Result<TokenStream> {
+    let struct_name = variant.struct_name.as_ref().expect("unit variants have no struct");
+    let visibility = &input.vis;
+    let doc_url = format!("[`{}::{}`](enum.{}.html)", input.ident, variant.variant.ident, input.ident);
+
+    let mut fields = variant.variant.fields.clone();
+    for field in &mut fields {
+        field.vis = syn::parse_quote!(pub);
+    }
+    // helper attributes are only valid on the input of the derive
+    let mut declared_fields = fields.clone();
+    for field in &mut declared_fields {
+        field.attrs.retain(|attr| !["nested_soa", "soa", "soa_field_attr"].iter().any(|name| attr.path().is_ident(name)));
+    }
+    let semicolon = match fields {
+        Fields::Unnamed(_) => quote! { ; },
+        _ => quote! {},
+    };
+
+    // forward the attributes controlling the generated code, except for the
+    // custom names which only apply to the enum itself
+    let attrs = input.attrs.iter().filter(|attr| {
+        attr.path().is_ident("soa_derive") || attr.path().is_ident("soa_attr")
+    });
+    let crate_path = crate_path.to_token_stream().to_string();
+
+    let mut generated = quote! {
+        /// Fields of the
+        #[doc = #doc_url]
+        /// variant, used to store it with struct of array layout.
+        #visibility struct #struct_name #declared_fields #semicolon
+    };
+
+    let ast = syn::parse2(quote! {
+        #(#attrs)*
+        #[soa(crate = #crate_path)]
+        #visibility struct #struct_name #fields #semicolon
+    }).expect("generated invalid variant struct");
+    generated.append_all(crate::derive_struct(ast)?);
+    return Ok(generated);
+}
+
+/// Check that the types generated for each variant do not have the same name
+/// as the types generated for the enum itself or for a previous variant, *e.g.*
+/// a variant called `Kind` would create a `ShapeKind` struct conflicting with
+/// the `ShapeKind` enum.
+fn check_generated_names(name: &Ident, data: &DataEnum, attrs: &ExtraAttributes, errors: &mut Errors) {
+    let enum_types = [
+        names::kind_name(name),
+        attrs.names.vec.clone(),
+        attrs.names.ref_.clone(),
+        attrs.names.ref_mut.clone(),
+        attrs.names.iter.clone(),
+    ];
+
+    let variants_types = data.variants.iter()
+        .filter(|variant| !variant.fields.is_empty())
+        .map(|variant| {
+            let struct_name = names::variant_struct_name(name, &variant.ident);
+            let mut types = names::Names::new(&struct_name).all().into_iter().cloned().collect::<Vec<_>>();
+            types.insert(0, struct_name);
+            (variant, types)
+        })
+        .collect::<Vec<_>>();
+
+    for (i, (variant, types)) in variants_types.iter().enumerate() {
+        let previous = variants_types[..i].iter().flat_map(|(_, types)| types);
+        let existing = enum_types.iter().chain(previous).collect::<Vec<_>>();
+        if let Some(conflict) = types.iter().find(|ty| existing.contains(ty)) {
+            errors.push(syn::Error::new_spanned(
+                &variant.ident,
+                format!(
+                    "#[derive(StructOfArray)] can not be used on an enum with a variant named {}, the generated type {} would conflict with another generated type",
+                    variant.ident, conflict,
+                ),
+            ));
+        }
+    }
+}
+
+pub fn derive(input: &DeriveInput, data: &DataEnum) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let visibility = &input.vis;
+    let mut errors = Errors::default();
+
+    if data.variants.is_empty() {
+        errors.push(syn::Error::new_spanned(name, "#[derive(StructOfArray)] does not support empty enums"));
+    } else if data.variants.iter().all(|variant| variant.fields.is_empty()) {
+        errors.push(syn::Error::new_spanned(name, "#[derive(StructOfArray)] requires at least one enum variant with fields"));
+    }
+
+    if !input.generics.params.is_empty() {
+        errors.push(syn::Error::new_spanned(&input.generics, "#[derive(StructOfArray)] does not support generic enums"));
+    }
+
+    for variant in &data.variants {
+        let field_name = names::variant_field_name(&variant.ident);
+        if RESERVED_FIELDS.iter().any(|reserved| field_name == reserved) {
+            errors.push(syn::Error::new_spanned(
+                &variant.ident,
+                format!("#[derive(StructOfArray)] can not be used on an enum with a variant named {}", variant.ident),
+            ));
+        }
+    }
+
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("soa")) {
+        // syntax errors are reported by `ExtraAttributes::parse` below
+        let _ = attr.parse_nested_meta(|meta| {
+            if let Some(option) = STRUCT_ONLY_OPTIONS.iter().find(|option| meta.path.is_ident(option)) {
+                errors.push(meta.error(format!(
+                    "#[soa({})] can not be used on enums, only vec, ref, ref_mut, iter and crate are supported", option
+                )));
+            }
+            if meta.input.peek(syn::Token![=]) {
+                meta.value()?.parse::<syn::Expr>()?;
+            }
+            Ok(())
+        });
+    }
+
+    let attrs = ExtraAttributes::parse(name, &input.attrs);
+    match &attrs {
+        Ok(attrs) => check_generated_names(name, data, attrs, &mut errors),
+        Err(error) => errors.push(error.clone()),
+    }
+    errors.finish()?;
+    let attrs = attrs.expect("errors are reported above");
+
+    let vec_attrs = &attrs.vec;
+    let ref_attrs = &attrs.ref_;
+    let ref_mut_attrs = &attrs.ref_mut;
+
+    let kind_name = names::kind_name(name);
+    let vec_name = &attrs.names.vec;
+    let ref_name = &attrs.names.ref_;
+    let ref_mut_name = &attrs.names.ref_mut;
+    let iter_name = &attrs.names.iter;
+    let krate = &attrs.crate_path;
+
+    let vec_name_str = format!("Vec<{}>", name);
+    let doc_url = format!("[`{0}`](enum.{0}.html)", name);
+    let vec_doc_url = format!("[`{0}`](struct.{0}.html)", vec_name);
+    let ref_doc_url = format!("[`{0}`](enum.{0}.html)", ref_name);
+
+    let variants = data.variants.iter().map(|variant| EnumVariant::new(name, variant)).collect::<Vec<_>>();
+    let stored = variants.iter().filter(|variant| variant.struct_name.is_some()).collect::<Vec<_>>();
+
+    let variants_idents = variants.iter().map(|variant| &variant.variant.ident).collect::<Vec<_>>();
+    let variants_patterns = variants.iter().map(|variant| variant.pattern(name)).collect::<Vec<_>>();
+
+    let stored_idents = stored.iter().map(|variant| &variant.variant.ident).collect::<Vec<_>>();
+    let stored_fields = stored.iter().map(|variant| &variant.field_name).collect::<Vec<_>>();
+    let stored_vec_types = stored.iter()
+        .map(|variant| names::vec_name(variant.struct_name.as_ref().expect("stored variant")))
+        .collect::<Vec<_>>();
+    let stored_fields_docs = stored.iter()
+        .map(|variant| format!("storage for the `{}` variant", variant.variant.ident))
+        .collect::<Vec<_>>();
+
+    let fields_types = data.variants.iter()
+        .flat_map(|variant| variant.fields.iter().map(|field| &field.ty))
+        .collect::<Vec<_>>();
+
+    let ref_variants = variants.iter().map(|variant| {
+        let ident = &variant.variant.ident;
+        if let Some(struct_name) = &variant.struct_name {
+            let ref_type = names::ref_name(struct_name);
+            quote! { #ident(#ref_type<'a>) }
+        } else {
+            quote! { #ident }
+        }
+    }).collect::<Vec<_>>();
+
+    let ref_mut_variants = variants.iter().map(|variant| {
+        let ident = &variant.variant.ident;
+        if let Some(struct_name) = &variant.struct_name {
+            let ref_mut_type = names::ref_mut_name(struct_name);
+            quote! { #ident(#ref_mut_type<'a>) }
+        } else {
+            quote! { #ident }
+        }
+    }).collect::<Vec<_>>();
+
+    let push = variants.iter().map(|variant| {
+        let ident = &variant.variant.ident;
+        let fields_names = &variant.fields_names;
+        let fields_names_hygienic = &variant.fields_names_hygienic;
+        if let Some(struct_name) = &variant.struct_name {
+            let field = &variant.field_name;
+            quote! {
+                self.indices.push(self.#field.len());
+                self.kinds.push(#kind_name::#ident);
+                self.#field.push(#struct_name { #(#fields_names: #fields_names_hygienic),* });
+            }
+        } else {
+            quote! {
+                self.indices.push(0);
+                self.kinds.push(#kind_name::#ident);
+            }
+        }
+    }).collect::<Vec<_>>();
+
+    let pop = variants.iter().map(|variant| {
+        let ident = &variant.variant.ident;
+        let fields_names = &variant.fields_names;
+        let fields_names_hygienic = &variant.fields_names_hygienic;
+        if let Some(struct_name) = &variant.struct_name {
+            let field = &variant.field_name;
+            quote! {
+                let #struct_name { #(#fields_names: #fields_names_hygienic),* } = self.#field.pop().expect("missing variant storage");
+                #name::#ident { #(#fields_names: #fields_names_hygienic),* }
+            }
+        } else {
+            quote! { #name::#ident {} }
+        }
+    }).collect::<Vec<_>>();
+
+    let to_owned = variants.iter().map(|variant| {
+        let ident = &variant.variant.ident;
+        let fields_names = &variant.fields_names;
+        let fields_names_hygienic = &variant.fields_names_hygienic;
+        if let Some(struct_name) = &variant.struct_name {
+            quote! {
+                #ref_name::#ident(value) => {
+                    let #struct_name { #(#fields_names: #fields_names_hygienic),* } = value.to_owned();
+                    #name::#ident { #(#fields_names: #fields_names_hygienic),* }
+                }
+            }
+        } else {
+            quote! { #ref_name::#ident => #name::#ident {} }
+        }
+    }).collect::<Vec<_>>();
+
+    let get = variants.iter().map(|variant| {
+        let ident = &variant.variant.ident;
+        if variant.struct_name.is_some() {
+            let field = &variant.field_name;
+            quote! { #kind_name::#ident => #ref_name::#ident(self.#field.index(self.indices[index])) }
+        } else {
+            quote! { #kind_name::#ident => #ref_name::#ident }
+        }
+    }).collect::<Vec<_>>();
+
+    let get_mut = variants.iter().map(|variant| {
+        let ident = &variant.variant.ident;
+        if variant.struct_name.is_some() {
+            let field = &variant.field_name;
+            quote! { #kind_name::#ident => #ref_mut_name::#ident(self.#field.index_mut(self.indices[index])) }
+        } else {
+            quote! { #kind_name::#ident => #ref_mut_name::#ident }
+        }
+    }).collect::<Vec<_>>();
+
+    let mut generated = TokenStream::new();
+    for variant in &stored {
+        generated.append_all(derive_variant_struct(input, variant, &attrs.crate_path)?);
+    }
+
+    generated.append_all(quote! {
+        /// The kind of variant of a
+        #[doc = #doc_url]
+        /// , used as the discriminant of
+        #[doc = #vec_doc_url]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #visibility enum #kind_name {
+            #(
+                #[allow(missing_docs)]
+                #variants_idents,
+            )*
+        }
+
+        /// An analog to `
+        #[doc = #vec_name_str]
+        /// ` with Struct of Array (SoA) layout. Each variant is stored in a
+        /// separate struct of arrays, together with the kind and the position
+        /// inside this storage of all elements.
+        #[allow(dead_code)]
+        #(#[#vec_attrs])*
+        #visibility struct #vec_name {
+            /// kind of variant of all elements in this vector
+            pub kinds: Vec<#kind_name>,
+            /// position of all elements in the storage for their variant
+            pub indices: Vec<usize>,
+            #(
+                #[doc = #stored_fields_docs]
+                pub #stored_fields: #stored_vec_types,
+            )*
+        }
+
+        impl Default for #vec_name {
+            fn default() -> Self {
+                #vec_name {
+                    kinds: Vec::new(),
+                    indices: Vec::new(),
+                    #( #stored_fields: Default::default(), )*
+                }
+            }
+        }
+
+        /// A reference to a
+        #[doc = #doc_url]
+        /// inside a
+        #[doc = #vec_doc_url]
+        #(#[#ref_attrs])*
+        #visibility enum #ref_name<'a> {
+            #(
+                #[allow(missing_docs)]
+                #ref_variants,
+            )*
+        }
+
+        impl<'a> Copy for #ref_name<'a> {}
+
+        impl<'a> Clone for #ref_name<'a> {
+            fn clone(&self) -> Self {
+                *self
+            }
+        }
+
+        /// A mutable reference to a
+        #[doc = #doc_url]
+        /// inside a
+        #[doc = #vec_doc_url]
+        #(#[#ref_mut_attrs])*
+        #visibility enum #ref_mut_name<'a> {
+            #(
+                #[allow(missing_docs)]
+                #ref_mut_variants,
+            )*
+        }
+
+        impl<'a> #ref_name<'a> {
+            /// Convert a reference to
+            #[doc = #doc_url]
+            /// into an owned value. This is only available if all fields
+            /// implement `Clone`.
+            pub fn to_owned(&self) -> #name
+                // only expose to_owned if all fields are Clone
+                // https://github.com/rust-lang/rust/issues/48214#issuecomment-1150463333
+                where #( for<'b> #fields_types: Clone, )*
+            {
+                match *self {
+                    #(#to_owned,)*
+                }
+            }
+        }
+
+        #[allow(dead_code)]
+        impl #vec_name {
+            /// Similar to [`
+            #[doc = #vec_name_str]
+            /// ::new()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.new)
+            pub fn new() -> #vec_name {
+                Default::default()
+            }
+
+            /// Similar to [`
+            #[doc = #vec_name_str]
+            /// ::len()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.len).
+            pub fn len(&self) -> usize {
+                debug_assert_eq!(self.kinds.len(), self.indices.len());
+                self.kinds.len()
+            }
+
+            /// Similar to [`
+            #[doc = #vec_name_str]
+            /// ::is_empty()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.is_empty).
+            pub fn is_empty(&self) -> bool {
+                self.kinds.is_empty()
+            }
+
+            /// Similar to [`
+            #[doc = #vec_name_str]
+            /// ::clear()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.clear).
+            pub fn clear(&mut self) {
+                self.kinds.clear();
+                self.indices.clear();
+                #(self.#stored_fields.clear();)*
+            }
+
+            /// Similar to [`
+            #[doc = #vec_name_str]
+            /// ::push()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.push).
+            pub fn push(&mut self, value: #name) {
+                match value {
+                    #(#variants_patterns => { #push })*
+                }
+            }
+
+            /// Similar to [`
+            #[doc = #vec_name_str]
+            /// ::pop()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.pop).
+            pub fn pop(&mut self) -> Option<#name> {
+                let kind = self.kinds.pop()?;
+                self.indices.pop();
+                let value = match kind {
+                    #(#kind_name::#variants_idents => { #pop })*
+                };
+                Some(value)
+            }
+
+            /// Similar to [`
+            #[doc = #vec_name_str]
+            /// ::get()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.get),
+            /// for a single index.
+            pub fn get(&self, index: usize) -> Option<#ref_name<'_>> {
+                let kind = self.kinds.get(index)?;
+                let value = match *kind {
+                    #(#get,)*
+                };
+                Some(value)
+            }
+
+            /// Similar to [`
+            #[doc = #vec_name_str]
+            /// ::get_mut()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.get_mut),
+            /// for a single index.
+            pub fn get_mut(&mut self, index: usize) -> Option<#ref_mut_name<'_>> {
+                let kind = *self.kinds.get(index)?;
+                let value = match kind {
+                    #(#get_mut,)*
+                };
+                Some(value)
+            }
+
+            /// Get an iterator over the
+            #[doc = #ref_doc_url]
+            /// in this vector
+            pub fn iter(&self) -> #iter_name<'_> {
+                #iter_name {
+                    vec: self,
+                    range: 0..self.len(),
+                }
+            }
+
+            /// Similar to [`
+            #[doc = #vec_name_str]
+            /// ::retain()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.retain).
+            pub fn retain<F>(&mut self, mut f: F) where F: FnMut(#ref_name<'_>) -> bool {
+                let keep = self.iter().map(|value| f(value)).collect::<Vec<_>>();
+
+                #(
+                    let mut keep_variant = keep.iter()
+                        .zip(&self.kinds)
+                        .filter(|(_, kind)| **kind == #kind_name::#stored_idents)
+                        .map(|(&keep, _)| keep);
+                    self.#stored_fields.retain(|_| keep_variant.next().expect("missing variant storage"));
+                )*
+
+                let mut keep = keep.into_iter();
+                self.kinds.retain(|_| keep.next().expect("missing kind"));
+
+                let mut counts = [0; #kind_name::COUNT];
+                self.indices.clear();
+                for &kind in &self.kinds {
+                    self.indices.push(counts[kind as usize]);
+                    counts[kind as usize] += 1;
+                }
+            }
+        }
+
+        impl #kind_name {
+            /// Number of variants in
+            #[doc = #doc_url]
+            const COUNT: usize = [#(#kind_name::#variants_idents),*].len();
+        }
+
+        /// Iterator over
+        #[doc = #doc_url]
+        /// inside a
+        #[doc = #vec_doc_url]
+        #[allow(missing_debug_implementations)]
+        #visibility struct #iter_name<'a> {
+            vec: &'a #vec_name,
+            range: ::std::ops::Range<usize>,
+        }
+
+        impl<'a> Iterator for #iter_name<'a> {
+            type Item = #ref_name<'a>;
+
+            #[inline]
+            fn next(&mut self) -> Option<#ref_name<'a>> {
+                let index = self.range.next()?;
+                self.vec.get(index)
+            }
+
+            #[inline]
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.range.size_hint()
+            }
+        }
+
+        impl<'a> DoubleEndedIterator for #iter_name<'a> {
+            #[inline]
+            fn next_back(&mut self) -> Option<#ref_name<'a>> {
+                let index = self.range.next_back()?;
+                self.vec.get(index)
+            }
+        }
+
+        impl<'a> ExactSizeIterator for #iter_name<'a> {
+            fn len(&self) -> usize {
+                self.range.len()
+            }
+        }
+
+        impl<'a> IntoIterator for &'a #vec_name {
+            type Item = #ref_name<'a>;
+            type IntoIter = #iter_name<'a>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.iter()
+            }
+        }
+
+        impl Extend<#name> for #vec_name {
+            fn extend<I: IntoIterator<Item = #name>>(&mut self, iter: I) {
+                for value in iter {
+                    self.push(value);
+                }
+            }
+        }
+
+        impl std::iter::FromIterator<#name> for #vec_name {
+            fn from_iter<I: IntoIterator<Item = #name>>(iter: I) -> Self {
+                let mut result = #vec_name::new();
+                result.extend(iter);
+                result
+            }
+        }
+
+        impl #krate::StructOfArray for #name {
+            type Type = #vec_name;
+        }
+    });
+
+    return Ok(generated);
+}
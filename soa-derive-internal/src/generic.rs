@@ -2,22 +2,27 @@ use proc_macro2::TokenStream;
 use quote::quote;
 
 use crate::input::Input;
-use crate::names;
 
 
 pub fn derive_slice(input: &Input) -> TokenStream {
+    let krate = &input.attrs.crate_path;
     let name = &input.name;
-    let slice_name = names::slice_name(name);
-    let ref_name = names::ref_name(&input.name);
-    let ptr_name = names::ptr_name(&input.name);
-    let iter_name = names::iter_name(name);
+    let slice_name = &input.attrs.names.slice;
+    let ref_name = &input.attrs.names.ref_;
+    let ptr_name = &input.attrs.names.ptr;
+    let iter_name = &input.attrs.names.iter;
+
+    let generics = input.generics_with_lifetime("'a");
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let ty_generics_t = input.ty_generics_with_lifetime("'t");
+    let (_, owned_ty_generics, _) = input.generics.split_for_impl();
 
     let generated = quote! {
-        impl<'a> ::soa_derive::SoASlice<#name> for #slice_name<'a> {
-            type Ref<'t>  = #ref_name<'t> where Self: 't, 'a: 't;
-            type Slice<'t> = #slice_name<'t> where Self: 't, 'a: 't;
-            type Iter<'t> = #iter_name<'t> where Self: 't, 'a: 't;
-            type Ptr = #ptr_name;
+        impl #impl_generics #krate::SoASlice<#name #owned_ty_generics> for #slice_name #ty_generics #where_clause {
+            type Ref<'t>  = #ref_name #ty_generics_t where Self: 't, 'a: 't;
+            type Slice<'t> = #slice_name #ty_generics_t where Self: 't, 'a: 't;
+            type Iter<'t> = #iter_name #ty_generics_t where Self: 't, 'a: 't;
+            type Ptr = #ptr_name #owned_ty_generics;
 
             fn len(&self) -> usize {
                 self.len()
@@ -67,28 +72,34 @@ pub fn derive_slice(input: &Input) -> TokenStream {
 }
 
 pub fn derive_slice_mut(input: &Input) -> TokenStream {
+    let krate = &input.attrs.crate_path;
     let name = &input.name;
-    let slice_name = names::slice_name(name);
-    let slice_mut_name = names::slice_mut_name(&input.name);
-    let ref_name = names::ref_name(&input.name);
-    let ref_mut_name = names::ref_mut_name(&input.name);
-    let ptr_name = names::ptr_name(&input.name);
-    let ptr_mut_name = names::ptr_mut_name(&input.name);
-    let iter_name = names::iter_name(name);
-    let iter_mut_name = names::iter_mut_name(name);
+    let slice_name = &input.attrs.names.slice;
+    let slice_mut_name = &input.attrs.names.slice_mut;
+    let ref_name = &input.attrs.names.ref_;
+    let ref_mut_name = &input.attrs.names.ref_mut;
+    let ptr_name = &input.attrs.names.ptr;
+    let ptr_mut_name = &input.attrs.names.ptr_mut;
+    let iter_name = &input.attrs.names.iter;
+    let iter_mut_name = &input.attrs.names.iter_mut;
+
+    let generics = input.generics_with_lifetime("'a");
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let ty_generics_t = input.ty_generics_with_lifetime("'t");
+    let (_, owned_ty_generics, _) = input.generics.split_for_impl();
 
     let generated = quote! {
 
-        impl<'a> ::soa_derive::SoASliceMut<#name> for #slice_mut_name<'a> {
-            type Ref<'t>  = #ref_name<'t> where Self: 't;
-            type Slice<'t> = #slice_name<'t> where Self: 't;
-            type Iter<'t> = #iter_name<'t> where Self: 't;
-            type Ptr = #ptr_name;
+        impl #impl_generics #krate::SoASliceMut<#name #owned_ty_generics> for #slice_mut_name #ty_generics #where_clause {
+            type Ref<'t>  = #ref_name #ty_generics_t where Self: 't;
+            type Slice<'t> = #slice_name #ty_generics_t where Self: 't;
+            type Iter<'t> = #iter_name #ty_generics_t where Self: 't;
+            type Ptr = #ptr_name #owned_ty_generics;
 
-            type RefMut<'t> = #ref_mut_name<'t> where Self: 't;
-            type SliceMut<'t> = #slice_mut_name<'t> where Self: 't;
-            type IterMut<'t> = #iter_mut_name<'t> where Self: 't;
-            type PtrMut = #ptr_mut_name;
+            type RefMut<'t> = #ref_mut_name #ty_generics_t where Self: 't;
+            type SliceMut<'t> = #slice_mut_name #ty_generics_t where Self: 't;
+            type IterMut<'t> = #iter_mut_name #ty_generics_t where Self: 't;
+            type PtrMut = #ptr_mut_name #owned_ty_generics;
 
             fn len(&self) -> usize {
                 self.len()
@@ -159,7 +170,7 @@ pub fn derive_slice_mut(input: &Input) -> TokenStream {
             }
 
             fn apply_index(&mut self, indices: &[usize]) {
-                self.__private_apply_permutation(&mut ::soa_derive::Permutation::oneline(indices).inverse());
+                self.__private_apply_permutation(&mut #krate::Permutation::oneline(indices).inverse());
             }
 
             fn as_ptr(&self) -> Self::Ptr {
@@ -176,29 +187,36 @@ pub fn derive_slice_mut(input: &Input) -> TokenStream {
 }
 
 pub fn derive_vec(input: &Input) -> TokenStream {
+    let krate = &input.attrs.crate_path;
     let name = &input.name;
-    let vec_name = names::vec_name(&input.name);
-    let slice_name = names::slice_name(name);
-    let slice_mut_name = names::slice_mut_name(&input.name);
-    let ref_name = names::ref_name(&input.name);
-    let ref_mut_name = names::ref_mut_name(&input.name);
-    let ptr_name = names::ptr_name(&input.name);
-    let ptr_mut_name = names::ptr_mut_name(&input.name);
-    let iter_name = names::iter_name(name);
-    let iter_mut_name = names::iter_mut_name(name);
+    let vec_name = &input.attrs.names.vec;
+    let slice_name = &input.attrs.names.slice;
+    let slice_mut_name = &input.attrs.names.slice_mut;
+    let ref_name = &input.attrs.names.ref_;
+    let ref_mut_name = &input.attrs.names.ref_mut;
+    let ptr_name = &input.attrs.names.ptr;
+    let ptr_mut_name = &input.attrs.names.ptr_mut;
+    let iter_name = &input.attrs.names.iter;
+    let iter_mut_name = &input.attrs.names.iter_mut;
+    let drain_name = &input.attrs.names.drain;
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let ty_generics_t = input.ty_generics_with_lifetime("'t");
 
     let generated = quote! {
 
-        impl ::soa_derive::SoAVec<#name> for #vec_name {
-            type Ref<'t> = #ref_name<'t>;
-            type Slice<'t> = #slice_name<'t>;
-            type Iter<'t> = #iter_name<'t>;
-            type Ptr = #ptr_name;
+        impl #impl_generics #krate::SoAVec<#name #ty_generics> for #vec_name #ty_generics #where_clause {
+            type Ref<'t> = #ref_name #ty_generics_t where Self: 't;
+            type Slice<'t> = #slice_name #ty_generics_t where Self: 't;
+            type Iter<'t> = #iter_name #ty_generics_t where Self: 't;
+            type Ptr = #ptr_name #ty_generics;
+
+            type RefMut<'t> = #ref_mut_name #ty_generics_t where Self: 't;
+            type SliceMut<'t> = #slice_mut_name #ty_generics_t where Self: 't;
+            type IterMut<'t> = #iter_mut_name #ty_generics_t where Self: 't;
+            type PtrMut = #ptr_mut_name #ty_generics;
 
-            type RefMut<'t> = #ref_mut_name<'t>;
-            type SliceMut<'t> = #slice_mut_name<'t>;
-            type IterMut<'t> = #iter_mut_name<'t>;
-            type PtrMut = #ptr_mut_name;
+            type Drain<'t> = #drain_name #ty_generics_t where Self: 't;
 
             fn len(&self) -> usize {
                 self.len()
@@ -269,7 +287,7 @@ pub fn derive_vec(input: &Input) -> TokenStream {
             }
 
             fn apply_index(&mut self, indices: &[usize]) {
-                use ::soa_derive::SoASliceMut;
+                use #krate::SoASliceMut;
                 self.as_mut_slice().apply_index(indices);
             }
 
@@ -301,27 +319,27 @@ pub fn derive_vec(input: &Input) -> TokenStream {
                 self.truncate(len);
             }
 
-            fn push(&mut self, value: #name) {
+            fn push(&mut self, value: #name #ty_generics) {
                 self.push(value);
             }
 
-            fn swap_remove(&mut self, index: usize) -> #name {
+            fn swap_remove(&mut self, index: usize) -> #name #ty_generics {
                 self.swap_remove(index)
             }
 
-            fn insert(&mut self, index: usize, element: #name) {
+            fn insert(&mut self, index: usize, element: #name #ty_generics) {
                 self.insert(index, element);
             }
 
-            fn replace(&mut self, index: usize, element: #name) -> #name {
+            fn replace(&mut self, index: usize, element: #name #ty_generics) -> #name #ty_generics {
                 self.replace(index, element)
             }
 
-            fn remove(&mut self, index: usize) -> #name {
+            fn remove(&mut self, index: usize) -> #name #ty_generics {
                 self.remove(index)
             }
 
-            fn pop(&mut self) -> Option<#name> {
+            fn pop(&mut self) -> Option<#name #ty_generics> {
                 self.pop()
             }
 
@@ -337,6 +355,23 @@ pub fn derive_vec(input: &Input) -> TokenStream {
                 self.split_off(at)
             }
 
+            fn drain(&mut self, range: impl core::ops::RangeBounds<usize>) -> Self::Drain<'_> {
+                self.drain(range)
+            }
+
+            fn swap(&mut self, a: usize, b: usize) {
+                self.as_mut_slice().swap(a, b);
+            }
+
+            fn __private_get_pair_mut(&mut self, a: usize, b: usize) -> (Self::RefMut<'_>, Self::RefMut<'_>) {
+                assert!(a < b, "expected {} < {} in __private_get_pair_mut", a, b);
+                let (head, tail) = self.as_mut_slice().split_at_mut(b);
+                let (_, head) = head.split_at_mut(a);
+                let (first, _) = head.split_first_mut().expect("a < b");
+                let (second, _) = tail.split_first_mut().expect("b < len");
+                (first, second)
+            }
+
             fn as_ptr(&self) -> Self::Ptr {
                 self.as_ptr()
             }
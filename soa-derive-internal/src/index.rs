@@ -3,18 +3,21 @@ use proc_macro2::TokenStream;
 use quote::quote;
 
 use crate::input::Input;
-use crate::names;
 
 pub fn derive(input: &Input) -> TokenStream {
-    let vec_name = names::vec_name(&input.name);
-    let slice_name = names::slice_name(&input.name);
-    let slice_mut_name = names::slice_mut_name(&input.name);
-    let ref_name = names::ref_name(&input.name);
-    let ref_mut_name = names::ref_mut_name(&input.name);
-
-    let fields_names = &input.fields.iter()
-        .map(|field| field.ident.clone().unwrap())
-        .collect::<Vec<_>>();
+    let krate = &input.attrs.crate_path;
+    let vec_name = &input.attrs.names.vec;
+    let slice_name = &input.attrs.names.slice;
+    let slice_mut_name = &input.attrs.names.slice_mut;
+    let ref_name = &input.attrs.names.ref_;
+    let ref_mut_name = &input.attrs.names.ref_mut;
+
+    let generics = input.generics_with_lifetime("'a");
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let (_, owned_ty_generics, _) = input.generics.split_for_impl();
+
+    let fields_names = &input.fields_names();
+    let marker = input.marker_init();
     let first_field_name = &fields_names[0];
 
 
@@ -40,11 +43,11 @@ pub fn derive(input: &Input) -> TokenStream {
 
     quote!{
         // usize
-        impl<'a> ::soa_derive::SoAIndex<&'a #vec_name> for usize {
-            type RefOutput = #ref_name<'a>;
+        impl #impl_generics #krate::SoAIndex<&'a #vec_name #owned_ty_generics> for usize #where_clause {
+            type RefOutput = #ref_name #ty_generics;
 
             #[inline]
-            fn get(self, soa: &'a #vec_name) -> Option<Self::RefOutput> {
+            fn get(self, soa: &'a #vec_name #owned_ty_generics) -> Option<Self::RefOutput> {
                 if self < soa.len() {
                     Some(unsafe { self.get_unchecked(soa) })
                 } else {
@@ -53,21 +56,21 @@ pub fn derive(input: &Input) -> TokenStream {
             }
 
             #[inline]
-            unsafe fn get_unchecked(self, soa: &'a #vec_name) -> Self::RefOutput {
+            unsafe fn get_unchecked(self, soa: &'a #vec_name #owned_ty_generics) -> Self::RefOutput {
                 self.get_unchecked(soa.as_slice())
             }
 
             #[inline]
-            fn index(self, soa: &'a #vec_name) -> Self::RefOutput {
+            fn index(self, soa: &'a #vec_name #owned_ty_generics) -> Self::RefOutput {
                 self.index(soa.as_slice())
             }
         }
 
-        impl<'a> ::soa_derive::SoAIndexMut<&'a mut #vec_name> for usize {
-            type MutOutput = #ref_mut_name<'a>;
+        impl #impl_generics #krate::SoAIndexMut<&'a mut #vec_name #owned_ty_generics> for usize #where_clause {
+            type MutOutput = #ref_mut_name #ty_generics;
 
             #[inline]
-            fn get_mut(self, soa: &'a mut #vec_name) -> Option<Self::MutOutput> {
+            fn get_mut(self, soa: &'a mut #vec_name #owned_ty_generics) -> Option<Self::MutOutput> {
                 if self < soa.len() {
                     Some(unsafe { self.get_unchecked_mut(soa) })
                 } else {
@@ -76,12 +79,12 @@ pub fn derive(input: &Input) -> TokenStream {
             }
 
             #[inline]
-            unsafe fn get_unchecked_mut(self, soa: &'a mut #vec_name) -> Self::MutOutput {
+            unsafe fn get_unchecked_mut(self, soa: &'a mut #vec_name #owned_ty_generics) -> Self::MutOutput {
                 self.get_unchecked_mut(soa.as_mut_slice())
             }
 
             #[inline]
-            fn index_mut(self, soa: &'a mut #vec_name) -> Self::MutOutput {
+            fn index_mut(self, soa: &'a mut #vec_name #owned_ty_generics) -> Self::MutOutput {
                 self.index_mut(soa.as_mut_slice())
             }
         }
@@ -89,11 +92,11 @@ pub fn derive(input: &Input) -> TokenStream {
 
 
         // Range<usize>
-        impl<'a> ::soa_derive::SoAIndex<&'a #vec_name> for ::std::ops::Range<usize> {
-            type RefOutput = #slice_name<'a>;
+        impl #impl_generics #krate::SoAIndex<&'a #vec_name #owned_ty_generics> for ::std::ops::Range<usize> #where_clause {
+            type RefOutput = #slice_name #ty_generics;
 
             #[inline]
-            fn get(self, soa: &'a #vec_name) -> Option<Self::RefOutput> {
+            fn get(self, soa: &'a #vec_name #owned_ty_generics) -> Option<Self::RefOutput> {
                 if self.start <= self.end && self.end <= soa.len() {
                     unsafe { Some(self.get_unchecked(soa)) }
                 } else {
@@ -102,21 +105,21 @@ pub fn derive(input: &Input) -> TokenStream {
             }
 
             #[inline]
-            unsafe fn get_unchecked(self, soa: &'a #vec_name) -> Self::RefOutput {
+            unsafe fn get_unchecked(self, soa: &'a #vec_name #owned_ty_generics) -> Self::RefOutput {
                 self.get_unchecked(soa.as_slice())
             }
 
             #[inline]
-            fn index(self, soa: &'a #vec_name) -> Self::RefOutput {
+            fn index(self, soa: &'a #vec_name #owned_ty_generics) -> Self::RefOutput {
                 self.index(soa.as_slice())
             }
         }
 
-        impl<'a> ::soa_derive::SoAIndexMut<&'a mut #vec_name> for ::std::ops::Range<usize> {
-            type MutOutput = #slice_mut_name<'a>;
+        impl #impl_generics #krate::SoAIndexMut<&'a mut #vec_name #owned_ty_generics> for ::std::ops::Range<usize> #where_clause {
+            type MutOutput = #slice_mut_name #ty_generics;
 
             #[inline]
-            fn get_mut(self, soa: &'a mut #vec_name) -> Option<Self::MutOutput> {
+            fn get_mut(self, soa: &'a mut #vec_name #owned_ty_generics) -> Option<Self::MutOutput> {
                 if self.start <= self.end && self.end <= soa.len() {
                     unsafe { Some(self.get_unchecked_mut(soa)) }
                 } else {
@@ -125,139 +128,139 @@ pub fn derive(input: &Input) -> TokenStream {
             }
 
             #[inline]
-            unsafe fn get_unchecked_mut(self, soa: &'a mut #vec_name) -> Self::MutOutput {
+            unsafe fn get_unchecked_mut(self, soa: &'a mut #vec_name #owned_ty_generics) -> Self::MutOutput {
                 self.get_unchecked_mut(soa.as_mut_slice())
             }
 
             #[inline]
-            fn index_mut(self, soa: &'a mut #vec_name) -> Self::MutOutput {
+            fn index_mut(self, soa: &'a mut #vec_name #owned_ty_generics) -> Self::MutOutput {
                 self.index_mut(soa.as_mut_slice())
             }
         }
 
         // RangeTo<usize>
-        impl<'a> ::soa_derive::SoAIndex<&'a #vec_name> for ::std::ops::RangeTo<usize> {
-            type RefOutput = #slice_name<'a>;
+        impl #impl_generics #krate::SoAIndex<&'a #vec_name #owned_ty_generics> for ::std::ops::RangeTo<usize> #where_clause {
+            type RefOutput = #slice_name #ty_generics;
 
             #[inline]
-            fn get(self, soa: &'a #vec_name) -> Option<Self::RefOutput> {
+            fn get(self, soa: &'a #vec_name #owned_ty_generics) -> Option<Self::RefOutput> {
                 (0..self.end).get(soa)
             }
 
             #[inline]
-            unsafe fn get_unchecked(self, soa: &'a #vec_name) -> Self::RefOutput {
+            unsafe fn get_unchecked(self, soa: &'a #vec_name #owned_ty_generics) -> Self::RefOutput {
                 (0..self.end).get_unchecked(soa)
             }
 
             #[inline]
-            fn index(self, soa: &'a #vec_name) -> Self::RefOutput {
+            fn index(self, soa: &'a #vec_name #owned_ty_generics) -> Self::RefOutput {
                 (0..self.end).index(soa)
             }
         }
 
-        impl<'a> ::soa_derive::SoAIndexMut<&'a mut #vec_name> for ::std::ops::RangeTo<usize> {
-            type MutOutput = #slice_mut_name<'a>;
+        impl #impl_generics #krate::SoAIndexMut<&'a mut #vec_name #owned_ty_generics> for ::std::ops::RangeTo<usize> #where_clause {
+            type MutOutput = #slice_mut_name #ty_generics;
 
             #[inline]
-            fn get_mut(self, soa: &'a mut #vec_name) -> Option<Self::MutOutput> {
+            fn get_mut(self, soa: &'a mut #vec_name #owned_ty_generics) -> Option<Self::MutOutput> {
                 (0..self.end).get_mut(soa)
             }
 
             #[inline]
-            unsafe fn get_unchecked_mut(self, soa: &'a mut #vec_name) -> Self::MutOutput {
+            unsafe fn get_unchecked_mut(self, soa: &'a mut #vec_name #owned_ty_generics) -> Self::MutOutput {
                 (0..self.end).get_unchecked_mut(soa)
             }
 
             #[inline]
-            fn index_mut(self, soa: &'a mut #vec_name) -> Self::MutOutput {
+            fn index_mut(self, soa: &'a mut #vec_name #owned_ty_generics) -> Self::MutOutput {
                 (0..self.end).index_mut(soa)
             }
         }
 
         // RangeFrom<usize>
-        impl<'a> ::soa_derive::SoAIndex<&'a #vec_name> for ::std::ops::RangeFrom<usize> {
-            type RefOutput = #slice_name<'a>;
+        impl #impl_generics #krate::SoAIndex<&'a #vec_name #owned_ty_generics> for ::std::ops::RangeFrom<usize> #where_clause {
+            type RefOutput = #slice_name #ty_generics;
 
             #[inline]
-            fn get(self, soa: &'a #vec_name) -> Option<Self::RefOutput> {
+            fn get(self, soa: &'a #vec_name #owned_ty_generics) -> Option<Self::RefOutput> {
                 (self.start..soa.len()).get(soa)
             }
 
             #[inline]
-            unsafe fn get_unchecked(self, soa: &'a #vec_name) -> Self::RefOutput {
+            unsafe fn get_unchecked(self, soa: &'a #vec_name #owned_ty_generics) -> Self::RefOutput {
                 (self.start..soa.len()).get_unchecked(soa)
             }
 
             #[inline]
-            fn index(self, soa: &'a #vec_name) -> Self::RefOutput {
+            fn index(self, soa: &'a #vec_name #owned_ty_generics) -> Self::RefOutput {
                 (self.start..soa.len()).index(soa)
             }
         }
 
-        impl<'a> ::soa_derive::SoAIndexMut<&'a mut #vec_name> for ::std::ops::RangeFrom<usize> {
-            type MutOutput = #slice_mut_name<'a>;
+        impl #impl_generics #krate::SoAIndexMut<&'a mut #vec_name #owned_ty_generics> for ::std::ops::RangeFrom<usize> #where_clause {
+            type MutOutput = #slice_mut_name #ty_generics;
 
             #[inline]
-            fn get_mut(self, soa: &'a mut #vec_name) -> Option<Self::MutOutput> {
+            fn get_mut(self, soa: &'a mut #vec_name #owned_ty_generics) -> Option<Self::MutOutput> {
                 (self.start..soa.len()).get_mut(soa)
             }
 
             #[inline]
-            unsafe fn get_unchecked_mut(self, soa: &'a mut #vec_name) -> Self::MutOutput {
+            unsafe fn get_unchecked_mut(self, soa: &'a mut #vec_name #owned_ty_generics) -> Self::MutOutput {
                 (self.start..soa.len()).get_unchecked_mut(soa)
             }
 
             #[inline]
-            fn index_mut(self, soa: &'a mut #vec_name) -> Self::MutOutput {
+            fn index_mut(self, soa: &'a mut #vec_name #owned_ty_generics) -> Self::MutOutput {
                 (self.start..soa.len()).index_mut(soa)
             }
         }
 
         // RangeFull
-        impl<'a> ::soa_derive::SoAIndex<&'a #vec_name> for ::std::ops::RangeFull {
-            type RefOutput = #slice_name<'a>;
+        impl #impl_generics #krate::SoAIndex<&'a #vec_name #owned_ty_generics> for ::std::ops::RangeFull #where_clause {
+            type RefOutput = #slice_name #ty_generics;
 
             #[inline]
-            fn get(self, soa: &'a #vec_name) -> Option<Self::RefOutput> {
+            fn get(self, soa: &'a #vec_name #owned_ty_generics) -> Option<Self::RefOutput> {
                 Some(soa.as_slice())
             }
 
             #[inline]
-            unsafe fn get_unchecked(self, soa: &'a #vec_name) -> Self::RefOutput {
+            unsafe fn get_unchecked(self, soa: &'a #vec_name #owned_ty_generics) -> Self::RefOutput {
                 soa.as_slice()
             }
 
             #[inline]
-            fn index(self, soa: &'a #vec_name) -> Self::RefOutput {
+            fn index(self, soa: &'a #vec_name #owned_ty_generics) -> Self::RefOutput {
                 soa.as_slice()
             }
         }
 
-        impl<'a> ::soa_derive::SoAIndexMut<&'a mut #vec_name> for ::std::ops::RangeFull {
-            type MutOutput = #slice_mut_name<'a>;
+        impl #impl_generics #krate::SoAIndexMut<&'a mut #vec_name #owned_ty_generics> for ::std::ops::RangeFull #where_clause {
+            type MutOutput = #slice_mut_name #ty_generics;
 
             #[inline]
-            fn get_mut(self, soa: &'a mut #vec_name) -> Option<Self::MutOutput> {
+            fn get_mut(self, soa: &'a mut #vec_name #owned_ty_generics) -> Option<Self::MutOutput> {
                 Some(soa.as_mut_slice())
             }
 
             #[inline]
-            unsafe fn get_unchecked_mut(self, soa: &'a mut #vec_name) -> Self::MutOutput {
+            unsafe fn get_unchecked_mut(self, soa: &'a mut #vec_name #owned_ty_generics) -> Self::MutOutput {
                 soa.as_mut_slice()
             }
 
             #[inline]
-            fn index_mut(self, soa: &'a mut #vec_name) -> Self::MutOutput {
+            fn index_mut(self, soa: &'a mut #vec_name #owned_ty_generics) -> Self::MutOutput {
                 soa.as_mut_slice()
             }
         }
 
         // RangeInclusive<usize>
-        impl<'a> ::soa_derive::SoAIndex<&'a #vec_name> for ::std::ops::RangeInclusive<usize> {
-            type RefOutput = #slice_name<'a>;
+        impl #impl_generics #krate::SoAIndex<&'a #vec_name #owned_ty_generics> for ::std::ops::RangeInclusive<usize> #where_clause {
+            type RefOutput = #slice_name #ty_generics;
 
             #[inline]
-            fn get(self, soa: &'a #vec_name) -> Option<Self::RefOutput> {
+            fn get(self, soa: &'a #vec_name #owned_ty_generics) -> Option<Self::RefOutput> {
                 if *self.end() == usize::MAX {
                     None
                 } else {
@@ -266,21 +269,21 @@ pub fn derive(input: &Input) -> TokenStream {
             }
 
             #[inline]
-            unsafe fn get_unchecked(self, soa: &'a #vec_name) -> Self::RefOutput {
+            unsafe fn get_unchecked(self, soa: &'a #vec_name #owned_ty_generics) -> Self::RefOutput {
                 (*self.start()..self.end() + 1).get_unchecked(soa)
             }
 
             #[inline]
-            fn index(self, soa: &'a #vec_name) -> Self::RefOutput {
+            fn index(self, soa: &'a #vec_name #owned_ty_generics) -> Self::RefOutput {
                 (*self.start()..self.end() + 1).index(soa)
             }
         }
 
-        impl<'a> ::soa_derive::SoAIndexMut<&'a mut #vec_name> for ::std::ops::RangeInclusive<usize> {
-            type MutOutput = #slice_mut_name<'a>;
+        impl #impl_generics #krate::SoAIndexMut<&'a mut #vec_name #owned_ty_generics> for ::std::ops::RangeInclusive<usize> #where_clause {
+            type MutOutput = #slice_mut_name #ty_generics;
 
             #[inline]
-            fn get_mut(self, soa: &'a mut #vec_name) -> Option<Self::MutOutput> {
+            fn get_mut(self, soa: &'a mut #vec_name #owned_ty_generics) -> Option<Self::MutOutput> {
                 if *self.end() == usize::MAX {
                     None
                 } else {
@@ -289,61 +292,61 @@ pub fn derive(input: &Input) -> TokenStream {
             }
 
             #[inline]
-            unsafe fn get_unchecked_mut(self, soa: &'a mut #vec_name) -> Self::MutOutput {
+            unsafe fn get_unchecked_mut(self, soa: &'a mut #vec_name #owned_ty_generics) -> Self::MutOutput {
                 (*self.start()..self.end() + 1).get_unchecked_mut(soa)
             }
 
             #[inline]
-            fn index_mut(self, soa: &'a mut #vec_name) -> Self::MutOutput {
+            fn index_mut(self, soa: &'a mut #vec_name #owned_ty_generics) -> Self::MutOutput {
                 (*self.start()..self.end() + 1).index_mut(soa)
             }
         }
 
         // RangeToInclusive<usize>
-        impl<'a> ::soa_derive::SoAIndex<&'a #vec_name> for ::std::ops::RangeToInclusive<usize> {
-            type RefOutput = #slice_name<'a>;
+        impl #impl_generics #krate::SoAIndex<&'a #vec_name #owned_ty_generics> for ::std::ops::RangeToInclusive<usize> #where_clause {
+            type RefOutput = #slice_name #ty_generics;
 
             #[inline]
-            fn get(self, soa: &'a #vec_name) -> Option<Self::RefOutput> {
+            fn get(self, soa: &'a #vec_name #owned_ty_generics) -> Option<Self::RefOutput> {
                 (0..=self.end).get(soa)
             }
 
             #[inline]
-            unsafe fn get_unchecked(self, soa: &'a #vec_name) -> Self::RefOutput {
+            unsafe fn get_unchecked(self, soa: &'a #vec_name #owned_ty_generics) -> Self::RefOutput {
                 (0..=self.end).get_unchecked(soa)
             }
 
             #[inline]
-            fn index(self, soa: &'a #vec_name) -> Self::RefOutput {
+            fn index(self, soa: &'a #vec_name #owned_ty_generics) -> Self::RefOutput {
                 (0..=self.end).index(soa)
             }
         }
 
-        impl<'a> ::soa_derive::SoAIndexMut<&'a mut #vec_name> for ::std::ops::RangeToInclusive<usize> {
-            type MutOutput = #slice_mut_name<'a>;
+        impl #impl_generics #krate::SoAIndexMut<&'a mut #vec_name #owned_ty_generics> for ::std::ops::RangeToInclusive<usize> #where_clause {
+            type MutOutput = #slice_mut_name #ty_generics;
 
             #[inline]
-            fn get_mut(self, soa: &'a mut #vec_name) -> Option<Self::MutOutput> {
+            fn get_mut(self, soa: &'a mut #vec_name #owned_ty_generics) -> Option<Self::MutOutput> {
                 (0..=self.end).get_mut(soa)
             }
 
             #[inline]
-            unsafe fn get_unchecked_mut(self, soa: &'a mut #vec_name) -> Self::MutOutput {
+            unsafe fn get_unchecked_mut(self, soa: &'a mut #vec_name #owned_ty_generics) -> Self::MutOutput {
                 (0..=self.end).get_unchecked_mut(soa)
             }
 
             #[inline]
-            fn index_mut(self, soa: &'a mut #vec_name) -> Self::MutOutput {
+            fn index_mut(self, soa: &'a mut #vec_name #owned_ty_generics) -> Self::MutOutput {
                 (0..=self.end).index_mut(soa)
             }
         }
 
         // usize
-        impl<'a> ::soa_derive::SoAIndex<#slice_name<'a>> for usize {
-            type RefOutput = #ref_name<'a>;
+        impl #impl_generics #krate::SoAIndex<#slice_name #ty_generics> for usize #where_clause {
+            type RefOutput = #ref_name #ty_generics;
 
             #[inline]
-            fn get(self, slice: #slice_name<'a>) -> Option<Self::RefOutput> {
+            fn get(self, slice: #slice_name #ty_generics) -> Option<Self::RefOutput> {
                 if self < slice.#first_field_name.len() {
                     Some(unsafe { self.get_unchecked(slice) })
                 } else {
@@ -352,25 +355,27 @@ pub fn derive(input: &Input) -> TokenStream {
             }
 
             #[inline]
-            unsafe fn get_unchecked(self, slice: #slice_name<'a>) -> Self::RefOutput {
+            unsafe fn get_unchecked(self, slice: #slice_name #ty_generics) -> Self::RefOutput {
                 #ref_name {
                     #( #fields_names: #get_unchecked, )*
+                    #marker
                 }
             }
 
             #[inline]
-            fn index(self, slice: #slice_name<'a>) -> Self::RefOutput {
+            fn index(self, slice: #slice_name #ty_generics) -> Self::RefOutput {
                 #ref_name {
                     #( #fields_names: #index, )*
+                    #marker
                 }
             }
         }
 
-        impl<'a> ::soa_derive::SoAIndexMut<#slice_mut_name<'a>> for usize {
-            type MutOutput = #ref_mut_name<'a>;
+        impl #impl_generics #krate::SoAIndexMut<#slice_mut_name #ty_generics> for usize #where_clause {
+            type MutOutput = #ref_mut_name #ty_generics;
 
             #[inline]
-            fn get_mut(self, slice: #slice_mut_name<'a>) -> Option<Self::MutOutput> {
+            fn get_mut(self, slice: #slice_mut_name #ty_generics) -> Option<Self::MutOutput> {
                 if self < slice.len() {
                     Some(unsafe { self.get_unchecked_mut(slice) })
                 } else {
@@ -379,16 +384,18 @@ pub fn derive(input: &Input) -> TokenStream {
             }
 
             #[inline]
-            unsafe fn get_unchecked_mut(self, slice: #slice_mut_name<'a>) -> Self::MutOutput {
+            unsafe fn get_unchecked_mut(self, slice: #slice_mut_name #ty_generics) -> Self::MutOutput {
                 #ref_mut_name {
                     #( #fields_names: #get_unchecked_mut, )*
+                    #marker
                 }
             }
 
             #[inline]
-            fn index_mut(self, slice: #slice_mut_name<'a>) -> Self::MutOutput {
+            fn index_mut(self, slice: #slice_mut_name #ty_generics) -> Self::MutOutput {
                 #ref_mut_name {
                     #( #fields_names: #index_mut, )*
+                    #marker
                 }
             }
         }
@@ -396,11 +403,11 @@ pub fn derive(input: &Input) -> TokenStream {
 
 
         // Range<usize>
-        impl<'a> ::soa_derive::SoAIndex<#slice_name<'a>> for ::std::ops::Range<usize> {
-            type RefOutput = #slice_name<'a>;
+        impl #impl_generics #krate::SoAIndex<#slice_name #ty_generics> for ::std::ops::Range<usize> #where_clause {
+            type RefOutput = #slice_name #ty_generics;
 
             #[inline]
-            fn get(self, slice: #slice_name<'a>) -> Option<Self::RefOutput> {
+            fn get(self, slice: #slice_name #ty_generics) -> Option<Self::RefOutput> {
                 if self.start <= self.end && self.end <= slice.#first_field_name.len() {
                     unsafe { Some(self.get_unchecked(slice)) }
                 } else {
@@ -409,25 +416,27 @@ pub fn derive(input: &Input) -> TokenStream {
             }
 
             #[inline]
-            unsafe fn get_unchecked(self, slice: #slice_name<'a>) -> Self::RefOutput {
+            unsafe fn get_unchecked(self, slice: #slice_name #ty_generics) -> Self::RefOutput {
                 #slice_name {
                     #( #fields_names: #get_unchecked, )*
+                    #marker
                 }
             }
 
             #[inline]
-            fn index(self, slice: #slice_name<'a>) -> Self::RefOutput {
+            fn index(self, slice: #slice_name #ty_generics) -> Self::RefOutput {
                 #slice_name {
                     #( #fields_names: #index, )*
+                    #marker
                 }
             }
         }
 
-        impl<'a> ::soa_derive::SoAIndexMut<#slice_mut_name<'a>> for ::std::ops::Range<usize> {
-            type MutOutput = #slice_mut_name<'a>;
+        impl #impl_generics #krate::SoAIndexMut<#slice_mut_name #ty_generics> for ::std::ops::Range<usize> #where_clause {
+            type MutOutput = #slice_mut_name #ty_generics;
 
             #[inline]
-            fn get_mut(self, slice: #slice_mut_name<'a>) -> Option<Self::MutOutput> {
+            fn get_mut(self, slice: #slice_mut_name #ty_generics) -> Option<Self::MutOutput> {
                 if self.start <= self.end && self.end <= slice.#first_field_name.len() {
                     unsafe { Some(self.get_unchecked_mut(slice)) }
                 } else {
@@ -436,16 +445,18 @@ pub fn derive(input: &Input) -> TokenStream {
             }
 
             #[inline]
-            unsafe fn get_unchecked_mut(self, slice: #slice_mut_name<'a>) -> Self::MutOutput {
+            unsafe fn get_unchecked_mut(self, slice: #slice_mut_name #ty_generics) -> Self::MutOutput {
                 #slice_mut_name {
                     #( #fields_names: #get_unchecked_mut, )*
+                    #marker
                 }
             }
 
             #[inline]
-            fn index_mut(self, slice: #slice_mut_name<'a>) -> Self::MutOutput {
+            fn index_mut(self, slice: #slice_mut_name #ty_generics) -> Self::MutOutput {
                 #slice_mut_name {
                     #( #fields_names: #index_mut, )*
+                    #marker
                 }
             }
         }
@@ -453,131 +464,131 @@ pub fn derive(input: &Input) -> TokenStream {
 
 
         // RangeTo<usize>
-        impl<'a> ::soa_derive::SoAIndex<#slice_name<'a>> for ::std::ops::RangeTo<usize> {
-            type RefOutput = #slice_name<'a>;
+        impl #impl_generics #krate::SoAIndex<#slice_name #ty_generics> for ::std::ops::RangeTo<usize> #where_clause {
+            type RefOutput = #slice_name #ty_generics;
 
             #[inline]
-            fn get(self, slice: #slice_name<'a>) -> Option<Self::RefOutput> {
+            fn get(self, slice: #slice_name #ty_generics) -> Option<Self::RefOutput> {
                 (0..self.end).get(slice)
             }
 
             #[inline]
-            unsafe fn get_unchecked(self, slice: #slice_name<'a>) -> Self::RefOutput {
+            unsafe fn get_unchecked(self, slice: #slice_name #ty_generics) -> Self::RefOutput {
                 (0..self.end).get_unchecked(slice)
             }
 
             #[inline]
-            fn index(self, slice: #slice_name<'a>) -> Self::RefOutput {
+            fn index(self, slice: #slice_name #ty_generics) -> Self::RefOutput {
                 (0..self.end).index(slice)
             }
         }
 
-        impl<'a> ::soa_derive::SoAIndexMut<#slice_mut_name<'a>> for ::std::ops::RangeTo<usize> {
-            type MutOutput = #slice_mut_name<'a>;
+        impl #impl_generics #krate::SoAIndexMut<#slice_mut_name #ty_generics> for ::std::ops::RangeTo<usize> #where_clause {
+            type MutOutput = #slice_mut_name #ty_generics;
 
             #[inline]
-            fn get_mut(self, slice: #slice_mut_name<'a>) -> Option<Self::MutOutput> {
+            fn get_mut(self, slice: #slice_mut_name #ty_generics) -> Option<Self::MutOutput> {
                 (0..self.end).get_mut(slice)
             }
 
             #[inline]
-            unsafe fn get_unchecked_mut(self, slice: #slice_mut_name<'a>) -> Self::MutOutput {
+            unsafe fn get_unchecked_mut(self, slice: #slice_mut_name #ty_generics) -> Self::MutOutput {
                 (0..self.end).get_unchecked_mut(slice)
             }
 
             #[inline]
-            fn index_mut(self, slice: #slice_mut_name<'a>) -> Self::MutOutput {
+            fn index_mut(self, slice: #slice_mut_name #ty_generics) -> Self::MutOutput {
                 (0..self.end).index_mut(slice)
             }
         }
 
 
         // RangeFrom<usize>
-        impl<'a> ::soa_derive::SoAIndex<#slice_name<'a>> for ::std::ops::RangeFrom<usize> {
-            type RefOutput = #slice_name<'a>;
+        impl #impl_generics #krate::SoAIndex<#slice_name #ty_generics> for ::std::ops::RangeFrom<usize> #where_clause {
+            type RefOutput = #slice_name #ty_generics;
 
             #[inline]
-            fn get(self, slice: #slice_name<'a>) -> Option<Self::RefOutput> {
+            fn get(self, slice: #slice_name #ty_generics) -> Option<Self::RefOutput> {
                 (self.start..slice.len()).get(slice)
             }
 
             #[inline]
-            unsafe fn get_unchecked(self, slice: #slice_name<'a>) -> Self::RefOutput {
+            unsafe fn get_unchecked(self, slice: #slice_name #ty_generics) -> Self::RefOutput {
                 (self.start..slice.len()).get_unchecked(slice)
             }
 
             #[inline]
-            fn index(self, slice: #slice_name<'a>) -> Self::RefOutput {
+            fn index(self, slice: #slice_name #ty_generics) -> Self::RefOutput {
                 (self.start..slice.len()).index(slice)
             }
         }
 
-        impl<'a> ::soa_derive::SoAIndexMut<#slice_mut_name<'a>> for ::std::ops::RangeFrom<usize> {
-            type MutOutput = #slice_mut_name<'a>;
+        impl #impl_generics #krate::SoAIndexMut<#slice_mut_name #ty_generics> for ::std::ops::RangeFrom<usize> #where_clause {
+            type MutOutput = #slice_mut_name #ty_generics;
 
             #[inline]
-            fn get_mut(self, slice: #slice_mut_name<'a>) -> Option<Self::MutOutput> {
+            fn get_mut(self, slice: #slice_mut_name #ty_generics) -> Option<Self::MutOutput> {
                 (self.start..slice.len()).get_mut(slice)
             }
 
             #[inline]
-            unsafe fn get_unchecked_mut(self, slice: #slice_mut_name<'a>) -> Self::MutOutput {
+            unsafe fn get_unchecked_mut(self, slice: #slice_mut_name #ty_generics) -> Self::MutOutput {
                 (self.start..slice.len()).get_unchecked_mut(slice)
             }
 
             #[inline]
-            fn index_mut(self, slice: #slice_mut_name<'a>) -> Self::MutOutput {
+            fn index_mut(self, slice: #slice_mut_name #ty_generics) -> Self::MutOutput {
                 (self.start..slice.len()).index_mut(slice)
             }
         }
 
 
         // RangeFull
-        impl<'a> ::soa_derive::SoAIndex<#slice_name<'a>> for ::std::ops::RangeFull {
-            type RefOutput = #slice_name<'a>;
+        impl #impl_generics #krate::SoAIndex<#slice_name #ty_generics> for ::std::ops::RangeFull #where_clause {
+            type RefOutput = #slice_name #ty_generics;
 
             #[inline]
-            fn get(self, slice: #slice_name<'a>) -> Option<Self::RefOutput> {
+            fn get(self, slice: #slice_name #ty_generics) -> Option<Self::RefOutput> {
                 Some(slice)
             }
 
             #[inline]
-            unsafe fn get_unchecked(self, slice: #slice_name<'a>) -> Self::RefOutput {
+            unsafe fn get_unchecked(self, slice: #slice_name #ty_generics) -> Self::RefOutput {
                 slice
             }
 
             #[inline]
-            fn index(self, slice: #slice_name<'a>) -> Self::RefOutput {
+            fn index(self, slice: #slice_name #ty_generics) -> Self::RefOutput {
                 slice
             }
         }
 
-        impl<'a> ::soa_derive::SoAIndexMut<#slice_mut_name<'a>> for ::std::ops::RangeFull {
-            type MutOutput = #slice_mut_name<'a>;
+        impl #impl_generics #krate::SoAIndexMut<#slice_mut_name #ty_generics> for ::std::ops::RangeFull #where_clause {
+            type MutOutput = #slice_mut_name #ty_generics;
 
             #[inline]
-            fn get_mut(self, slice: #slice_mut_name<'a>) -> Option<Self::MutOutput> {
+            fn get_mut(self, slice: #slice_mut_name #ty_generics) -> Option<Self::MutOutput> {
                 Some(slice)
             }
 
             #[inline]
-            unsafe fn get_unchecked_mut(self, slice: #slice_mut_name<'a>) -> Self::MutOutput {
+            unsafe fn get_unchecked_mut(self, slice: #slice_mut_name #ty_generics) -> Self::MutOutput {
                 slice
             }
 
             #[inline]
-            fn index_mut(self, slice: #slice_mut_name<'a>) -> Self::MutOutput {
+            fn index_mut(self, slice: #slice_mut_name #ty_generics) -> Self::MutOutput {
                 slice
             }
         }
 
 
         // RangeInclusive<usize>
-        impl<'a> ::soa_derive::SoAIndex<#slice_name<'a>> for ::std::ops::RangeInclusive<usize> {
-            type RefOutput = #slice_name<'a>;
+        impl #impl_generics #krate::SoAIndex<#slice_name #ty_generics> for ::std::ops::RangeInclusive<usize> #where_clause {
+            type RefOutput = #slice_name #ty_generics;
 
             #[inline]
-            fn get(self, slice: #slice_name<'a>) -> Option<Self::RefOutput> {
+            fn get(self, slice: #slice_name #ty_generics) -> Option<Self::RefOutput> {
                 if *self.end() == usize::MAX {
                     None
                 } else {
@@ -586,21 +597,21 @@ pub fn derive(input: &Input) -> TokenStream {
             }
 
             #[inline]
-            unsafe fn get_unchecked(self, slice: #slice_name<'a>) -> Self::RefOutput {
+            unsafe fn get_unchecked(self, slice: #slice_name #ty_generics) -> Self::RefOutput {
                 (*self.start()..self.end() + 1).get_unchecked(slice)
             }
 
             #[inline]
-            fn index(self, slice: #slice_name<'a>) -> Self::RefOutput {
+            fn index(self, slice: #slice_name #ty_generics) -> Self::RefOutput {
                 (*self.start()..self.end() + 1).index(slice)
             }
         }
 
-        impl<'a> ::soa_derive::SoAIndexMut<#slice_mut_name<'a>> for ::std::ops::RangeInclusive<usize> {
-            type MutOutput = #slice_mut_name<'a>;
+        impl #impl_generics #krate::SoAIndexMut<#slice_mut_name #ty_generics> for ::std::ops::RangeInclusive<usize> #where_clause {
+            type MutOutput = #slice_mut_name #ty_generics;
 
             #[inline]
-            fn get_mut(self, slice: #slice_mut_name<'a>) -> Option<Self::MutOutput> {
+            fn get_mut(self, slice: #slice_mut_name #ty_generics) -> Option<Self::MutOutput> {
                 if *self.end() == usize::MAX {
                     None
                 } else {
@@ -609,52 +620,52 @@ pub fn derive(input: &Input) -> TokenStream {
             }
 
             #[inline]
-            unsafe fn get_unchecked_mut(self, slice: #slice_mut_name<'a>) -> Self::MutOutput {
+            unsafe fn get_unchecked_mut(self, slice: #slice_mut_name #ty_generics) -> Self::MutOutput {
                 (*self.start()..self.end() + 1).get_unchecked_mut(slice)
             }
 
             #[inline]
-            fn index_mut(self, slice: #slice_mut_name<'a>) -> Self::MutOutput {
+            fn index_mut(self, slice: #slice_mut_name #ty_generics) -> Self::MutOutput {
                 (*self.start()..self.end() + 1).index_mut(slice)
             }
         }
 
 
         // RangeToInclusive<usize>
-        impl<'a> ::soa_derive::SoAIndex<#slice_name<'a>> for ::std::ops::RangeToInclusive<usize> {
-            type RefOutput = #slice_name<'a>;
+        impl #impl_generics #krate::SoAIndex<#slice_name #ty_generics> for ::std::ops::RangeToInclusive<usize> #where_clause {
+            type RefOutput = #slice_name #ty_generics;
 
             #[inline]
-            fn get(self, slice: #slice_name<'a>) -> Option<Self::RefOutput> {
+            fn get(self, slice: #slice_name #ty_generics) -> Option<Self::RefOutput> {
                 (0..=self.end).get(slice)
             }
 
             #[inline]
-            unsafe fn get_unchecked(self, slice: #slice_name<'a>) -> Self::RefOutput {
+            unsafe fn get_unchecked(self, slice: #slice_name #ty_generics) -> Self::RefOutput {
                 (0..=self.end).get_unchecked(slice)
             }
 
             #[inline]
-            fn index(self, slice: #slice_name<'a>) -> Self::RefOutput {
+            fn index(self, slice: #slice_name #ty_generics) -> Self::RefOutput {
                 (0..=self.end).index(slice)
             }
         }
 
-        impl<'a> ::soa_derive::SoAIndexMut<#slice_mut_name<'a>> for ::std::ops::RangeToInclusive<usize> {
-            type MutOutput = #slice_mut_name<'a>;
+        impl #impl_generics #krate::SoAIndexMut<#slice_mut_name #ty_generics> for ::std::ops::RangeToInclusive<usize> #where_clause {
+            type MutOutput = #slice_mut_name #ty_generics;
 
             #[inline]
-            fn get_mut(self, slice: #slice_mut_name<'a>) -> Option<Self::MutOutput> {
+            fn get_mut(self, slice: #slice_mut_name #ty_generics) -> Option<Self::MutOutput> {
                 (0..=self.end).get_mut(slice)
             }
 
             #[inline]
-            unsafe fn get_unchecked_mut(self, slice: #slice_mut_name<'a>) -> Self::MutOutput {
+            unsafe fn get_unchecked_mut(self, slice: #slice_mut_name #ty_generics) -> Self::MutOutput {
                 (0..=self.end).get_unchecked_mut(slice)
             }
 
             #[inline]
-            fn index_mut(self, slice: #slice_mut_name<'a>) -> Self::MutOutput {
+            fn index_mut(self, slice: #slice_mut_name #ty_generics) -> Self::MutOutput {
                 (0..=self.end).index_mut(slice)
             }
         }
@@ -1,18 +1,41 @@
 use proc_macro2::{Span, TokenStream};
-use quote::quote;
+use quote::{quote, quote_spanned, ToTokens};
 
 use syn::punctuated::Punctuated;
-use syn::{Attribute, Data, DeriveInput, Field, Path, Token, Visibility};
-use syn::{Meta, MetaList};
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::visit_mut::{self, VisitMut};
+use syn::{Attribute, Data, DeriveInput, Field, Fields, Path, Token, Visibility};
+use syn::{GenericParam, Generics, Lifetime, Member, Meta, MetaList, WhereClause, WherePredicate};
+
+use crate::names::{self, Names};
 
 /// Representing the struct we are deriving
 pub struct Input {
     /// The input struct name
     pub name: syn::Ident,
-    /// The list of fields in the struct
+    /// The generic parameters of the struct, renamed if they clash with the
+    /// ones used in the generated code
+    pub generics: Generics,
+    /// The list of fields in the struct, without the fields marked with
+    /// `#[soa(skip)]`
     pub fields: Vec<Field>,
+    /// The names of the fields in `fields` inside the input struct. These can
+    /// differ from `fields_names()` for tuple structs with skipped fields.
+    pub original_fields_names: Vec<Member>,
+    /// The fields marked with `#[soa(skip)]`, which are not stored in the
+    /// generated types
+    pub skipped_fields: Vec<SkippedField>,
+    /// `PhantomData` type for the generic parameters only used by skipped
+    /// fields, stored in an additional field of the generated types
+    pub marker: Option<syn::Type>,
+    /// Is the input a tuple struct, with unnamed fields?
+    pub is_tuple: bool,
     /// Is field marked with `#[nested_soa]`
     pub field_is_nested: Vec<bool>,
+    /// Additional attributes for each field, requested with
+    /// `#[soa_field_attr(...)]`
+    pub field_attrs: Vec<FieldAttributes>,
     /// The struct overall visibility
     pub visibility: Visibility,
     /// Additional attributes requested with `#[soa_attr(...)]` or
@@ -20,9 +43,139 @@ pub struct Input {
     pub attrs: ExtraAttributes,
 }
 
+/// A field marked with `#[soa(skip)]`
+pub struct SkippedField {
+    /// The name of the field in the input struct
+    pub name: Member,
+    /// Expression used to re-create the value of this field, either
+    /// `Default::default()` or a call to the function given with
+    /// `#[soa(skip, default = "path")]`
+    pub default: TokenStream,
+}
+
+/// The generated types which can receive additional attributes with
+/// `#[soa_attr]` and `#[soa_field_attr]`
+#[derive(Clone, Copy)]
+enum SoAType {
+    Vec,
+    Slice,
+    SliceMut,
+    Ref,
+    RefMut,
+    Ptr,
+    PtrMut,
+}
+
+impl SoAType {
+    /// All the generated types which can receive additional attributes
+    const ALL: [SoAType; 7] = [
+        SoAType::Vec, SoAType::Slice, SoAType::SliceMut, SoAType::Ref,
+        SoAType::RefMut, SoAType::Ptr, SoAType::PtrMut,
+    ];
+}
+
+/// Parse an attribute like `#[soa_attr(<Type>, <attr>)]`, where `name` is the
+/// name of the attribute
+fn parse_soa_attr(attr: &Attribute, name: &str) -> syn::Result<(SoAType, Meta)> {
+    let nested = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+    if nested.len() != 2 {
+        return Err(syn::Error::new_spanned(attr, format!("expected attribute like #[{}(<Type>, <attr>)]", name)));
+    }
+
+    let soa_type = nested.first().expect("should have 2 elements");
+    let meta = nested.last().expect("should have 2 elements").clone();
+
+    let soa_type = match soa_type.path().get_ident() {
+        Some(ident) if ident == "Vec" => SoAType::Vec,
+        Some(ident) if ident == "Slice" => SoAType::Slice,
+        Some(ident) if ident == "SliceMut" => SoAType::SliceMut,
+        Some(ident) if ident == "Ref" => SoAType::Ref,
+        Some(ident) if ident == "RefMut" => SoAType::RefMut,
+        Some(ident) if ident == "Ptr" => SoAType::Ptr,
+        Some(ident) if ident == "PtrMut" => SoAType::PtrMut,
+        _ => return Err(syn::Error::new_spanned(
+            soa_type,
+            format!(
+                "expected one of the SoA type (Vec, Slice, SliceMut, Ref, RefMut, Ptr, PtrMut), got {}",
+                soa_type.to_token_stream()
+            ),
+        )),
+    };
+
+    return Ok((soa_type, meta));
+}
+
+/// Additional attributes requested with `#[soa_field_attr(...)]` on a single
+/// field, to be added to the corresponding field in the generated types. The
+/// doc comments on the field are also forwarded to all the generated types.
+#[derive(Default)]
+pub struct FieldAttributes {
+    pub vec: Vec<Meta>,
+    pub slice: Vec<Meta>,
+    pub slice_mut: Vec<Meta>,
+    pub ref_: Vec<Meta>,
+    pub ref_mut: Vec<Meta>,
+    pub ptr: Vec<Meta>,
+    pub ptr_mut: Vec<Meta>,
+}
+
+impl FieldAttributes {
+    /// Parse the `#[soa_field_attr]` and `#[doc]` attributes on a field
+    fn parse(attrs: &[Attribute]) -> syn::Result<FieldAttributes> {
+        let mut field_attrs = FieldAttributes::default();
+
+        let docs = attrs.iter()
+            .filter(|attr| attr.path().is_ident("doc"))
+            .map(|attr| attr.meta.clone())
+            .collect::<Vec<_>>();
+        if !docs.is_empty() {
+            for soa_type in SoAType::ALL {
+                let field_docs = field_attrs.attrs_mut(soa_type);
+                // separate the docs of the field from the generated ones
+                field_docs.push(syn::parse_quote!(doc = ""));
+                field_docs.extend(docs.iter().cloned());
+            }
+        }
+
+        let mut errors = Errors::default();
+        for attr in attrs {
+            if attr.path().is_ident("soa_field_attr") {
+                match parse_soa_attr(attr, "soa_field_attr") {
+                    Ok((soa_type, meta)) => field_attrs.attrs_mut(soa_type).push(meta),
+                    Err(error) => errors.push(error),
+                }
+            }
+        }
+
+        errors.finish()?;
+        return Ok(field_attrs);
+    }
+
+    /// Get the list of attributes to add to the field in `soa_type`
+    fn attrs_mut(&mut self, soa_type: SoAType) -> &mut Vec<Meta> {
+        match soa_type {
+            SoAType::Vec => &mut self.vec,
+            SoAType::Slice => &mut self.slice,
+            SoAType::SliceMut => &mut self.slice_mut,
+            SoAType::Ref => &mut self.ref_,
+            SoAType::RefMut => &mut self.ref_mut,
+            SoAType::Ptr => &mut self.ptr,
+            SoAType::PtrMut => &mut self.ptr_mut,
+        }
+    }
+}
+
 pub struct ExtraAttributes {
     // did the user explicitly asked us to derive clone?
     pub derive_clone: bool,
+    /// Names of the generated types, from `#[soa(vec = "...", ...)]`
+    pub names: Names,
+    /// Path to the `soa_derive` crate, from `#[soa(crate = "...")]`
+    pub crate_path: Path,
+    /// Generate conversions to and from Arrow, from `#[soa(arrow)]`
+    pub arrow: bool,
+    /// Generate conversions to and from Polars, from `#[soa(polars)]`
+    pub polars: bool,
 
     pub vec: Vec<Meta>,
     pub slice: Vec<Meta>,
@@ -34,9 +187,13 @@ pub struct ExtraAttributes {
 }
 
 impl ExtraAttributes {
-    fn new() -> ExtraAttributes {
+    fn new(name: &syn::Ident) -> ExtraAttributes {
         ExtraAttributes {
             derive_clone: false,
+            names: Names::new(name),
+            crate_path: syn::parse_quote!(::soa_derive),
+            arrow: false,
+            polars: false,
             vec: Vec::new(),
             slice: Vec::new(),
             slice_mut: Vec::new(),
@@ -47,6 +204,148 @@ impl ExtraAttributes {
         }
     }
 
+    /// Parse the `#[soa_derive]`, `#[soa_attr]` and `#[soa]` attributes on the
+    /// input named `name`
+    pub(crate) fn parse(name: &syn::Ident, attrs: &[Attribute]) -> syn::Result<ExtraAttributes> {
+        let mut extra_attrs = ExtraAttributes::new(name);
+        let mut errors = Errors::default();
+
+        for attr in attrs {
+            if attr.path().is_ident("soa") {
+                if let Err(error) = attr.parse_nested_meta(|meta| extra_attrs.parse_soa_option(&meta)) {
+                    errors.push(error);
+                }
+            }
+
+            if attr.path().is_ident("soa_derive") {
+                let result = attr.parse_nested_meta(|meta| {
+                    match meta.path.get_ident() {
+                        Some(ident) => {
+                            if ident == "Copy" {
+                                return Err(meta.error("can not derive Copy for SoA vectors"));
+                            }
+                            if ident != "Default" {
+                                // ignore as Default is already derived for SoA vectors, slices and mut slices
+                                extra_attrs.add_derive(ident);
+                            }
+                        }
+                        None => {
+                            return Err(meta.error("expected #[soa_derive(Traits, To, Derive)]"));
+                        }
+                    }
+                    Ok(())
+                });
+                if let Err(error) = result {
+                    errors.push(error);
+                }
+            }
+
+            if attr.path().is_ident("soa_attr") {
+                match parse_soa_attr(attr, "soa_attr") {
+                    Ok((soa_type, meta)) => extra_attrs.attrs_mut(soa_type).push(meta),
+                    Err(error) => errors.push(error),
+                }
+            }
+        }
+
+        errors.finish()?;
+        return Ok(extra_attrs);
+    }
+
+    /// Parse a single option inside `#[soa(...)]`, either the name of one of
+    /// the generated types or the path to the crate
+    fn parse_soa_option(&mut self, meta: &syn::meta::ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("crate") {
+            self.crate_path = meta.value()?.parse::<syn::LitStr>()?.parse_with(Path::parse_mod_style)?;
+            return Ok(());
+        }
+
+        if meta.path.is_ident("arrow") {
+            if cfg!(feature = "arrow") {
+                self.arrow = true;
+                return Ok(());
+            }
+            return Err(meta.error("#[soa(arrow)] requires the `arrow` feature of soa_derive"));
+        }
+
+        if meta.path.is_ident("polars") {
+            if cfg!(feature = "polars") {
+                self.polars = true;
+                return Ok(());
+            }
+            return Err(meta.error("#[soa(polars)] requires the `polars` feature of soa_derive"));
+        }
+
+        let name = if meta.path.is_ident("vec") {
+            &mut self.names.vec
+        } else if meta.path.is_ident("slice") {
+            &mut self.names.slice
+        } else if meta.path.is_ident("slice_mut") {
+            &mut self.names.slice_mut
+        } else if meta.path.is_ident("ref") {
+            &mut self.names.ref_
+        } else if meta.path.is_ident("ref_mut") {
+            &mut self.names.ref_mut
+        } else if meta.path.is_ident("ptr") {
+            &mut self.names.ptr
+        } else if meta.path.is_ident("ptr_mut") {
+            &mut self.names.ptr_mut
+        } else if meta.path.is_ident("iter") {
+            &mut self.names.iter
+        } else if meta.path.is_ident("iter_mut") {
+            &mut self.names.iter_mut
+        } else if meta.path.is_ident("into_iter") {
+            &mut self.names.into_iter
+        } else if meta.path.is_ident("drain") {
+            &mut self.names.drain
+        } else if meta.path.is_ident("splice") {
+            &mut self.names.splice
+        } else if meta.path.is_ident("extract_if") {
+            &mut self.names.extract_if
+        } else if meta.path.is_ident("chunks") {
+            &mut self.names.chunks
+        } else if meta.path.is_ident("chunks_exact") {
+            &mut self.names.chunks_exact
+        } else if meta.path.is_ident("chunks_mut") {
+            &mut self.names.chunks_mut
+        } else if meta.path.is_ident("rchunks") {
+            &mut self.names.rchunks
+        } else if meta.path.is_ident("windows") {
+            &mut self.names.windows
+        } else if meta.path.is_ident("column") {
+            &mut self.names.column
+        } else if meta.path.is_ident("selection") {
+            &mut self.names.selection
+        } else if meta.path.is_ident("par_iter") {
+            &mut self.names.par_iter
+        } else if meta.path.is_ident("par_iter_mut") {
+            &mut self.names.par_iter_mut
+        } else {
+            return Err(meta.error(
+                "expected one of vec, slice, slice_mut, ref, ref_mut, ptr, ptr_mut, iter, iter_mut, into_iter, drain, splice, extract_if, chunks, chunks_exact, chunks_mut, rchunks, windows, column, selection, par_iter, par_iter_mut, crate, arrow or polars in #[soa(...)]"
+            ));
+        };
+
+        let lit = meta.value()?.parse::<syn::LitStr>()?;
+        *name = lit.parse::<syn::Ident>().map_err(|_| {
+            syn::Error::new_spanned(&lit, format!("expected a type name, got {}", lit.value()))
+        })?;
+        return Ok(());
+    }
+
+    /// Get the list of attributes to add to `soa_type`
+    fn attrs_mut(&mut self, soa_type: SoAType) -> &mut Vec<Meta> {
+        match soa_type {
+            SoAType::Vec => &mut self.vec,
+            SoAType::Slice => &mut self.slice,
+            SoAType::SliceMut => &mut self.slice_mut,
+            SoAType::Ref => &mut self.ref_,
+            SoAType::RefMut => &mut self.ref_mut,
+            SoAType::Ptr => &mut self.ptr,
+            SoAType::PtrMut => &mut self.ptr_mut,
+        }
+    }
+
     /// Add a single trait from `#[soa_derive]`
     fn add_derive(&mut self, ident: &proc_macro2::Ident) {
         let derive_only_vec = |ident| {
@@ -83,6 +382,107 @@ impl ExtraAttributes {
     }
 }
 
+/// Lifetimes used by the generated code, which would shadow lifetimes with the
+/// same name in the input struct
+static RESERVED_LIFETIMES: &[&str] = &["a", "b", "c", "t"];
+/// Type parameters used by the generated code, which would shadow type
+/// parameters with the same name in the input struct
+static RESERVED_TYPES: &[&str] = &["B", "C", "CB", "F", "I", "K", "P", "R", "T"];
+
+/// Rename the generic parameters of the input struct which clash with the
+/// names used in the generated code, everywhere they are used
+struct RenameGenerics {
+    renamed: Vec<(syn::Ident, syn::Ident)>,
+}
+
+impl RenameGenerics {
+    fn new(generics: &Generics) -> RenameGenerics {
+        let mut renamed = Vec::new();
+        for param in &generics.params {
+            let (ident, reserved) = match param {
+                GenericParam::Lifetime(param) => (&param.lifetime.ident, RESERVED_LIFETIMES),
+                GenericParam::Type(param) => (&param.ident, RESERVED_TYPES),
+                GenericParam::Const(param) => (&param.ident, RESERVED_TYPES),
+            };
+            if reserved.iter().any(|name| ident == name) {
+                renamed.push((ident.clone(), syn::Ident::new(&format!("__{}", ident), ident.span())));
+            }
+        }
+        RenameGenerics { renamed }
+    }
+
+    fn rename(&self, ident: &mut syn::Ident) {
+        for (old, new) in &self.renamed {
+            if ident == old {
+                *ident = new.clone();
+                return;
+            }
+        }
+    }
+}
+
+impl VisitMut for RenameGenerics {
+    fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+        self.rename(&mut lifetime.ident);
+    }
+
+    fn visit_type_param_mut(&mut self, param: &mut syn::TypeParam) {
+        self.rename(&mut param.ident);
+        visit_mut::visit_type_param_mut(self, param);
+    }
+
+    fn visit_const_param_mut(&mut self, param: &mut syn::ConstParam) {
+        self.rename(&mut param.ident);
+        visit_mut::visit_const_param_mut(self, param);
+    }
+
+    fn visit_path_mut(&mut self, path: &mut Path) {
+        // generic parameters can only be used as the first segment of a path
+        if path.leading_colon.is_none() {
+            if let Some(first) = path.segments.first_mut() {
+                self.rename(&mut first.ident);
+            }
+        }
+        visit_mut::visit_path_mut(self, path);
+    }
+}
+
+/// Accumulate multiple errors, to report all of them at once to the user
+#[derive(Default)]
+pub(crate) struct Errors {
+    error: Option<syn::Error>,
+}
+
+impl Errors {
+    /// Add a new error to the list
+    pub(crate) fn push(&mut self, error: syn::Error) {
+        match &mut self.error {
+            Some(existing) => existing.combine(error),
+            None => self.error = Some(error),
+        }
+    }
+
+    /// Get all the accumulated errors, if any
+    pub(crate) fn finish(self) -> syn::Result<()> {
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Check if `ty` can be used with `#[nested_soa]`, *i.e.* if it is a path to
+/// a named type. See also `names::nested_type`.
+fn is_nested_type(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => {
+            let last = path.segments.last().expect("paths have at least one segment");
+            !matches!(last.arguments, syn::PathArguments::Parenthesized(_))
+        }
+        _ => false,
+    }
+}
+
 fn contains_nested_soa(attrs: &[Attribute]) -> bool {
     for attr in attrs {
         if attr.path().is_ident("nested_soa") {
@@ -92,99 +492,365 @@ fn contains_nested_soa(attrs: &[Attribute]) -> bool {
     return false;
 }
 
+/// Parse the `#[soa(skip)]` and `#[soa(skip, default = "path")]` attributes
+/// on a field. This returns the expression used to re-create the value of the
+/// field if it is skipped.
+fn parse_skip(field: &Field) -> syn::Result<Option<TokenStream>> {
+    let mut skip = false;
+    let mut default = None;
+    let mut errors = Errors::default();
+    for attr in &field.attrs {
+        if !attr.path().is_ident("soa") {
+            continue;
+        }
+
+        let result = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                skip = true;
+            } else if meta.path.is_ident("default") {
+                let path = meta.value()?.parse::<syn::LitStr>()?.parse::<syn::ExprPath>()?;
+                default = Some(path);
+            } else {
+                return Err(meta.error("expected #[soa(skip)] or #[soa(skip, default = \"path\")] on fields"));
+            }
+            Ok(())
+        });
+        if let Err(error) = result {
+            errors.push(error);
+        }
+    }
+    errors.finish()?;
+
+    if !skip {
+        if let Some(default) = default {
+            return Err(syn::Error::new_spanned(default, "#[soa(default = ...)] can only be used on #[soa(skip)] fields"));
+        }
+        return Ok(None);
+    }
+
+    let span = field.ty.span();
+    if let Some(path) = default {
+        return Ok(Some(quote_spanned! { span => #path() }));
+    }
+    return Ok(Some(quote_spanned! { span => ::std::default::Default::default() }));
+}
+
+/// Collect the names of all generic parameters used in a type
+#[derive(Default)]
+struct UsedGenerics {
+    used: Vec<syn::Ident>,
+}
+
+impl<'ast> Visit<'ast> for UsedGenerics {
+    fn visit_lifetime(&mut self, lifetime: &'ast Lifetime) {
+        self.used.push(lifetime.ident.clone());
+    }
+
+    fn visit_path(&mut self, path: &'ast Path) {
+        if path.leading_colon.is_none() {
+            if let Some(first) = path.segments.first() {
+                self.used.push(first.ident.clone());
+            }
+        }
+        visit::visit_path(self, path);
+    }
+}
+
+/// Get the `PhantomData` type to store in the generated types for the generic
+/// parameters which are not used by any of the `fields` stored in these
+/// types, or `None` if all parameters are used. The marker refers to the
+/// types of the `skipped` fields using these parameters through a function
+/// pointer, since the generated types never contain values of these types and
+/// should implement `Send` and `Sync` regardless of them. Const parameters
+/// can be left unused, and do not need a marker.
+fn unused_generics_marker(generics: &Generics, fields: &[Field], skipped: &[syn::Type]) -> Option<syn::Type> {
+    let mut visitor = UsedGenerics::default();
+    for field in fields {
+        visitor.visit_type(&field.ty);
+    }
+
+    let unused = generics.params.iter().filter_map(|param| match param {
+        GenericParam::Lifetime(param) => Some(&param.lifetime.ident),
+        GenericParam::Type(param) => Some(&param.ident),
+        GenericParam::Const(_) => None,
+    }).filter(|ident| !visitor.used.contains(ident)).collect::<Vec<_>>();
+
+    let marker_types = skipped.iter().filter(|ty| {
+        let mut visitor = UsedGenerics::default();
+        visitor.visit_type(ty);
+        visitor.used.iter().any(|ident| unused.contains(&ident))
+    }).collect::<Vec<_>>();
+
+    if marker_types.is_empty() {
+        return None;
+    }
+
+    return Some(syn::parse_quote! { ::std::marker::PhantomData<fn() -> (#(#marker_types,)*)> });
+}
+
+/// Check that the `fields` stored in the generated types get different
+/// variants in the generated column enum, since fields such as `foo_bar` and
+/// `fooBar` are both converted to `FooBar`.
+fn check_column_variants(fields: &[Field], errors: &mut Errors) {
+    let mut variants: Vec<(syn::Ident, &syn::Ident)> = Vec::new();
+    for field in fields {
+        if let Some(ident) = &field.ident {
+            let variant = names::column_variant_name(&Member::Named(ident.clone()));
+            if let Some((_, previous)) = variants.iter().find(|(existing, _)| *existing == variant) {
+                errors.push(syn::Error::new_spanned(
+                    ident,
+                    format!(
+                        "#[derive(StructOfArray)] can not be used on a struct with fields named {} and {}, they would both use the {} variant of the generated column enum",
+                        previous, ident, variant,
+                    ),
+                ));
+            } else {
+                variants.push((variant, ident));
+            }
+        }
+    }
+}
+
 impl Input {
-    pub fn new(input: DeriveInput) -> Input {
+    pub fn new(input: DeriveInput) -> syn::Result<Input> {
+        let mut errors = Errors::default();
+
+        let extra_attrs = ExtraAttributes::parse(&input.ident, &input.attrs);
+        if let Err(error) = &extra_attrs {
+            errors.push(error.clone());
+        }
+
+        let mut rename = RenameGenerics::new(&input.generics);
+        let mut generics = input.generics;
+        rename.visit_generics_mut(&mut generics);
+
         let mut fields = Vec::new();
+        let mut original_fields_names = Vec::new();
+        let mut skipped_fields = Vec::new();
+        let mut skipped_types = Vec::new();
         let mut field_is_nested = Vec::new();
+        let mut field_attrs = Vec::new();
+        let mut is_tuple = false;
+        let mut marker = None;
         match input.data {
             Data::Struct(s) => {
-                for field in s.fields.iter().cloned() {
-                    fields.push(field.clone());
-                    field_is_nested.push(contains_nested_soa(&field.attrs));
-                }
-            }
-            _ => panic!("#[derive(StructOfArray)] only supports struct"),
-        };
+                is_tuple = matches!(s.fields, Fields::Unnamed(_));
+                for (i, mut field) in s.fields.iter().cloned().enumerate() {
+                    let name = match &field.ident {
+                        Some(ident) => Member::Named(ident.clone()),
+                        None => Member::Unnamed(i.into()),
+                    };
 
-        assert!(!fields.is_empty(), "#[derive(StructOfArray)] only supports struct with fields");
-
-        let mut extra_attrs = ExtraAttributes::new();
+                    let is_nested = contains_nested_soa(&field.attrs);
+                    if is_nested && !is_nested_type(&field.ty) {
+                        errors.push(syn::Error::new_spanned(&field.ty, "#[nested_soa] fields must have a named struct type"));
+                    }
 
-        for attr in input.attrs {
-            if attr.path().is_ident("soa_derive") {
-                attr.parse_nested_meta(|meta| {
-                    match meta.path.get_ident() {
-                        Some(ident) => {
-                            assert!(ident != "Copy", "can not derive Copy for SoA vectors");
-                            if ident != "Default" {
-                                // ignore as Default is already derived for SoA vectors, slices and mut slices
-                                extra_attrs.add_derive(ident);
+                    match parse_skip(&field) {
+                        Ok(Some(default)) => {
+                            if is_nested {
+                                errors.push(syn::Error::new_spanned(&field.ty, "#[soa(skip)] fields can not be #[nested_soa]"));
                             }
+                            rename.visit_type_mut(&mut field.ty);
+                            skipped_types.push(field.ty);
+                            skipped_fields.push(SkippedField { name, default });
+                            continue;
                         }
-                        None => {
-                            panic!(
-                                "expected #[soa_derive(Traits, To, Derive)], got #[{}]",
-                                quote!(attr)
-                            );
-                        }
+                        Ok(None) => {}
+                        Err(error) => errors.push(error),
                     }
-                    Ok(())
-                }).expect("failed to parse soa_derive");
-            }
 
-            if attr.path().is_ident("soa_attr") {
-                let nested = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
-                    .expect("expected attribute like #[soa_attr(<Type>, <attr>)]");
-                assert!(nested.len() == 2, "expected attribute like #[soa_attr(<Type>, <attr>)]");
-
-                let soa_type = nested.first().expect("should have 2 elements");
-                let attr = nested.last().expect("should have 2 elements").clone();
-
-                match soa_type.path().get_ident() {
-                    Some(ident) => {
-                        if ident == "Vec" {
-                            extra_attrs.vec.push(attr);
-                        } else if ident == "Slice" {
-                            extra_attrs.slice.push(attr);
-                        } else if ident == "SliceMut" {
-                            extra_attrs.slice_mut.push(attr);
-                        } else if ident == "Ref" {
-                            extra_attrs.ref_.push(attr);
-                        } else if ident == "RefMut" {
-                            extra_attrs.ref_mut.push(attr);
-                        } else if ident == "Ptr" {
-                            extra_attrs.ptr.push(attr);
-                        } else if ident == "PtrMut" {
-                            extra_attrs.ptr_mut.push(attr);
-                        } else {
-                            panic!("expected one of the SoA type, got {}", quote!(#soa_type));
-                        }
+                    match FieldAttributes::parse(&field.attrs) {
+                        Ok(attrs) => field_attrs.push(attrs),
+                        Err(error) => errors.push(error),
                     }
-                    None => panic!("expected one of the SoA type, got {}", quote!(#soa_type))
+
+                    rename.visit_type_mut(&mut field.ty);
+                    fields.push(field);
+                    original_fields_names.push(name);
+                    field_is_nested.push(is_nested);
+                }
+
+                if s.fields.is_empty() {
+                    errors.push(syn::Error::new_spanned(&input.ident, "#[derive(StructOfArray)] only supports struct with fields"));
+                } else if fields.is_empty() {
+                    errors.push(syn::Error::new_spanned(&input.ident, "#[derive(StructOfArray)] needs at least one field without #[soa(skip)]"));
+                } else if !skipped_fields.is_empty() {
+                    marker = unused_generics_marker(&generics, &fields, &skipped_types);
                 }
+                check_column_variants(&fields, &mut errors);
+            }
+            Data::Enum(_) => unreachable!("enums are handled in enums.rs"),
+            Data::Union(data) => {
+                errors.push(syn::Error::new(data.union_token.span, "#[derive(StructOfArray)] only supports struct and enum"));
             }
         }
 
-        Input {
+        errors.finish()?;
+        return Ok(Input {
             name: input.ident,
+            generics: generics,
             fields: fields,
+            original_fields_names: original_fields_names,
+            skipped_fields: skipped_fields,
+            marker: marker,
+            is_tuple: is_tuple,
             visibility: input.vis,
-            attrs: extra_attrs,
+            attrs: extra_attrs.expect("errors are reported above"),
             field_is_nested,
+            field_attrs,
+        });
+    }
+
+    /// Get the generics to use for the generated types borrowing data from a
+    /// vector (slices, references and iterators), *i.e.* the generics of the
+    /// input struct with an additional `lifetime` in first position. All the
+    /// other parameters are required to outlive this `lifetime`.
+    pub(crate) fn generics_with_lifetime(&self, lifetime: &str) -> Generics {
+        let lifetime = Lifetime::new(lifetime, Span::call_site());
+        let mut generics = self.generics.clone();
+        for param in &mut generics.params {
+            match param {
+                GenericParam::Lifetime(param) => param.bounds.push(lifetime.clone()),
+                GenericParam::Type(param) => param.bounds.push(syn::TypeParamBound::Lifetime(lifetime.clone())),
+                GenericParam::Const(_) => {}
+            }
+        }
+        generics.params.insert(0, GenericParam::Lifetime(syn::LifetimeParam::new(lifetime)));
+        return generics;
+    }
+
+    /// Get the generic arguments to use when referring to one of the
+    /// generated types borrowing data for `lifetime`, *i.e.* `<'lifetime, T,
+    /// U>` for an input struct with `T` and `U` as parameters.
+    pub(crate) fn ty_generics_with_lifetime(&self, lifetime: &str) -> TokenStream {
+        let lifetime = Lifetime::new(lifetime, Span::call_site());
+        let params = self.generics.params.iter().map(|param| match param {
+            GenericParam::Lifetime(param) => param.lifetime.to_token_stream(),
+            GenericParam::Type(param) => param.ident.to_token_stream(),
+            GenericParam::Const(param) => param.ident.to_token_stream(),
+        });
+        quote! { <#lifetime #(, #params)*> }
+    }
+
+    /// Get the predicates in the `where` clause of the input struct, to be
+    /// used in generated code adding its own predicates.
+    pub(crate) fn where_predicates(&self) -> Vec<&WherePredicate> {
+        match &self.generics.where_clause {
+            Some(where_clause) => where_clause.predicates.iter().collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Get the names of all fields in the struct, *i.e.* the identifier of
+    /// the fields for structs with named fields and the index of the fields
+    /// for tuple structs. The generated types use the same names.
+    pub(crate) fn fields_names(&self) -> Vec<Member> {
+        self.fields.iter().enumerate().map(|(i, field)| match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(i.into()),
+        }).collect()
+    }
+
+    /// Generate an expression creating an instance of the input struct, using
+    /// `values` for the fields stored in the generated types (in the same
+    /// order as `fields`) and re-creating the value of skipped fields.
+    pub(crate) fn construct<V: ToTokens>(&self, values: impl IntoIterator<Item = V>) -> TokenStream {
+        let name = &self.name;
+        let original_fields_names = &self.original_fields_names;
+        let values = values.into_iter();
+        let skipped_names = self.skipped_fields.iter().map(|field| &field.name);
+        let skipped_defaults = self.skipped_fields.iter().map(|field| &field.default);
+        quote! {
+            #name {
+                #( #original_fields_names: #values, )*
+                #( #skipped_names: #skipped_defaults, )*
+            }
+        }
+    }
+
+    /// Generate code dropping the skipped fields of `value`. This is used
+    /// when the other fields of `value` are moved out with `ptr::read`
+    /// before calling `mem::forget(value)`, and must not be used inside
+    /// another unsafe block.
+    pub(crate) fn drop_skipped_fields(&self, value: &TokenStream) -> TokenStream {
+        let skipped_names = self.skipped_fields.iter().map(|field| &field.name);
+        quote! {
+            #( ::std::mem::drop(unsafe { ::std::ptr::read(&#value.#skipped_names) }); )*
+        }
+    }
+
+    /// Get the name of the field storing the `marker` in the generated
+    /// types, after all the other fields.
+    fn marker_name(&self) -> Member {
+        if self.is_tuple {
+            Member::Unnamed(self.fields.len().into())
+        } else {
+            Member::Named(syn::Ident::new("__soa_marker", Span::call_site()))
+        }
+    }
+
+    /// Generate the initializer of the `marker` field when constructing one
+    /// of the generated types declared with `struct_body`, to be inserted
+    /// after the initializers of all the other fields. This is empty if there
+    /// is no marker.
+    pub(crate) fn marker_init(&self) -> TokenStream {
+        if self.marker.is_none() {
+            return quote! {};
+        }
+        let marker_name = self.marker_name();
+        quote! { #marker_name: ::std::marker::PhantomData, }
+    }
+
+    /// Generate the body of the declaration of a generated struct, with one
+    /// field for each field in the input struct, using `docs` and `types` for
+    /// the corresponding field, and the additional attributes selected by
+    /// `attrs`. The generated struct is a tuple struct if the input struct is
+    /// one, and gets an additional hidden field for the `marker`.
+    pub(crate) fn struct_body(
+        &self,
+        where_clause: Option<&WhereClause>,
+        docs: &[TokenStream],
+        attrs: fn(&FieldAttributes) -> &Vec<Meta>,
+        types: &[TokenStream],
+    ) -> TokenStream {
+        let attrs = self.field_attrs.iter().map(|field_attrs| {
+            let attrs = attrs(field_attrs);
+            quote! { #(#[#attrs])* }
+        }).collect::<Vec<_>>();
+
+        let marker = self.marker.as_ref().map(|marker| {
+            if self.is_tuple {
+                quote! { #[doc(hidden)] pub #marker, }
+            } else {
+                let marker_name = self.marker_name();
+                quote! { #[doc(hidden)] pub #marker_name: #marker, }
+            }
+        });
+
+        if self.is_tuple {
+            quote! {
+                ( #( #docs #attrs pub #types, )* #marker ) #where_clause ;
+            }
+        } else {
+            let fields_names = self.fields_names();
+            quote! {
+                #where_clause { #( #docs #attrs pub #fields_names: #types, )* #marker }
+            }
         }
     }
 
     /// Map over all fields in the struct, calling the first function if the
     /// field is a nested struct of array, the second function otherwise
     pub(crate) fn map_fields_nested_or<'a, A, B>(&'a self, nested: A, not_nested: B) -> impl TokenStreamIterator + 'a
-        where A: Fn(&syn::Ident, &syn::Type) -> TokenStream + 'a,
-              B: Fn(&syn::Ident, &syn::Type) -> TokenStream + 'a,
+        where A: Fn(&Member, &syn::Type) -> TokenStream + 'a,
+              B: Fn(&Member, &syn::Type) -> TokenStream + 'a,
     {
-        self.fields.iter().zip(self.field_is_nested.iter()).map(move |(field, &is_nested)| {
+        self.fields_names().into_iter().zip(&self.fields).zip(&self.field_is_nested).map(move |((name, field), &is_nested)| {
             if is_nested {
-                nested(field.ident.as_ref().expect("missing ident"), &field.ty)
+                nested(&name, &field.ty)
             } else {
-                not_nested(field.ident.as_ref().expect("missing ident"), &field.ty)
+                not_nested(&name, &field.ty)
             }
         })
     }
@@ -218,4 +884,26 @@ mod tests {
             quote!{(#current, #next)}
         }).to_string(), "((a , b) , c)");
     }
+
+    #[test]
+    fn forward_field_docs() {
+        let ast: syn::DeriveInput = syn::parse_quote! {
+            struct Particle {
+                /// Mass of the particle
+                #[soa_field_attr(Vec, serde(rename = "m"))]
+                mass: f64,
+                name: String,
+            }
+        };
+        let input = Input::new(ast).unwrap();
+
+        let mass = &input.field_attrs[0];
+        for attrs in [&mass.vec, &mass.slice, &mass.slice_mut, &mass.ref_, &mass.ref_mut, &mass.ptr, &mass.ptr_mut] {
+            let attrs = attrs.iter().map(|attr| attr.to_token_stream().to_string()).collect::<Vec<_>>();
+            assert_eq!(attrs[..2], ["doc = \"\"", "doc = r\" Mass of the particle\""]);
+        }
+        assert_eq!(mass.vec.len(), 3);
+        assert_eq!(mass.slice.len(), 2);
+        assert!(input.field_attrs[1].vec.is_empty());
+    }
 }
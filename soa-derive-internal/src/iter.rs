@@ -1,26 +1,47 @@
-use proc_macro2::TokenStream;
+use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
+use syn::parse_quote;
 
 use crate::input::{Input, TokenStreamIterator};
 use crate::names;
 
 pub fn derive(input: &Input) -> TokenStream {
+    let krate = &input.attrs.crate_path;
     let name = &input.name;
     let visibility = &input.visibility;
-    let vec_name = names::vec_name(&input.name);
-    let slice_name = names::slice_name(name);
-    let slice_mut_name = names::slice_mut_name(&input.name);
-    let ref_name = names::ref_name(&input.name);
-    let ref_mut_name = names::ref_mut_name(&input.name);
-    let iter_name = names::iter_name(&input.name);
-    let iter_mut_name = names::iter_mut_name(&input.name);
+    let vec_name = &input.attrs.names.vec;
+    let slice_name = &input.attrs.names.slice;
+    let slice_mut_name = &input.attrs.names.slice_mut;
+    let ref_name = &input.attrs.names.ref_;
+    let ref_mut_name = &input.attrs.names.ref_mut;
+    let iter_name = &input.attrs.names.iter;
+    let iter_mut_name = &input.attrs.names.iter_mut;
+    let into_iter_name = &input.attrs.names.into_iter;
+
+    let generics = input.generics_with_lifetime("'a");
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let ty_generics_anon = input.ty_generics_with_lifetime("'_");
+    let mut generics_b = input.generics_with_lifetime("'b");
+    generics_b.params.insert(0, parse_quote! { 'a });
+    let (impl_generics_b, _, _) = generics_b.split_for_impl();
+    let ty_generics_b = input.ty_generics_with_lifetime("'b");
+    let (owned_impl_generics, owned_ty_generics, owned_where_clause) = input.generics.split_for_impl();
+    let where_predicates = input.where_predicates();
 
     let doc_url = format!("[`{0}`](struct.{0}.html)", name);
     let ref_doc_url = format!("[`{0}`](struct.{0}.html)", ref_name);
     let ref_mut_doc_url = format!("[`{0}`](struct.{0}.html)", ref_mut_name);
+    let vec_doc_url = format!("[`{0}`](struct.{0}.html)", vec_name);
 
-    let fields_names = &input.fields.iter()
-        .map(|field| field.ident.clone().unwrap())
+    let fields_names = &input.fields_names();
+    let marker = input.marker_init();
+    // the iterators wrapping the fields iterators also need the marker
+    let marker_field = input.marker.as_ref().map(|marker| quote! { , #marker });
+    let marker_value = input.marker.as_ref().map(|_| quote! { , ::std::marker::PhantomData });
+
+    let fields_names_hygienic = &input.fields.iter()
+        .enumerate()
+        .map(|(i, _)| Ident::new(&format!("___soa_derive_private_{}", i), Span::call_site()))
         .collect::<Vec<_>>();
 
     let fields_types = &input.fields.iter()
@@ -28,19 +49,40 @@ pub fn derive(input: &Input) -> TokenStream {
         .collect::<Vec<_>>();
 
     let iter_type = input.map_fields_nested_or(
-        |_, field_type| quote! { <#field_type as soa_derive::SoAIter<'a>>::Iter },
+        |_, field_type| quote! { <#field_type as #krate::SoAIter<'a>>::Iter },
         |_, field_type| quote! { ::std::slice::Iter<'a, #field_type> },
     ).concat_by(
         |seq, next| { quote! { ::std::iter::Zip<#seq, #next> } }
     );
 
     let iter_mut_type = input.map_fields_nested_or(
-        |_, field_type| quote! { <#field_type as soa_derive::SoAIter<'a>>::IterMut },
+        |_, field_type| quote! { <#field_type as #krate::SoAIter<'a>>::IterMut },
         |_, field_type| quote! { ::std::slice::IterMut<'a, #field_type> },
     ).concat_by(
         |seq, next| { quote! { ::std::iter::Zip<#seq, #next> } }
     );
 
+    let vec_into_iter_type = input.map_fields_nested_or(
+        |_, field_type| {
+            let into_iter_type = names::nested_type(field_type, names::into_iter_name, None);
+            quote! { #into_iter_type }
+        },
+        |_, field_type| quote! { ::std::vec::IntoIter<#field_type> },
+    ).concat_by(
+        |seq, next| { quote! { ::std::iter::Zip<#seq, #next> } }
+    );
+
+    // the vector implements Drop, so we need to take the fields out of it
+    let create_vec_into_iter = fields_names.iter().fold(None, |seq, ident| {
+        if let Some(seq) = seq {
+            Some(quote! { #seq.zip(::std::mem::take(&mut self.#ident).into_iter()) })
+        } else {
+            Some(quote! { ::std::mem::take(&mut self.#ident).into_iter() })
+        }
+    }).expect("should be Some");
+
+    let construct_hygienic = input.construct(fields_names_hygienic);
+
     let create_into_iter = input.map_fields_nested_or(
         |ident, _| quote! { self.#ident.into_iter() },
         |ident, _| quote! { self.#ident.iter() },
@@ -55,7 +97,7 @@ pub fn derive(input: &Input) -> TokenStream {
         |seq, next| { quote! { #seq.zip(#next) } }
     );
 
-    let iter_pat = fields_names.iter().fold(None, |seq, ident| {
+    let iter_pat = fields_names_hygienic.iter().fold(None, |seq, ident| {
         if let Some(seq) = seq {
             Some(quote! { (#seq, #ident) })
         } else {
@@ -83,16 +125,17 @@ pub fn derive(input: &Input) -> TokenStream {
         /// Iterator over
         #[doc = #doc_url]
         #[allow(missing_debug_implementations)]
-        #visibility struct #iter_name<'a>(#iter_type);
+        #visibility struct #iter_name #impl_generics (#iter_type #marker_field) #where_clause;
 
-        impl<'a> Iterator for #iter_name<'a> {
-            type Item = #ref_name<'a>;
+        impl #impl_generics Iterator for #iter_name #ty_generics #where_clause {
+            type Item = #ref_name #ty_generics;
 
             #[inline]
-            fn next(&mut self) -> Option<#ref_name<'a>> {
+            fn next(&mut self) -> Option<#ref_name #ty_generics> {
                 self.0.next().and_then(|#iter_pat|
                     Some(#ref_name{
-                        #(#fields_names,)*
+                        #(#fields_names: #fields_names_hygienic,)*
+                        #marker
                     })
                 )
             }
@@ -103,61 +146,63 @@ pub fn derive(input: &Input) -> TokenStream {
             }
         }
 
-        impl<'a> DoubleEndedIterator for #iter_name<'a> {
+        impl #impl_generics DoubleEndedIterator for #iter_name #ty_generics #where_clause {
             #[inline]
-            fn next_back(&mut self) -> Option<#ref_name<'a>> {
+            fn next_back(&mut self) -> Option<#ref_name #ty_generics> {
                 self.0.next_back().and_then(|#iter_pat|
                     Some(#ref_name{
-                        #(#fields_names,)*
+                        #(#fields_names: #fields_names_hygienic,)*
+                        #marker
                     })
                 )
             }
         }
 
-        impl<'a> ExactSizeIterator for #iter_name<'a> {
+        impl #impl_generics ExactSizeIterator for #iter_name #ty_generics #where_clause {
             fn len(&self) -> usize {
                 self.0.len()
             }
         }
 
-        impl #vec_name {
+        impl #owned_impl_generics #vec_name #owned_ty_generics #owned_where_clause {
             /// Get an iterator over the
             #[doc = #ref_doc_url]
             /// in this vector
-            pub fn iter(&self) -> #iter_name {
+            pub fn iter(&self) -> #iter_name #ty_generics_anon {
                 self.as_slice().into_iter()
             }
         }
 
-        impl<'a> #slice_name<'a> {
+        impl #impl_generics #slice_name #ty_generics #where_clause {
             /// Get an iterator over the
             #[doc = #ref_doc_url]
             /// in this slice.
-            pub fn iter(&self) -> #iter_name {
-                #iter_name(#create_iter)
+            pub fn iter(&self) -> #iter_name #ty_generics_anon {
+                #iter_name(#create_iter #marker_value)
             }
 
             /// Get an iterator over the
             #[doc = #ref_doc_url]
             /// in this slice.
-            pub fn into_iter(self) -> #iter_name<'a> {
-                #iter_name(#create_into_iter)
+            pub fn into_iter(self) -> #iter_name #ty_generics {
+                #iter_name(#create_into_iter #marker_value)
             }
         }
 
         /// Mutable iterator over
         #[doc = #doc_url]
         #[allow(missing_debug_implementations)]
-        #visibility struct #iter_mut_name<'a>(#iter_mut_type);
+        #visibility struct #iter_mut_name #impl_generics (#iter_mut_type #marker_field) #where_clause;
 
-        impl<'a> Iterator for #iter_mut_name<'a> {
-            type Item = #ref_mut_name<'a>;
+        impl #impl_generics Iterator for #iter_mut_name #ty_generics #where_clause {
+            type Item = #ref_mut_name #ty_generics;
 
             #[inline]
-            fn next(&mut self) -> Option<#ref_mut_name<'a>> {
+            fn next(&mut self) -> Option<#ref_mut_name #ty_generics> {
                 self.0.next().and_then(|#iter_pat|
                     Some(#ref_mut_name{
-                        #(#fields_names,)*
+                        #(#fields_names: #fields_names_hygienic,)*
+                        #marker
                     })
                 )
             }
@@ -168,73 +213,74 @@ pub fn derive(input: &Input) -> TokenStream {
             }
         }
 
-        impl<'a> DoubleEndedIterator for #iter_mut_name<'a> {
+        impl #impl_generics DoubleEndedIterator for #iter_mut_name #ty_generics #where_clause {
             #[inline]
-            fn next_back(&mut self) -> Option<#ref_mut_name<'a>> {
+            fn next_back(&mut self) -> Option<#ref_mut_name #ty_generics> {
                 self.0.next_back().and_then(|#iter_pat|
                     Some(#ref_mut_name{
-                        #(#fields_names,)*
+                        #(#fields_names: #fields_names_hygienic,)*
+                        #marker
                     })
                 )
             }
         }
-        impl<'a> ExactSizeIterator for #iter_mut_name<'a> {
+        impl #impl_generics ExactSizeIterator for #iter_mut_name #ty_generics #where_clause {
             fn len(&self) -> usize {
                 self.0.len()
             }
         }
 
-        impl #vec_name {
+        impl #owned_impl_generics #vec_name #owned_ty_generics #owned_where_clause {
             /// Get a mutable iterator over the
             #[doc = #ref_mut_doc_url]
             /// in this vector
-            pub fn iter_mut(&mut self) -> #iter_mut_name {
+            pub fn iter_mut(&mut self) -> #iter_mut_name #ty_generics_anon {
                 self.as_mut_slice().into_iter()
             }
         }
 
-        impl<'a> #slice_mut_name<'a> {
+        impl #impl_generics #slice_mut_name #ty_generics #where_clause {
             /// Get an iterator over the
             #[doc = #ref_doc_url]
             /// in this vector
-            pub fn iter(&mut self) -> #iter_name {
+            pub fn iter(&mut self) -> #iter_name #ty_generics_anon {
                 self.as_ref().into_iter()
             }
 
             /// Get a mutable iterator over the
             #[doc = #ref_mut_doc_url]
             /// in this vector
-            pub fn iter_mut(&mut self) -> #iter_mut_name {
-                #iter_mut_name(#create_iter_mut)
+            pub fn iter_mut(&mut self) -> #iter_mut_name #ty_generics_anon {
+                #iter_mut_name(#create_iter_mut #marker_value)
             }
 
             /// Get a mutable iterator over the
             #[doc = #ref_mut_doc_url]
             /// in this vector
-            pub fn into_iter(self) -> #iter_mut_name<'a> {
-                #iter_mut_name(#create_mut_into_iter)
+            pub fn into_iter(self) -> #iter_mut_name #ty_generics {
+                #iter_mut_name(#create_mut_into_iter #marker_value)
             }
         }
 
-        impl<'a> soa_derive::SoAIter<'a> for #name {
-            type Ref = #ref_name<'a>;
-            type RefMut = #ref_mut_name<'a>;
-            type Iter = #iter_name<'a>;
-            type IterMut = #iter_mut_name<'a>;
+        impl #impl_generics #krate::SoAIter<'a> for #name #owned_ty_generics #where_clause {
+            type Ref = #ref_name #ty_generics;
+            type RefMut = #ref_mut_name #ty_generics;
+            type Iter = #iter_name #ty_generics;
+            type IterMut = #iter_mut_name #ty_generics;
         }
 
-        impl<'a> IntoIterator for #slice_name<'a> {
-            type Item = #ref_name<'a>;
-            type IntoIter = #iter_name<'a>;
+        impl #impl_generics IntoIterator for #slice_name #ty_generics #where_clause {
+            type Item = #ref_name #ty_generics;
+            type IntoIter = #iter_name #ty_generics;
 
             fn into_iter(self) -> Self::IntoIter {
-                #iter_name(#create_into_iter)
+                #iter_name(#create_into_iter #marker_value)
             }
         }
 
 
-        impl std::iter::FromIterator<#name> for #vec_name {
-            fn from_iter<T: IntoIterator<Item=#name>>(iter: T) -> Self {
+        impl #owned_impl_generics std::iter::FromIterator<#name #owned_ty_generics> for #vec_name #owned_ty_generics #owned_where_clause {
+            fn from_iter<T: IntoIterator<Item=#name #owned_ty_generics>>(iter: T) -> Self {
                 let mut result = #vec_name::new();
                 for element in iter {
                     result.push(element);
@@ -243,61 +289,119 @@ pub fn derive(input: &Input) -> TokenStream {
             }
         }
 
-        impl<'a, 'b> IntoIterator for &'a #slice_name<'b> {
-            type Item = #ref_name<'a>;
-            type IntoIter = #iter_name<'a>;
+        impl #impl_generics_b IntoIterator for &'a #slice_name #ty_generics_b #where_clause {
+            type Item = #ref_name #ty_generics;
+            type IntoIter = #iter_name #ty_generics;
 
             fn into_iter(self) -> Self::IntoIter {
-                #iter_name(#create_into_iter)
+                #iter_name(#create_into_iter #marker_value)
             }
         }
 
-        impl<'a> IntoIterator for &'a #vec_name {
-            type Item = #ref_name<'a>;
-            type IntoIter = #iter_name<'a>;
+        impl #impl_generics IntoIterator for &'a #vec_name #owned_ty_generics #where_clause {
+            type Item = #ref_name #ty_generics;
+            type IntoIter = #iter_name #ty_generics;
 
             fn into_iter(self) -> Self::IntoIter {
                 self.as_slice().into_iter()
             }
         }
 
-        impl<'a> IntoIterator for #slice_mut_name<'a> {
-            type Item = #ref_mut_name<'a>;
-            type IntoIter = #iter_mut_name<'a>;
+        impl #impl_generics IntoIterator for #slice_mut_name #ty_generics #where_clause {
+            type Item = #ref_mut_name #ty_generics;
+            type IntoIter = #iter_mut_name #ty_generics;
 
             fn into_iter(self) -> Self::IntoIter {
-                #iter_mut_name(#create_mut_into_iter)
+                #iter_mut_name(#create_mut_into_iter #marker_value)
             }
         }
 
-        impl<'a> IntoIterator for &'a mut #vec_name {
-            type Item = #ref_mut_name<'a>;
-            type IntoIter = #iter_mut_name<'a>;
+        impl #impl_generics IntoIterator for &'a mut #vec_name #owned_ty_generics #where_clause {
+            type Item = #ref_mut_name #ty_generics;
+            type IntoIter = #iter_mut_name #ty_generics;
 
             fn into_iter(self) -> Self::IntoIter {
                 self.as_mut_slice().into_iter()
             }
         }
 
-        impl Extend<#name> for #vec_name {
-            fn extend<I: IntoIterator<Item = #name>>(&mut self, iter: I) {
+        impl #owned_impl_generics Extend<#name #owned_ty_generics> for #vec_name #owned_ty_generics #owned_where_clause {
+            fn extend<I: IntoIterator<Item = #name #owned_ty_generics>>(&mut self, iter: I) {
                 for item in iter {
                     self.push(item)
                 }
             }
         }
 
-        impl<'a> Extend<#ref_name<'a>> for #vec_name
+        impl #impl_generics Extend<#ref_name #ty_generics> for #vec_name #owned_ty_generics
             // only expose if all fields are Clone
             // https://github.com/rust-lang/rust/issues/48214#issuecomment-1150463333
-            where #( for<'b> #fields_types: Clone, )*
+            where
+                #( #where_predicates, )*
+                #( for<'b> #fields_types: Clone, )*
         {
-            fn extend<I: IntoIterator<Item = #ref_name<'a>>>(&mut self, iter: I) {
-                <Self as Extend<#name>>::extend(self, iter.into_iter().map(|item| item.to_owned()))
+            fn extend<I: IntoIterator<Item = #ref_name #ty_generics>>(&mut self, iter: I) {
+                <Self as Extend<#name #owned_ty_generics>>::extend(self, iter.into_iter().map(|item| item.to_owned()))
+            }
+        }
+
+        impl #impl_generics #krate::IntoSoAIter<'a, #name #owned_ty_generics> for #slice_name #ty_generics #where_clause {}
+
+        /// Owning iterator over
+        #[doc = #doc_url]
+        /// , created by calling `into_iter()` on a
+        #[doc = #vec_doc_url]
+        #[allow(missing_debug_implementations)]
+        #visibility struct #into_iter_name #owned_impl_generics (#vec_into_iter_type #marker_field) #owned_where_clause;
+
+        impl #owned_impl_generics Iterator for #into_iter_name #owned_ty_generics #owned_where_clause {
+            type Item = #name #owned_ty_generics;
+
+            #[inline]
+            fn next(&mut self) -> Option<#name #owned_ty_generics> {
+                self.0.next().map(|#iter_pat| #construct_hygienic)
+            }
+
+            #[inline]
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.0.size_hint()
             }
         }
 
-        impl<'a> ::soa_derive::IntoSoAIter<'a, #name> for #slice_name<'a> {}
+        impl #owned_impl_generics DoubleEndedIterator for #into_iter_name #owned_ty_generics #owned_where_clause {
+            #[inline]
+            fn next_back(&mut self) -> Option<#name #owned_ty_generics> {
+                self.0.next_back().map(|#iter_pat| #construct_hygienic)
+            }
+        }
+
+        impl #owned_impl_generics ExactSizeIterator for #into_iter_name #owned_ty_generics #owned_where_clause {
+            fn len(&self) -> usize {
+                self.0.len()
+            }
+        }
+
+        impl #owned_impl_generics ::std::iter::FusedIterator for #into_iter_name #owned_ty_generics #owned_where_clause {}
+
+        #[allow(clippy::drop_non_drop)]
+        impl #owned_impl_generics Drop for #into_iter_name #owned_ty_generics #owned_where_clause {
+            fn drop(&mut self) {
+                // similar to the vector, re-create the remaining values to
+                // make sure their Drop implementation runs
+                for value in self {
+                    ::std::mem::drop(value);
+                }
+            }
+        }
+
+        impl #owned_impl_generics IntoIterator for #vec_name #owned_ty_generics #owned_where_clause {
+            type Item = #name #owned_ty_generics;
+            type IntoIter = #into_iter_name #owned_ty_generics;
+
+            fn into_iter(mut self) -> Self::IntoIter {
+                #into_iter_name(#create_vec_into_iter #marker_value)
+            }
+        }
     };
 
     return generated;
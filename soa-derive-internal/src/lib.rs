@@ -9,22 +9,52 @@ extern crate proc_macro;
 use proc_macro2::TokenStream;
 use quote::TokenStreamExt;
 
+#[cfg(feature = "arrow")]
+mod arrow;
+mod chunks;
+mod columns;
+mod enums;
 mod index;
 #[macro_use]
 mod input;
 mod iter;
+#[cfg(feature = "ndarray")]
+mod ndarray;
+#[cfg(feature = "rayon")]
+mod par;
+#[cfg(feature = "polars")]
+mod polars;
 mod ptr;
 mod refs;
+mod selection;
 mod slice;
 mod vec;
 mod generic;
 
 pub(crate) mod names;
 
-#[proc_macro_derive(StructOfArray, attributes(soa_derive, soa_attr, nested_soa))]
+#[proc_macro_derive(StructOfArray, attributes(soa_derive, soa_attr, soa_field_attr, nested_soa, soa))]
 pub fn soa_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let ast = syn::parse(input).expect("Failed to parse derive macro for StructOfArray");
-    let input = input::Input::new(ast);
+    let ast = match syn::parse::<syn::DeriveInput>(input) {
+        Ok(ast) => ast,
+        Err(error) => return error.to_compile_error().into(),
+    };
+
+    let generated = match &ast.data {
+        syn::Data::Enum(data) => enums::derive(&ast, data),
+        _ => derive_struct(ast),
+    };
+
+    match generated {
+        Ok(generated) => generated.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+/// Generate all the code for a struct, this is also used to generate the
+/// storage of the different variants of an enum.
+pub(crate) fn derive_struct(ast: syn::DeriveInput) -> syn::Result<TokenStream> {
+    let input = input::Input::new(ast)?;
 
     let mut generated = TokenStream::new();
     generated.append_all(vec::derive(&input));
@@ -34,24 +64,41 @@ pub fn soa_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     generated.append_all(slice::derive_mut(&input));
     generated.append_all(index::derive(&input));
     generated.append_all(iter::derive(&input));
+    generated.append_all(chunks::derive(&input));
+    generated.append_all(columns::derive(&input));
+    generated.append_all(selection::derive(&input));
+    #[cfg(feature = "rayon")]
+    generated.append_all(par::derive(&input));
+    #[cfg(feature = "arrow")]
+    if input.attrs.arrow {
+        generated.append_all(arrow::derive(&input));
+    }
+    #[cfg(feature = "polars")]
+    if input.attrs.polars {
+        generated.append_all(polars::derive(&input));
+    }
+    #[cfg(feature = "ndarray")]
+    generated.append_all(ndarray::derive(&input));
     generated.append_all(derive_trait(&input));
 
     generated.append_all(generic::derive_slice(&input));
     generated.append_all(generic::derive_slice_mut(&input));
     generated.append_all(generic::derive_vec(&input));
-    generated.into()
+    return Ok(generated);
 }
 
 use crate::input::Input;
 use quote::quote;
 
 fn derive_trait(input: &Input) -> TokenStream {
+    let krate = &input.attrs.crate_path;
     let name = &input.name;
-    let vec_name = names::vec_name(name);
+    let vec_name = &input.attrs.names.vec;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
 
     quote! {
-        impl soa_derive::StructOfArray for #name {
-            type Type = #vec_name;
+        impl #impl_generics #krate::StructOfArray for #name #ty_generics #where_clause {
+            type Type = #vec_name #ty_generics;
         }
     }
 }
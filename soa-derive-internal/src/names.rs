@@ -1,48 +1,302 @@
 use proc_macro2::Span;
 use quote::ToTokens;
-use syn::Ident;
+use syn::ext::IdentExt;
+use syn::{parse_quote, GenericArgument, Ident, Lifetime, Member, PathArguments, Type, TypePath};
+
+/// Names of all the types generated for a struct, which default to the
+/// functions below and can be changed with `#[soa(vec = "...", ...)]`
+pub struct Names {
+    pub vec: Ident,
+    pub slice: Ident,
+    pub slice_mut: Ident,
+    pub ref_: Ident,
+    pub ref_mut: Ident,
+    pub ptr: Ident,
+    pub ptr_mut: Ident,
+    pub iter: Ident,
+    pub iter_mut: Ident,
+    pub into_iter: Ident,
+    pub drain: Ident,
+    pub splice: Ident,
+    pub extract_if: Ident,
+    pub chunks: Ident,
+    pub chunks_exact: Ident,
+    pub chunks_mut: Ident,
+    pub rchunks: Ident,
+    pub windows: Ident,
+    pub column: Ident,
+    pub selection: Ident,
+    pub par_iter: Ident,
+    pub par_iter_mut: Ident,
+}
+
+impl Names {
+    /// Get the default names for the types associated with `name`
+    pub fn new(name: &Ident) -> Names {
+        Names {
+            vec: vec_name(name),
+            slice: slice_name(name),
+            slice_mut: slice_mut_name(name),
+            ref_: ref_name(name),
+            ref_mut: ref_mut_name(name),
+            ptr: ptr_name(name),
+            ptr_mut: ptr_mut_name(name),
+            iter: iter_name(name),
+            iter_mut: iter_mut_name(name),
+            into_iter: into_iter_name(name),
+            drain: drain_name(name),
+            splice: splice_name(name),
+            extract_if: extract_if_name(name),
+            chunks: chunks_name(name),
+            chunks_exact: chunks_exact_name(name),
+            chunks_mut: chunks_mut_name(name),
+            rchunks: rchunks_name(name),
+            windows: windows_name(name),
+            column: column_name(name),
+            selection: selection_name(name),
+            par_iter: par_iter_name(name),
+            par_iter_mut: par_iter_mut_name(name),
+        }
+    }
+
+    /// Get all the names in this set
+    pub fn all(&self) -> Vec<&Ident> {
+        vec![
+            &self.vec, &self.slice, &self.slice_mut, &self.ref_, &self.ref_mut,
+            &self.ptr, &self.ptr_mut, &self.iter, &self.iter_mut, &self.into_iter,
+            &self.drain, &self.splice, &self.extract_if, &self.chunks,
+            &self.chunks_exact, &self.chunks_mut, &self.rchunks, &self.windows,
+            &self.column, &self.selection, &self.par_iter, &self.par_iter_mut,
+        ]
+    }
+}
 
 /// Get the ident for the `Vec` type associated with `name`
-pub fn vec_name(name: impl ToTokens) -> Ident {
-    Ident::new(&format!("{}Vec", name.to_token_stream()), Span::call_site())
+pub fn vec_name(name: &Ident) -> Ident {
+    Ident::new(&format!("{}Vec", name), Span::call_site())
 }
 
 /// Get the ident for the slice type associated with `name`
-pub fn slice_name(name: impl ToTokens) -> Ident {
-    Ident::new(&format!("{}Slice", name.to_token_stream()), Span::call_site())
+pub fn slice_name(name: &Ident) -> Ident {
+    Ident::new(&format!("{}Slice", name), Span::call_site())
 }
 
 /// Get the ident for the mutable slice type associated with `name`
-pub fn slice_mut_name(name: impl ToTokens) -> Ident {
-    Ident::new(&format!("{}SliceMut", name.to_token_stream()), Span::call_site())
+pub fn slice_mut_name(name: &Ident) -> Ident {
+    Ident::new(&format!("{}SliceMut", name), Span::call_site())
 }
 
 /// Get the ident for the reference type associated with `name`
-pub fn ref_name(name: impl ToTokens) -> Ident {
-    Ident::new(&format!("{}Ref", name.to_token_stream()), Span::call_site())
+pub fn ref_name(name: &Ident) -> Ident {
+    Ident::new(&format!("{}Ref", name), Span::call_site())
 }
 
 /// Get the ident for the mutable reference type associated with `name`
-pub fn ref_mut_name(name: impl ToTokens) -> Ident {
-    Ident::new(&format!("{}RefMut", name.to_token_stream()), Span::call_site())
+pub fn ref_mut_name(name: &Ident) -> Ident {
+    Ident::new(&format!("{}RefMut", name), Span::call_site())
 }
 
 /// Get the ident for the iterator type associated with `name`
-pub fn iter_name(name: impl ToTokens) -> Ident {
-    Ident::new(&format!("{}Iter", name.to_token_stream()), Span::call_site())
+pub fn iter_name(name: &Ident) -> Ident {
+    Ident::new(&format!("{}Iter", name), Span::call_site())
 }
 
 /// Get the ident for the mutable iterator type associated with `name`
-pub fn iter_mut_name(name: impl ToTokens) -> Ident {
-    Ident::new(&format!("{}IterMut", name.to_token_stream()), Span::call_site())
+pub fn iter_mut_name(name: &Ident) -> Ident {
+    Ident::new(&format!("{}IterMut", name), Span::call_site())
+}
+
+/// Get the ident for the owning iterator type associated with `name`
+pub fn into_iter_name(name: &Ident) -> Ident {
+    Ident::new(&format!("{}IntoIter", name), Span::call_site())
+}
+
+/// Get the ident for the draining iterator type associated with `name`
+pub fn drain_name(name: &Ident) -> Ident {
+    Ident::new(&format!("{}Drain", name), Span::call_site())
+}
+
+/// Get the ident for the splicing iterator type associated with `name`
+pub fn splice_name(name: &Ident) -> Ident {
+    Ident::new(&format!("{}Splice", name), Span::call_site())
+}
+
+/// Get the ident for the filtering removal iterator type associated with
+/// `name`
+pub fn extract_if_name(name: &Ident) -> Ident {
+    Ident::new(&format!("{}ExtractIf", name), Span::call_site())
+}
+
+/// Get the ident for the chunks iterator type associated with `name`
+pub fn chunks_name(name: &Ident) -> Ident {
+    Ident::new(&format!("{}Chunks", name), Span::call_site())
+}
+
+/// Get the ident for the exact chunks iterator type associated with `name`
+pub fn chunks_exact_name(name: &Ident) -> Ident {
+    Ident::new(&format!("{}ChunksExact", name), Span::call_site())
+}
+
+/// Get the ident for the mutable chunks iterator type associated with `name`
+pub fn chunks_mut_name(name: &Ident) -> Ident {
+    Ident::new(&format!("{}ChunksMut", name), Span::call_site())
+}
+
+/// Get the ident for the reversed chunks iterator type associated with
+/// `name`
+pub fn rchunks_name(name: &Ident) -> Ident {
+    Ident::new(&format!("{}RChunks", name), Span::call_site())
+}
+
+/// Get the ident for the windows iterator type associated with `name`
+pub fn windows_name(name: &Ident) -> Ident {
+    Ident::new(&format!("{}Windows", name), Span::call_site())
+}
+
+/// Get the ident for the column selector type associated with `name`
+pub fn column_name(name: &Ident) -> Ident {
+    Ident::new(&format!("{}Column", name), Span::call_site())
+}
+
+/// Get the ident for the selection type associated with `name`
+pub fn selection_name(name: &Ident) -> Ident {
+    Ident::new(&format!("{}Selection", name), Span::call_site())
+}
+
+/// Get the ident for the parallel iterator type associated with `name`
+pub fn par_iter_name(name: &Ident) -> Ident {
+    Ident::new(&format!("{}ParIter", name), Span::call_site())
+}
+
+/// Get the ident for the mutable parallel iterator type associated with
+/// `name`
+pub fn par_iter_mut_name(name: &Ident) -> Ident {
+    Ident::new(&format!("{}ParIterMut", name), Span::call_site())
 }
 
 /// Get the ident for the pointer type associated with `name`
-pub fn ptr_name(name: impl ToTokens) -> Ident {
-    Ident::new(&format!("{}Ptr", name.to_token_stream()), Span::call_site())
+pub fn ptr_name(name: &Ident) -> Ident {
+    Ident::new(&format!("{}Ptr", name), Span::call_site())
 }
 
 /// Get the ident for the mutable pointer type associated with `name`
-pub fn ptr_mut_name(name: impl ToTokens) -> Ident {
-    Ident::new(&format!("{}PtrMut", name.to_token_stream()), Span::call_site())
+pub fn ptr_mut_name(name: &Ident) -> Ident {
+    Ident::new(&format!("{}PtrMut", name), Span::call_site())
+}
+
+/// Get the ident for the discriminant type associated with the enum `name`
+pub fn kind_name(name: &Ident) -> Ident {
+    Ident::new(&format!("{}Kind", name), Span::call_site())
+}
+
+/// Get the ident for the struct storing the fields of `variant` in the enum
+/// `name`
+pub fn variant_struct_name(name: &Ident, variant: &Ident) -> Ident {
+    Ident::new(&format!("{}{}", name, variant), Span::call_site())
+}
+
+/// Get the ident for the field storing `variant` in the `Vec` type associated
+/// with an enum, *i.e.* `variant` converted to snake case.
+pub fn variant_field_name(variant: &Ident) -> Ident {
+    let mut snake_case = String::new();
+    for (i, c) in variant.to_string().chars().enumerate() {
+        if c.is_uppercase() {
+            if i != 0 {
+                snake_case.push('_');
+            }
+            snake_case.extend(c.to_lowercase());
+        } else {
+            snake_case.push(c);
+        }
+    }
+
+    // use a raw identifier for keywords (`Type` => `r#type`)
+    match syn::parse_str::<Ident>(&snake_case) {
+        Ok(ident) => ident,
+        Err(_) => Ident::new_raw(&snake_case, Span::call_site()),
+    }
+}
+
+/// Get the name of the column storing `field` when converting to data frame
+/// libraries, *i.e.* the name of the field without `r#`, or `0`, `1`, ... for
+/// tuple structs.
+#[cfg(any(feature = "arrow", feature = "polars", feature = "ndarray"))]
+pub fn data_column_name(field: &Member) -> String {
+    match field {
+        Member::Named(ident) => ident.unraw().to_string(),
+        Member::Unnamed(index) => index.index.to_string(),
+    }
+}
+
+/// Get the ident of the method returning an ndarray view of `field`, *i.e.*
+/// `field_view` (or `field_view_mut` if `mutable` is true), or `field0_view`,
+/// `field1_view`, ... for tuple structs.
+#[cfg(feature = "ndarray")]
+pub fn view_method_name(field: &Member, mutable: bool) -> Ident {
+    let suffix = if mutable { "_view_mut" } else { "_view" };
+    let name = match field {
+        Member::Named(ident) => format!("{}{}", ident.unraw(), suffix),
+        Member::Unnamed(index) => format!("field{}{}", index.index, suffix),
+    };
+    return Ident::new(&name, Span::call_site());
+}
+
+/// Get the ident for the variant selecting `field` in the column selector
+/// type, *i.e.* `field` converted to camel case (`with_mushrooms` =>
+/// `WithMushrooms`), or `Field0`, `Field1`, ... for tuple structs.
+pub fn column_variant_name(field: &Member) -> Ident {
+    let ident = match field {
+        Member::Named(ident) => ident,
+        Member::Unnamed(index) => {
+            return Ident::new(&format!("Field{}", index.index), Span::call_site());
+        }
+    };
+
+    let mut camel_case = String::new();
+    let mut uppercase_next = true;
+    for c in ident.unraw().to_string().chars() {
+        if c == '_' {
+            uppercase_next = true;
+        } else if uppercase_next {
+            camel_case.extend(c.to_uppercase());
+            uppercase_next = false;
+        } else {
+            camel_case.push(c);
+        }
+    }
+
+    return Ident::new(&camel_case, Span::call_site());
+}
+
+/// Get the type generated by `name` for a `#[nested_soa]` field of type `ty`,
+/// keeping the generic arguments of `ty` and adding `lifetime` in front of
+/// them if needed. For example, using [`slice_name`] with `'a` transforms
+/// `Point<T>` into `PointSlice<'a, T>`. This always uses the default names,
+/// since custom names given to the nested struct are not visible here. The
+/// generated name keeps the span of `ty`, so that errors about missing types
+/// point to the `#[nested_soa]` field.
+pub fn nested_type(ty: &Type, name: fn(&Ident) -> Ident, lifetime: Option<&str>) -> Type {
+    let mut path = match ty {
+        Type::Path(TypePath { qself: None, path }) => path.clone(),
+        _ => panic!("#[nested_soa] fields must have a named struct type, got {}", ty.to_token_stream()),
+    };
+
+    let last = path.segments.last_mut().expect("paths have at least one segment");
+    let span = last.ident.span();
+    last.ident = name(&last.ident);
+    last.ident.set_span(span);
+    if let Some(lifetime) = lifetime {
+        let lifetime = GenericArgument::Lifetime(Lifetime::new(lifetime, Span::call_site()));
+        match &mut last.arguments {
+            PathArguments::None => {
+                last.arguments = PathArguments::AngleBracketed(parse_quote! { <#lifetime> });
+            }
+            PathArguments::AngleBracketed(arguments) => arguments.args.insert(0, lifetime),
+            PathArguments::Parenthesized(_) => {
+                panic!("#[nested_soa] fields must have a named struct type, got {}", ty.to_token_stream())
+            }
+        }
+    }
+
+    return Type::Path(TypePath { qself: None, path });
 }
@@ -0,0 +1,99 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::input::Input;
+use crate::names;
+
+pub fn derive(input: &Input) -> TokenStream {
+    let krate = &input.attrs.crate_path;
+    let slice_name = &input.attrs.names.slice;
+    let slice_mut_name = &input.attrs.names.slice_mut;
+
+    let generics = input.generics_with_lifetime("'a");
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let where_predicates = input.where_predicates();
+
+    // nested fields do not have a single column to view
+    let slice_views = input.map_fields_nested_or(
+        |_, _| quote! {},
+        |ident, field_type| {
+            let view = names::view_method_name(ident, false);
+            let doc = format!(
+                "Get an ndarray view of the `{}` column, without copying the data",
+                names::data_column_name(ident),
+            );
+            quote! {
+                #[doc = #doc]
+                pub fn #view(&self) -> #krate::ndarray::ArrayView<
+                    'a,
+                    <#field_type as #krate::ndarray::NdColumn>::Elem,
+                    <#field_type as #krate::ndarray::NdColumn>::Dim,
+                >
+                where
+                    for<'b> #field_type: #krate::ndarray::NdColumn,
+                {
+                    #krate::ndarray::NdColumn::view(self.#ident)
+                }
+            }
+        },
+    ).collect::<Vec<_>>();
+
+    let slice_mut_views = input.map_fields_nested_or(
+        |_, _| quote! {},
+        |ident, field_type| {
+            let view = names::view_method_name(ident, false);
+            let view_mut = names::view_method_name(ident, true);
+            let doc = format!(
+                "Get an ndarray view of the `{}` column, without copying the data",
+                names::data_column_name(ident),
+            );
+            let doc_mut = format!(
+                "Get a mutable ndarray view of the `{}` column, without copying the data",
+                names::data_column_name(ident),
+            );
+            quote! {
+                #[doc = #doc]
+                pub fn #view(&self) -> #krate::ndarray::ArrayView<
+                    '_,
+                    <#field_type as #krate::ndarray::NdColumn>::Elem,
+                    <#field_type as #krate::ndarray::NdColumn>::Dim,
+                >
+                where
+                    for<'b> #field_type: #krate::ndarray::NdColumn,
+                {
+                    #krate::ndarray::NdColumn::view(&*self.#ident)
+                }
+
+                #[doc = #doc_mut]
+                pub fn #view_mut(&mut self) -> #krate::ndarray::ArrayViewMut<
+                    '_,
+                    <#field_type as #krate::ndarray::NdColumn>::Elem,
+                    <#field_type as #krate::ndarray::NdColumn>::Dim,
+                >
+                where
+                    for<'b> #field_type: #krate::ndarray::NdColumn,
+                {
+                    #krate::ndarray::NdColumn::view_mut(&mut *self.#ident)
+                }
+            }
+        },
+    ).collect::<Vec<_>>();
+
+    return quote! {
+        #[allow(dead_code)]
+        impl #impl_generics #slice_name #ty_generics
+        where
+            #( #where_predicates, )*
+        {
+            #( #slice_views )*
+        }
+
+        #[allow(dead_code)]
+        impl #impl_generics #slice_mut_name #ty_generics
+        where
+            #( #where_predicates, )*
+        {
+            #( #slice_mut_views )*
+        }
+    };
+}
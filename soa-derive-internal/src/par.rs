@@ -0,0 +1,458 @@
+use proc_macro2::TokenStream;
+use quote::{quote, TokenStreamExt};
+
+use crate::input::Input;
+use crate::names;
+
+pub fn derive(input: &Input) -> TokenStream {
+    let krate = &input.attrs.crate_path;
+    let visibility = &input.visibility;
+    let vec_name = &input.attrs.names.vec;
+    let slice_name = &input.attrs.names.slice;
+    let slice_mut_name = &input.attrs.names.slice_mut;
+    let ref_name = &input.attrs.names.ref_;
+    let ref_mut_name = &input.attrs.names.ref_mut;
+    let iter_name = &input.attrs.names.iter;
+    let iter_mut_name = &input.attrs.names.iter_mut;
+    let par_iter_name = &input.attrs.names.par_iter;
+    let par_iter_mut_name = &input.attrs.names.par_iter_mut;
+
+    let generics = input.generics_with_lifetime("'a");
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let ty_generics_anon = input.ty_generics_with_lifetime("'_");
+    let ty_generics_b = input.ty_generics_with_lifetime("'b");
+    let (owned_impl_generics, owned_ty_generics, owned_where_clause) = input.generics.split_for_impl();
+    let where_predicates = input.where_predicates();
+
+    let slice_doc_url = format!("[`{0}`](struct.{0}.html)", slice_name);
+    let slice_mut_doc_url = format!("[`{0}`](struct.{0}.html)", slice_mut_name);
+    let ref_doc_url = format!("[`{0}`](struct.{0}.html)", ref_name);
+    let ref_mut_doc_url = format!("[`{0}`](struct.{0}.html)", ref_mut_name);
+    let slice_name_str = format!("[{}]", input.name);
+    let vec_name_str = format!("Vec<{}>", input.name);
+
+    let permute_bounds = input.map_fields_nested_or(
+        |_, field_type| {
+            let slice_mut_type = names::nested_type(field_type, names::slice_mut_name, Some("'b"));
+            quote! { for<'b> #slice_mut_type: #krate::SoAParallelPermute }
+        },
+        |_, field_type| quote! { for<'b> #field_type: Send },
+    ).collect::<Vec<_>>();
+
+    let retain_bounds = input.map_fields_nested_or(
+        |_, field_type| {
+            let vec_type = names::nested_type(field_type, names::vec_name, None);
+            quote! { for<'b> #vec_type: #krate::SoAParallelRetain }
+        },
+        |_, field_type| quote! { for<'b> #field_type: Send },
+    ).collect::<Vec<_>>();
+
+    // all the tasks share the same indices and cycles, which the caller of
+    // `__private_par_apply_index` guarantees to be valid for all the columns,
+    // and each one moves the values of a single column in place
+    let spawn_apply_index = input.map_fields_nested_or(
+        |ident, _| quote! {
+            let column = &mut self.#ident;
+            scope.spawn(move |_| unsafe { #krate::SoAParallelPermute::__private_par_apply_index(column, indices, cycles) });
+        },
+        |ident, _| quote! {
+            let column = &mut *self.#ident;
+            scope.spawn(move |_| unsafe { #krate::__private_apply_index_in_place(column, indices, cycles) });
+        },
+    ).collect::<Vec<_>>();
+
+    let spawn_retain_by_mask = input.map_fields_nested_or(
+        |ident, _| quote! {
+            let column = &mut self.#ident;
+            scope.spawn(move |_| #krate::SoAParallelRetain::__private_par_retain_by_mask(column, mask));
+        },
+        |ident, _| quote! {
+            let column = &mut self.#ident;
+            scope.spawn(move |_| {
+                let mut keep = mask.iter();
+                column.retain(|_| *keep.next().expect("mask has the same length as the vector"));
+            });
+        },
+    ).collect::<Vec<_>>();
+
+    let mut generated = quote! {
+        /// Parallel iterator over the
+        #[doc = #ref_doc_url]
+        /// in a
+        #[doc = #slice_doc_url]
+        /// . The work is split by splitting all the columns at the same
+        /// index.
+        #[allow(missing_debug_implementations)]
+        #visibility struct #par_iter_name #impl_generics (#slice_name #ty_generics) #where_clause;
+
+        impl #impl_generics #krate::rayon::iter::ParallelIterator for #par_iter_name #ty_generics
+        where
+            #( #where_predicates, )*
+            #slice_name #ty_generics: Send,
+            #ref_name #ty_generics: Send,
+        {
+            type Item = #ref_name #ty_generics;
+
+            fn drive_unindexed<C>(self, consumer: C) -> C::Result
+                where C: #krate::rayon::iter::plumbing::UnindexedConsumer<Self::Item>
+            {
+                #krate::rayon::iter::plumbing::bridge(self, consumer)
+            }
+
+            fn opt_len(&self) -> Option<usize> {
+                Some(self.0.len())
+            }
+        }
+
+        impl #impl_generics #krate::rayon::iter::IndexedParallelIterator for #par_iter_name #ty_generics
+        where
+            #( #where_predicates, )*
+            #slice_name #ty_generics: Send,
+            #ref_name #ty_generics: Send,
+        {
+            fn len(&self) -> usize {
+                self.0.len()
+            }
+
+            fn drive<C>(self, consumer: C) -> C::Result
+                where C: #krate::rayon::iter::plumbing::Consumer<Self::Item>
+            {
+                #krate::rayon::iter::plumbing::bridge(self, consumer)
+            }
+
+            fn with_producer<CB>(self, callback: CB) -> CB::Output
+                where CB: #krate::rayon::iter::plumbing::ProducerCallback<Self::Item>
+            {
+                struct Producer #impl_generics (#slice_name #ty_generics) #where_clause;
+
+                impl #impl_generics #krate::rayon::iter::plumbing::Producer for Producer #ty_generics
+                where
+                    #( #where_predicates, )*
+                    #slice_name #ty_generics: Send,
+                    #ref_name #ty_generics: Send,
+                {
+                    type Item = #ref_name #ty_generics;
+                    type IntoIter = #iter_name #ty_generics;
+
+                    fn into_iter(self) -> Self::IntoIter {
+                        self.0.into_iter()
+                    }
+
+                    fn split_at(self, index: usize) -> (Self, Self) {
+                        let (left, right) = self.0.split_at(index);
+                        (Producer(left), Producer(right))
+                    }
+                }
+
+                callback.callback(Producer(self.0))
+            }
+        }
+
+        /// Mutable parallel iterator over the
+        #[doc = #ref_mut_doc_url]
+        /// in a
+        #[doc = #slice_mut_doc_url]
+        /// . The work is split by splitting all the columns at the same
+        /// index.
+        #[allow(missing_debug_implementations)]
+        #visibility struct #par_iter_mut_name #impl_generics (#slice_mut_name #ty_generics) #where_clause;
+
+        impl #impl_generics #krate::rayon::iter::ParallelIterator for #par_iter_mut_name #ty_generics
+        where
+            #( #where_predicates, )*
+            #slice_mut_name #ty_generics: Send,
+            #ref_mut_name #ty_generics: Send,
+        {
+            type Item = #ref_mut_name #ty_generics;
+
+            fn drive_unindexed<C>(self, consumer: C) -> C::Result
+                where C: #krate::rayon::iter::plumbing::UnindexedConsumer<Self::Item>
+            {
+                #krate::rayon::iter::plumbing::bridge(self, consumer)
+            }
+
+            fn opt_len(&self) -> Option<usize> {
+                Some(self.0.len())
+            }
+        }
+
+        impl #impl_generics #krate::rayon::iter::IndexedParallelIterator for #par_iter_mut_name #ty_generics
+        where
+            #( #where_predicates, )*
+            #slice_mut_name #ty_generics: Send,
+            #ref_mut_name #ty_generics: Send,
+        {
+            fn len(&self) -> usize {
+                self.0.len()
+            }
+
+            fn drive<C>(self, consumer: C) -> C::Result
+                where C: #krate::rayon::iter::plumbing::Consumer<Self::Item>
+            {
+                #krate::rayon::iter::plumbing::bridge(self, consumer)
+            }
+
+            fn with_producer<CB>(self, callback: CB) -> CB::Output
+                where CB: #krate::rayon::iter::plumbing::ProducerCallback<Self::Item>
+            {
+                struct Producer #impl_generics (#slice_mut_name #ty_generics) #where_clause;
+
+                impl #impl_generics #krate::rayon::iter::plumbing::Producer for Producer #ty_generics
+                where
+                    #( #where_predicates, )*
+                    #slice_mut_name #ty_generics: Send,
+                    #ref_mut_name #ty_generics: Send,
+                {
+                    type Item = #ref_mut_name #ty_generics;
+                    type IntoIter = #iter_mut_name #ty_generics;
+
+                    fn into_iter(self) -> Self::IntoIter {
+                        self.0.into_iter()
+                    }
+
+                    fn split_at(self, index: usize) -> (Self, Self) {
+                        let (left, right) = self.0.split_at_mut(index);
+                        (Producer(left), Producer(right))
+                    }
+                }
+
+                callback.callback(Producer(self.0))
+            }
+        }
+
+        #[allow(dead_code)]
+        impl #owned_impl_generics #vec_name #owned_ty_generics #owned_where_clause {
+            /// Get a parallel iterator over the
+            #[doc = #ref_doc_url]
+            /// in this vector
+            pub fn par_iter(&self) -> #par_iter_name #ty_generics_anon {
+                #par_iter_name(self.as_slice())
+            }
+
+            /// Get a mutable parallel iterator over the
+            #[doc = #ref_mut_doc_url]
+            /// in this vector
+            pub fn par_iter_mut(&mut self) -> #par_iter_mut_name #ty_generics_anon {
+                #par_iter_mut_name(self.as_mut_slice())
+            }
+        }
+
+        #[allow(dead_code)]
+        impl #impl_generics #slice_name #ty_generics #where_clause {
+            /// Get a parallel iterator over the
+            #[doc = #ref_doc_url]
+            /// in this slice.
+            pub fn par_iter(&self) -> #par_iter_name #ty_generics_anon {
+                #par_iter_name(*self)
+            }
+        }
+
+        #[allow(dead_code)]
+        impl #impl_generics #slice_mut_name #ty_generics #where_clause {
+            /// Get a parallel iterator over the
+            #[doc = #ref_doc_url]
+            /// in this slice.
+            pub fn par_iter(&self) -> #par_iter_name #ty_generics_anon {
+                #par_iter_name(self.as_ref())
+            }
+
+            /// Get a mutable parallel iterator over the
+            #[doc = #ref_mut_doc_url]
+            /// in this slice.
+            pub fn par_iter_mut(&mut self) -> #par_iter_mut_name #ty_generics_anon {
+                #par_iter_mut_name(self.reborrow())
+            }
+        }
+
+        impl #impl_generics #krate::rayon::iter::IntoParallelIterator for #slice_name #ty_generics
+        where
+            #( #where_predicates, )*
+            #slice_name #ty_generics: Send,
+            #ref_name #ty_generics: Send,
+        {
+            type Item = #ref_name #ty_generics;
+            type Iter = #par_iter_name #ty_generics;
+
+            fn into_par_iter(self) -> Self::Iter {
+                #par_iter_name(self)
+            }
+        }
+
+        impl #impl_generics #krate::rayon::iter::IntoParallelIterator for &'a #vec_name #owned_ty_generics
+        where
+            #( #where_predicates, )*
+            #slice_name #ty_generics: Send,
+            #ref_name #ty_generics: Send,
+        {
+            type Item = #ref_name #ty_generics;
+            type Iter = #par_iter_name #ty_generics;
+
+            fn into_par_iter(self) -> Self::Iter {
+                #par_iter_name(self.as_slice())
+            }
+        }
+
+        impl #impl_generics #krate::rayon::iter::IntoParallelIterator for #slice_mut_name #ty_generics
+        where
+            #( #where_predicates, )*
+            #slice_mut_name #ty_generics: Send,
+            #ref_mut_name #ty_generics: Send,
+        {
+            type Item = #ref_mut_name #ty_generics;
+            type Iter = #par_iter_mut_name #ty_generics;
+
+            fn into_par_iter(self) -> Self::Iter {
+                #par_iter_mut_name(self)
+            }
+        }
+
+        impl #impl_generics #krate::rayon::iter::IntoParallelIterator for &'a mut #vec_name #owned_ty_generics
+        where
+            #( #where_predicates, )*
+            #slice_mut_name #ty_generics: Send,
+            #ref_mut_name #ty_generics: Send,
+        {
+            type Item = #ref_mut_name #ty_generics;
+            type Iter = #par_iter_mut_name #ty_generics;
+
+            fn into_par_iter(self) -> Self::Iter {
+                #par_iter_mut_name(self.as_mut_slice())
+            }
+        }
+    };
+
+    generated.append_all(quote! {
+        impl #impl_generics #krate::SoAParallelPermute for #slice_mut_name #ty_generics
+        where
+            #( #where_predicates, )*
+            #( #permute_bounds, )*
+        {
+            unsafe fn __private_par_apply_index(&mut self, indices: &[usize], cycles: &[usize]) {
+                #krate::rayon::scope(|scope| {
+                    #( #spawn_apply_index )*
+                });
+            }
+        }
+
+        impl #owned_impl_generics #krate::SoAParallelRetain for #vec_name #owned_ty_generics
+        where
+            #( #where_predicates, )*
+            #( #retain_bounds, )*
+        {
+            fn __private_par_retain_by_mask(&mut self, mask: &[bool]) {
+                #krate::rayon::scope(|scope| {
+                    #( #spawn_retain_by_mask )*
+                });
+            }
+        }
+
+        #[allow(dead_code)]
+        impl #impl_generics #slice_mut_name #ty_generics #where_clause {
+            /// Similar to [`&mut
+            #[doc = #slice_name_str]
+            /// ::par_sort_by()`](https://docs.rs/rayon/latest/rayon/slice/trait.ParallelSliceMut.html#method.par_sort_by).
+            /// The permutation sorting the slice is computed in parallel, and
+            /// then applied in place to all the fields concurrently, one task
+            /// per field.
+            pub fn par_sort_by<F>(&mut self, f: F)
+            where
+                F: Fn(#ref_name #ty_generics_anon, #ref_name #ty_generics_anon) -> std::cmp::Ordering + Sync,
+                for<'b> #slice_name #ty_generics_b: Sync,
+                Self: #krate::SoAParallelPermute,
+            {
+                use #krate::rayon::slice::ParallelSliceMut;
+
+                let mut indices: Vec<usize> = (0..self.len()).collect();
+                let slice = self.as_slice();
+                indices.par_sort_by(|j, k| f(slice.index(*j), slice.index(*k)));
+
+                let cycles = #krate::__private_permutation_cycles(&indices);
+                // SAFETY: `indices` is a permutation of `0..self.len()`, as
+                // checked by `__private_permutation_cycles`
+                unsafe {
+                    #krate::SoAParallelPermute::__private_par_apply_index(self, &indices, &cycles);
+                }
+            }
+
+            /// Similar to [`&mut
+            #[doc = #slice_name_str]
+            /// ::par_sort_unstable_by_key()`](https://docs.rs/rayon/latest/rayon/slice/trait.ParallelSliceMut.html#method.par_sort_unstable_by_key).
+            /// The permutation sorting the slice is computed in parallel, and
+            /// then applied in place to all the fields concurrently, one task
+            /// per field.
+            pub fn par_sort_unstable_by_key<F, K>(&mut self, f: F)
+            where
+                F: Fn(#ref_name #ty_generics_anon) -> K + Sync,
+                K: Ord,
+                for<'b> #slice_name #ty_generics_b: Sync,
+                Self: #krate::SoAParallelPermute,
+            {
+                use #krate::rayon::slice::ParallelSliceMut;
+
+                let mut indices: Vec<usize> = (0..self.len()).collect();
+                let slice = self.as_slice();
+                indices.par_sort_unstable_by_key(|i| f(slice.index(*i)));
+
+                let cycles = #krate::__private_permutation_cycles(&indices);
+                // SAFETY: `indices` is a permutation of `0..self.len()`, as
+                // checked by `__private_permutation_cycles`
+                unsafe {
+                    #krate::SoAParallelPermute::__private_par_apply_index(self, &indices, &cycles);
+                }
+            }
+        }
+
+        #[allow(dead_code)]
+        impl #owned_impl_generics #vec_name #owned_ty_generics #owned_where_clause {
+            /// Similar to [`&mut
+            #[doc = #slice_name_str]
+            /// ::par_sort_by()`](https://docs.rs/rayon/latest/rayon/slice/trait.ParallelSliceMut.html#method.par_sort_by),
+            /// see `par_sort_by` on the mutable slice type.
+            pub fn par_sort_by<F>(&mut self, f: F)
+            where
+                F: Fn(#ref_name #ty_generics_anon, #ref_name #ty_generics_anon) -> std::cmp::Ordering + Sync,
+                for<'b> #slice_name #ty_generics_b: Sync,
+                for<'b> #slice_mut_name #ty_generics_b: #krate::SoAParallelPermute,
+            {
+                self.as_mut_slice().par_sort_by(f);
+            }
+
+            /// Similar to [`&mut
+            #[doc = #slice_name_str]
+            /// ::par_sort_unstable_by_key()`](https://docs.rs/rayon/latest/rayon/slice/trait.ParallelSliceMut.html#method.par_sort_unstable_by_key),
+            /// see `par_sort_unstable_by_key` on the mutable slice type.
+            pub fn par_sort_unstable_by_key<F, K>(&mut self, f: F)
+            where
+                F: Fn(#ref_name #ty_generics_anon) -> K + Sync,
+                K: Ord,
+                for<'b> #slice_name #ty_generics_b: Sync,
+                for<'b> #slice_mut_name #ty_generics_b: #krate::SoAParallelPermute,
+            {
+                self.as_mut_slice().par_sort_unstable_by_key(f);
+            }
+
+            /// Similar to [`
+            #[doc = #vec_name_str]
+            /// ::retain()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.retain),
+            /// calling `f` on all elements in parallel. The elements for which
+            /// `f` returned `false` are then removed from all the fields
+            /// concurrently, one task per field.
+            pub fn par_retain<F>(&mut self, f: F)
+            where
+                F: Fn(#ref_name #ty_generics_anon) -> bool + Sync,
+                for<'b> #slice_name #ty_generics_b: Sync,
+                for<'b> #vec_name #owned_ty_generics: #krate::SoAParallelRetain,
+            {
+                use #krate::rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
+
+                let slice = self.as_slice();
+                let mask: Vec<bool> = (0..self.len()).into_par_iter()
+                    .map(|i| f(slice.index(i)))
+                    .collect();
+                #krate::SoAParallelRetain::__private_par_retain_by_mask(self, &mask);
+            }
+        }
+    });
+
+    return generated;
+}
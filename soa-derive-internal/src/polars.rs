@@ -0,0 +1,99 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::input::Input;
+use crate::names;
+
+pub fn derive(input: &Input) -> TokenStream {
+    let krate = &input.attrs.crate_path;
+    let vec_name = &input.attrs.names.vec;
+
+    let (owned_impl_generics, owned_ty_generics, _) = input.generics.split_for_impl();
+    let where_predicates = input.where_predicates();
+
+    let fields_names = input.fields_names();
+    let marker = input.marker_init();
+
+    let polars_bounds = input.map_fields_nested_or(
+        |_, field_type| {
+            let vec_type = names::nested_type(field_type, names::vec_name, None);
+            quote! { for<'b> #vec_type: #krate::polars::PolarsStructOfArray }
+        },
+        |_, field_type| quote! { for<'b> #field_type: #krate::polars::PolarsColumn },
+    ).collect::<Vec<_>>();
+
+    // the vector implements Drop, so we need to take the fields out of it
+    let into_columns = input.map_fields_nested_or(
+        |ident, _| {
+            let name = names::data_column_name(ident);
+            quote! { #krate::polars::__private_struct_column(#name, ::std::mem::take(&mut self.#ident)) }
+        },
+        |ident, _| {
+            let name = names::data_column_name(ident);
+            quote! { #krate::polars::__private_column(#name, ::std::mem::take(&mut self.#ident)) }
+        },
+    ).collect::<Vec<_>>();
+
+    let read_columns = input.map_fields_nested_or(
+        |ident, _| {
+            let name = names::data_column_name(ident);
+            quote! { #krate::polars::__private_read_struct_column(dataframe, #name)? }
+        },
+        |ident, _| {
+            let name = names::data_column_name(ident);
+            quote! { #krate::polars::__private_read_column(dataframe, #name)? }
+        },
+    ).collect::<Vec<_>>();
+
+    return quote! {
+        impl #owned_impl_generics #krate::polars::PolarsStructOfArray for #vec_name #owned_ty_generics
+        where
+            #( #where_predicates, )*
+            #( #polars_bounds, )*
+        {
+            fn into_polars_columns(mut self) -> Vec<#krate::polars::polars_core::prelude::Column> {
+                vec![
+                    #( #into_columns, )*
+                ]
+            }
+
+            fn try_from_polars_columns(
+                dataframe: &#krate::polars::DataFrame,
+            ) -> #krate::polars::polars_core::prelude::PolarsResult<Self> {
+                Ok(#vec_name {
+                    #( #fields_names: #read_columns, )*
+                    #marker
+                })
+            }
+        }
+
+        #[allow(dead_code)]
+        impl #owned_impl_generics #vec_name #owned_ty_generics
+        where
+            #( #where_predicates, )*
+            #( #polars_bounds, )*
+        {
+            /// Convert this vector into a Polars data frame, with one column
+            /// for each field. The columns of primitive types are moved to
+            /// the data frame without copying the data.
+            pub fn into_dataframe(self) -> #krate::polars::DataFrame {
+                let columns = #krate::polars::PolarsStructOfArray::into_polars_columns(self);
+                #krate::polars::DataFrame::new(columns).expect("the columns should have the same length")
+            }
+
+            /// Create a vector containing a copy of the columns of
+            /// `dataframe` with the same names as the fields.
+            ///
+            /// # Errors
+            ///
+            /// If a column is missing, has the wrong data type or contains
+            /// unexpected nulls. The error message contains the name of the
+            /// corresponding column.
+            pub fn try_from_dataframe(
+                dataframe: &#krate::polars::DataFrame,
+            ) -> #krate::polars::polars_core::prelude::PolarsResult<Self> {
+                <Self as #krate::polars::PolarsStructOfArray>::try_from_polars_columns(dataframe)
+            }
+        }
+    };
+}
@@ -5,15 +5,20 @@ use crate::input::Input;
 use crate::names;
 
 pub fn derive(input: &Input) -> TokenStream {
+    let krate = &input.attrs.crate_path;
     let name = &input.name;
     let visibility = &input.visibility;
     let attrs = &input.attrs.ptr;
     let mut_attrs = &input.attrs.ptr_mut;
-    let vec_name = names::vec_name(&input.name);
-    let ptr_name = names::ptr_name(&input.name);
-    let ptr_mut_name = names::ptr_mut_name(&input.name);
-    let ref_name = names::ref_name(&input.name);
-    let ref_mut_name = names::ref_mut_name(&input.name);
+    let vec_name = &input.attrs.names.vec;
+    let ptr_name = &input.attrs.names.ptr;
+    let ptr_mut_name = &input.attrs.names.ptr_mut;
+    let ref_name = &input.attrs.names.ref_;
+    let ref_mut_name = &input.attrs.names.ref_mut;
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let ref_generics = input.generics_with_lifetime("'a");
+    let (ref_impl_generics, ref_ty_generics, ref_where_clause) = ref_generics.split_for_impl();
 
     let doc_url = format!("[`{0}`](struct.{0}.html)", name);
     let vec_doc_url = format!("[`{0}`](struct.{0}.html)", vec_name);
@@ -22,13 +27,17 @@ pub fn derive(input: &Input) -> TokenStream {
     let ref_doc_url = format!("[`{0}`](struct.{0}.html)", ref_name);
     let ref_mut_doc_url = format!("[`{0}`](struct.{0}.html)", ref_mut_name);
 
-    let fields_names = &input.fields.iter()
-        .map(|field| field.ident.clone().unwrap())
-        .collect::<Vec<_>>();
+    let fields_names = &input.fields_names();
+    let marker = input.marker_init();
+    let original_fields_names = &input.original_fields_names;
+    let construct_read = input.construct(fields_names.iter().map(|field| quote! { self.#field.read() }));
+    let construct_read_volatile = input.construct(fields_names.iter().map(|field| quote! { self.#field.read_volatile() }));
+    let construct_read_unaligned = input.construct(fields_names.iter().map(|field| quote! { self.#field.read_unaligned() }));
+    let drop_skipped_val = input.drop_skipped_fields(&quote! { val });
 
     let ptr_fields_types = input.map_fields_nested_or(
         |_, field_type| {
-            let field_ptr_type = names::ptr_name(field_type);
+            let field_ptr_type = names::nested_type(field_type, names::ptr_name, None);
             quote! { #field_ptr_type }
         },
         |_, field_type| quote! { *const #field_type },
@@ -36,7 +45,7 @@ pub fn derive(input: &Input) -> TokenStream {
 
     let ptr_mut_fields_types = input.map_fields_nested_or(
         |_, field_type| {
-            let field_ptr_type = names::ptr_mut_name(field_type);
+            let field_ptr_type = names::nested_type(field_type, names::ptr_mut_name, None);
             quote! { #field_ptr_type }
         },
         |_, field_type| quote! { *mut #field_type },
@@ -47,6 +56,26 @@ pub fn derive(input: &Input) -> TokenStream {
         |ident, _| quote! { self.#ident as *const _ },
     ).collect::<Vec<_>>();
 
+    let ptr_fields_docs = fields_names.iter().map(|field_name| quote! {
+        /// pointer to the `
+        #[doc = stringify!(#field_name)]
+        ///` field of a single
+        #[doc = #doc_url]
+        /// inside a
+        #[doc = #vec_doc_url]
+    }).collect::<Vec<_>>();
+    let ptr_body = input.struct_body(where_clause, &ptr_fields_docs, |attrs| &attrs.ptr, &ptr_fields_types);
+
+    let ptr_mut_fields_docs = fields_names.iter().map(|field_name| quote! {
+        /// pointer to the `
+        #[doc = stringify!(#field_name)]
+        ///` field of a single
+        #[doc = #doc_url]
+        /// inside a
+        #[doc = #vec_doc_url]
+    }).collect::<Vec<_>>();
+    let ptr_mut_body = input.struct_body(where_clause, &ptr_mut_fields_docs, |attrs| &attrs.ptr_mut, &ptr_mut_fields_types);
+
     let as_mut_ptr = input.map_fields_nested_or(
         |ident, _| quote! { self.#ident.as_mut_ptr() },
         |ident, _| quote! { self.#ident as *mut _ },
@@ -57,46 +86,41 @@ pub fn derive(input: &Input) -> TokenStream {
         #[doc = #doc_url]
         /// with struct of array layout.
         #(#[#attrs])*
-        #[derive(Copy, Clone)]
-        #visibility struct #ptr_name {
-            #(
-                /// pointer to the `
-                #[doc = stringify!(#fields_names)]
-                ///` field of a single
-                #[doc = #doc_url]
-                /// inside a
-                #[doc = #vec_doc_url]
-                pub #fields_names: #ptr_fields_types,
-            )*
-        }
+        #visibility struct #ptr_name #impl_generics #ptr_body
 
         /// An analog of a mutable pointer to
         #[doc = #doc_url]
         /// with struct of array layout.
         #(#[#mut_attrs])*
-        #[derive(Copy, Clone)]
-        #visibility struct #ptr_mut_name {
-            #(
-                /// pointer to the `
-                #[doc = stringify!(#fields_names)]
-                ///` field of a single
-                #[doc = #doc_url]
-                /// inside a
-                #[doc = #vec_doc_url]
-                pub #fields_names: #ptr_mut_fields_types,
-            )*
+        #visibility struct #ptr_mut_name #impl_generics #ptr_mut_body
+
+        impl #impl_generics Copy for #ptr_name #ty_generics #where_clause {}
+
+        impl #impl_generics Clone for #ptr_name #ty_generics #where_clause {
+            fn clone(&self) -> Self {
+                *self
+            }
+        }
+
+        impl #impl_generics Copy for #ptr_mut_name #ty_generics #where_clause {}
+
+        impl #impl_generics Clone for #ptr_mut_name #ty_generics #where_clause {
+            fn clone(&self) -> Self {
+                *self
+            }
         }
 
         #[allow(dead_code)]
-        impl #ptr_name {
+        impl #impl_generics #ptr_name #ty_generics #where_clause {
             /// Convert a
             #[doc = #ptr_doc_url]
             /// to a
             #[doc = #ptr_mut_doc_url]
             /// ; *i.e.* do a `*const T as *mut T` transformation.
-            #visibility fn as_mut_ptr(&self) -> #ptr_mut_name {
+            #visibility fn as_mut_ptr(&self) -> #ptr_mut_name #ty_generics {
                 #ptr_mut_name {
                     #( #fields_names: #as_mut_ptr, )*
+                    #marker
                 }
             }
 
@@ -107,102 +131,104 @@ pub fn derive(input: &Input) -> TokenStream {
 
             /// Similar to [`*const T::as_ref()`](https://doc.rust-lang.org/std/primitive.pointer.html#method.as_ref),
             /// with the same safety caveats.
-            pub unsafe fn as_ref<'a>(self) -> Option<#ref_name<'a>> {
+            pub unsafe fn as_ref<'a>(self) -> Option<#ref_name #ref_ty_generics> {
                 if self.is_null() {
                     None
                 } else {
                     Some(#ref_name {
                         #(#fields_names: self.#fields_names.as_ref().expect("should not be null"), )*
+                        #marker
                     })
                 }
             }
 
             /// Similar to [`*const T::offset()`](https://doc.rust-lang.org/std/primitive.pointer.html#method.offset),
             /// with the same safety caveats.
-            pub unsafe fn offset(self, count: isize) -> #ptr_name {
+            pub unsafe fn offset(self, count: isize) -> #ptr_name #ty_generics {
                 #ptr_name {
                     #(#fields_names: self.#fields_names.offset(count), )*
+                    #marker
                 }
             }
 
             /// Similar to [`*const T::offset()`](https://doc.rust-lang.org/std/primitive.pointer.html#method.offset).
-            pub fn wrapping_offset(self, count: isize) -> #ptr_name {
+            pub fn wrapping_offset(self, count: isize) -> #ptr_name #ty_generics {
                 #ptr_name {
                     #(#fields_names: self.#fields_names.wrapping_offset(count), )*
+                    #marker
                 }
             }
 
             /// Similar to [`*const T::add()`](https://doc.rust-lang.org/std/primitive.pointer.html#method.add),
             /// with the same safety caveats.
-            pub unsafe fn add(self, count: usize) -> #ptr_name {
+            pub unsafe fn add(self, count: usize) -> #ptr_name #ty_generics {
                 #ptr_name {
                     #(#fields_names: self.#fields_names.add(count), )*
+                    #marker
                 }
             }
 
             /// Similar to [`*const T::sub()`](https://doc.rust-lang.org/std/primitive.pointer.html#method.sub),
             /// with the same safety caveats.
-            pub unsafe fn sub(self, count: usize) -> #ptr_name {
+            pub unsafe fn sub(self, count: usize) -> #ptr_name #ty_generics {
                 #ptr_name {
                     #(#fields_names: self.#fields_names.sub(count), )*
+                    #marker
                 }
             }
 
             /// Similar to [`*const T::wrapping_add()`](https://doc.rust-lang.org/std/primitive.pointer.html#method.wrapping_add).
-            pub fn wrapping_add(self, count: usize) -> #ptr_name {
+            pub fn wrapping_add(self, count: usize) -> #ptr_name #ty_generics {
                 #ptr_name {
                     #(#fields_names: self.#fields_names.wrapping_add(count), )*
+                    #marker
                 }
             }
 
             /// Similar to [`*const T::wrapping_sub()`](https://doc.rust-lang.org/std/primitive.pointer.html#method.wrapping_sub).
-            pub fn wrapping_sub(self, count: usize) -> #ptr_name {
+            pub fn wrapping_sub(self, count: usize) -> #ptr_name #ty_generics {
                 #ptr_name {
                     #(#fields_names: self.#fields_names.wrapping_sub(count), )*
+                    #marker
                 }
             }
 
             /// Similar to [`*const T::read()`](https://doc.rust-lang.org/std/primitive.pointer.html#method.read),
             /// with the same safety caveats.
-            pub unsafe fn read(self) -> #name {
-                #name {
-                    #(#fields_names: self.#fields_names.read(), )*
-                }
+            pub unsafe fn read(self) -> #name #ty_generics {
+                #construct_read
             }
 
             /// Similar to [`*const T::read_volatile()`](https://doc.rust-lang.org/std/primitive.pointer.html#method.read_volatile),
             /// with the same safety caveats.
-            pub unsafe fn read_volatile(self) -> #name {
-                #name {
-                    #(#fields_names: self.#fields_names.read_volatile(), )*
-                }
+            pub unsafe fn read_volatile(self) -> #name #ty_generics {
+                #construct_read_volatile
             }
 
             /// Similar to [`*const T::read_unaligned()`](https://doc.rust-lang.org/std/primitive.pointer.html#method.read_unaligned),
             /// with the same safety caveats.
-            pub unsafe fn read_unaligned(self) -> #name {
-                #name {
-                    #(#fields_names: self.#fields_names.read_unaligned(), )*
-                }
+            pub unsafe fn read_unaligned(self) -> #name #ty_generics {
+                #construct_read_unaligned
             }
         }
 
-        impl ::soa_derive::SoAPointers for #name {
-            type Ptr = #ptr_name;
-            type MutPtr = #ptr_mut_name;
+        impl #impl_generics #krate::SoAPointers for #name #ty_generics #where_clause {
+            type Ptr = #ptr_name #ty_generics;
+            type MutPtr = #ptr_mut_name #ty_generics;
         }
 
         #[allow(dead_code)]
         #[allow(clippy::forget_non_drop)]
-        impl #ptr_mut_name {
+        impl #impl_generics #ptr_mut_name #ty_generics #where_clause {
             /// Convert a
             #[doc = #ptr_mut_doc_url]
             /// to a
             #[doc = #ptr_doc_url]
             /// ; *i.e.* do a `*mut T as *const T` transformation
-            #visibility fn as_ptr(&self) -> #ptr_name {
+            #visibility fn as_ptr(&self) -> #ptr_name #ty_generics {
                 #ptr_name {
                     #( #fields_names: #as_ptr, )*
+                    #marker
                 }
             }
 
@@ -213,106 +239,121 @@ pub fn derive(input: &Input) -> TokenStream {
 
             /// Similar to [`*mut T::as_ref()`](https://doc.rust-lang.org/std/primitive.pointer.html#method.as_ref),
             /// with the same safety caveats.
-            pub unsafe fn as_ref<'a>(self) -> Option<#ref_name<'a>> {
+            pub unsafe fn as_ref<'a>(self) -> Option<#ref_name #ref_ty_generics> {
                 if self.is_null() {
                     None
                 } else {
                     Some(#ref_name {
                         #(#fields_names: self.#fields_names.as_ref().expect("should not be null"), )*
+                        #marker
                     })
                 }
             }
 
             /// Similar to [`*mut T::as_mut()`](https://doc.rust-lang.org/std/primitive.pointer.html#method.as_mut),
             /// with the same safety caveats.
-            pub unsafe fn as_mut<'a>(self) -> Option<#ref_mut_name<'a>> {
+            pub unsafe fn as_mut<'a>(self) -> Option<#ref_mut_name #ref_ty_generics> {
                 if self.is_null() {
                     None
                 } else {
                     Some(#ref_mut_name {
                         #(#fields_names: self.#fields_names.as_mut().expect("should not be null"), )*
+                        #marker
                     })
                 }
             }
 
             /// Similar to [`*mut T::offset()`](https://doc.rust-lang.org/std/primitive.pointer.html#method.offset),
             /// with the same safety caveats.
-            pub unsafe fn offset(self, count: isize) -> #ptr_mut_name {
+            pub unsafe fn offset(self, count: isize) -> #ptr_mut_name #ty_generics {
                 #ptr_mut_name {
                     #(#fields_names: self.#fields_names.offset(count), )*
+                    #marker
                 }
             }
 
             /// Similar to [`*mut T::wrapping_offset()`](https://doc.rust-lang.org/std/primitive.pointer.html#method.wrapping_offset)
-            pub fn wrapping_offset(self, count: isize) -> #ptr_mut_name {
+            pub fn wrapping_offset(self, count: isize) -> #ptr_mut_name #ty_generics {
                 #ptr_mut_name {
                     #(#fields_names: self.#fields_names.wrapping_offset(count), )*
+                    #marker
                 }
             }
 
             /// Similar to [`*mut T::add()`](https://doc.rust-lang.org/std/primitive.pointer.html#method.add),
             /// with the same safety caveats.
-            pub unsafe fn add(self, count: usize) -> #ptr_mut_name {
+            pub unsafe fn add(self, count: usize) -> #ptr_mut_name #ty_generics {
                 #ptr_mut_name {
                     #(#fields_names: self.#fields_names.add(count), )*
+                    #marker
                 }
             }
 
             /// Similar to [`*mut T::sub()`](https://doc.rust-lang.org/std/primitive.pointer.html#method.sub),
             /// with the same safety caveats.
-            pub unsafe fn sub(self, count: usize) -> #ptr_mut_name {
+            pub unsafe fn sub(self, count: usize) -> #ptr_mut_name #ty_generics {
                 #ptr_mut_name {
                     #(#fields_names: self.#fields_names.sub(count), )*
+                    #marker
                 }
             }
 
             /// Similar to [`*mut T::wrapping_add()`](https://doc.rust-lang.org/std/primitive.pointer.html#method.wrapping_add),
             /// with the same safety caveats.
-            pub fn wrapping_add(self, count: usize) -> #ptr_mut_name {
+            pub fn wrapping_add(self, count: usize) -> #ptr_mut_name #ty_generics {
                 #ptr_mut_name {
                     #(#fields_names: self.#fields_names.wrapping_add(count), )*
+                    #marker
                 }
             }
 
             /// Similar to [`*mut T::wrapping_sub()`](https://doc.rust-lang.org/std/primitive.pointer.html#method.wrapping_sub),
             /// with the same safety caveats.
-            pub fn wrapping_sub(self, count: usize) -> #ptr_mut_name {
+            pub fn wrapping_sub(self, count: usize) -> #ptr_mut_name #ty_generics {
                 #ptr_mut_name {
                     #(#fields_names: self.#fields_names.wrapping_sub(count), )*
+                    #marker
                 }
             }
 
+            /// Similar to [`*mut T::copy_from()`](https://doc.rust-lang.org/std/primitive.pointer.html#method.copy_from),
+            /// with the same safety caveats.
+            pub unsafe fn copy_from(self, src: #ptr_name #ty_generics, count: usize) {
+                #(self.#fields_names.copy_from(src.#fields_names, count);)*
+            }
+
+            /// Similar to [`*mut T::copy_from_nonoverlapping()`](https://doc.rust-lang.org/std/primitive.pointer.html#method.copy_from_nonoverlapping),
+            /// with the same safety caveats.
+            pub unsafe fn copy_from_nonoverlapping(self, src: #ptr_name #ty_generics, count: usize) {
+                #(self.#fields_names.copy_from_nonoverlapping(src.#fields_names, count);)*
+            }
+
             /// Similar to [`*mut T::read()`](https://doc.rust-lang.org/std/primitive.pointer.html#method.read),
             /// with the same safety caveats.
-            pub unsafe fn read(self) -> #name {
-                #name {
-                    #(#fields_names: self.#fields_names.read(), )*
-                }
+            pub unsafe fn read(self) -> #name #ty_generics {
+                #construct_read
             }
 
             /// Similar to [`*mut T::read_volatile()`](https://doc.rust-lang.org/std/primitive.pointer.html#method.read_volatile),
             /// with the same safety caveats.
-            pub unsafe fn read_volatile(self) -> #name {
-                #name {
-                    #(#fields_names: self.#fields_names.read_volatile(), )*
-                }
+            pub unsafe fn read_volatile(self) -> #name #ty_generics {
+                #construct_read_volatile
             }
 
             /// Similar to [`*mut T::read_unaligned()`](https://doc.rust-lang.org/std/primitive.pointer.html#method.read_unaligned),
             /// with the same safety caveats.
-            pub unsafe fn read_unaligned(self) -> #name {
-                #name {
-                    #(#fields_names: self.#fields_names.read_unaligned(), )*
-                }
+            pub unsafe fn read_unaligned(self) -> #name #ty_generics {
+                #construct_read_unaligned
             }
 
             /// Similar to [`*mut T::write()`](https://doc.rust-lang.org/std/primitive.pointer.html#method.write),
             /// with the same safety caveats.
             #[allow(clippy::forget_non_drop)]
-            pub unsafe fn write(self, val: #name) {
+            pub unsafe fn write(self, val: #name #ty_generics) {
                 unsafe {
-                    #(self.#fields_names.write(::std::ptr::read(&val.#fields_names));)*
+                    #(self.#fields_names.write(::std::ptr::read(&val.#original_fields_names));)*
                 }
+                #drop_skipped_val
                 // if val implements Drop, we don't want to run it here, only
                 // when the vec itself will be dropped
                 ::std::mem::forget(val);
@@ -321,10 +362,11 @@ pub fn derive(input: &Input) -> TokenStream {
             /// Similar to [`*mut T::write_volatile()`](https://doc.rust-lang.org/std/primitive.pointer.html#method.write_volatile),
             /// with the same safety caveats.
             #[allow(clippy::forget_non_drop)]
-            pub unsafe fn write_volatile(self, val: #name) {
+            pub unsafe fn write_volatile(self, val: #name #ty_generics) {
                 unsafe {
-                    #(self.#fields_names.write_volatile(::std::ptr::read(&val.#fields_names));)*
+                    #(self.#fields_names.write_volatile(::std::ptr::read(&val.#original_fields_names));)*
                 }
+                #drop_skipped_val
                 // if val implements Drop, we don't want to run it here, only
                 // when the vec itself will be dropped
                 ::std::mem::forget(val);
@@ -333,10 +375,11 @@ pub fn derive(input: &Input) -> TokenStream {
             /// Similar to [`*mut T::write_unaligned()`](https://doc.rust-lang.org/std/primitive.pointer.html#method.write_unaligned),
             /// with the same safety caveats.
             #[allow(clippy::forget_non_drop)]
-            pub unsafe fn write_unaligned(self, val: #name) {
+            pub unsafe fn write_unaligned(self, val: #name #ty_generics) {
                 unsafe {
-                    #(self.#fields_names.write_unaligned(::std::ptr::read(&val.#fields_names));)*
+                    #(self.#fields_names.write_unaligned(::std::ptr::read(&val.#original_fields_names));)*
                 }
+                #drop_skipped_val
                 // if val implements Drop, we don't want to run it here, only
                 // when the vec itself will be dropped
                 ::std::mem::forget(val);
@@ -344,29 +387,31 @@ pub fn derive(input: &Input) -> TokenStream {
         }
 
         #[allow(dead_code)]
-        impl<'a> #ref_name<'a> {
+        impl #ref_impl_generics #ref_name #ref_ty_generics #ref_where_clause {
             /// Convert a
             #[doc = #ref_doc_url]
             /// to a
             #[doc = #ptr_doc_url]
             /// ; *i.e.* do a `&T as *const T` transformation
-            #visibility fn as_ptr(&self) -> #ptr_name {
+            #visibility fn as_ptr(&self) -> #ptr_name #ty_generics {
                 #ptr_name {
                     #( #fields_names: #as_ptr, )*
+                    #marker
                 }
             }
         }
 
         #[allow(dead_code)]
-        impl<'a> #ref_mut_name<'a> {
+        impl #ref_impl_generics #ref_mut_name #ref_ty_generics #ref_where_clause {
             /// Convert a
             #[doc = #ref_mut_doc_url]
             /// to a
             #[doc = #ptr_doc_url]
             /// ; *i.e.* do a `&mut T as *const T` transformation
-            #visibility fn as_ptr(&self) -> #ptr_name {
+            #visibility fn as_ptr(&self) -> #ptr_name #ty_generics {
                 #ptr_name {
                     #( #fields_names: #as_ptr, )*
+                    #marker
                 }
             }
 
@@ -375,9 +420,10 @@ pub fn derive(input: &Input) -> TokenStream {
             /// to a
             #[doc = #ptr_mut_doc_url]
             /// ; *i.e.* do a `&mut T as *mut T` transformation
-            #visibility fn as_mut_ptr(&mut self) -> #ptr_mut_name {
+            #visibility fn as_mut_ptr(&mut self) -> #ptr_mut_name #ty_generics {
                 #ptr_mut_name {
                     #( #fields_names: #as_mut_ptr, )*
+                    #marker
                 }
             }
         }
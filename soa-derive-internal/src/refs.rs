@@ -9,9 +9,15 @@ pub fn derive(input: &Input) -> TokenStream {
     let visibility = &input.visibility;
     let attrs = &input.attrs.ref_;
     let mut_attrs = &input.attrs.ref_mut;
-    let vec_name = names::vec_name(&input.name);
-    let ref_name = names::ref_name(&input.name);
-    let ref_mut_name = names::ref_mut_name(&input.name);
+    let vec_name = &input.attrs.names.vec;
+    let ref_name = &input.attrs.names.ref_;
+    let ref_mut_name = &input.attrs.names.ref_mut;
+
+    let generics = input.generics_with_lifetime("'a");
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let ty_generics_anon = input.ty_generics_with_lifetime("'_");
+    let (owned_impl_generics, owned_ty_generics, owned_where_clause) = input.generics.split_for_impl();
+    let where_predicates = input.where_predicates();
 
     let fields_types = &input.fields.iter()
         .map(|field| field.ty.clone())
@@ -22,45 +28,77 @@ pub fn derive(input: &Input) -> TokenStream {
     let ref_doc_url = format!("[`{0}`](struct.{0}.html)", ref_name);
     let ref_mut_doc_url = format!("[`{0}`](struct.{0}.html)", ref_mut_name);
 
-    let fields_names = &input.fields.iter()
-        .map(|field| field.ident.clone().unwrap())
-        .collect::<Vec<_>>();
+    let fields_names = &input.fields_names();
+    let marker = input.marker_init();
 
     let fields_names_hygienic = input.fields.iter()
         .enumerate()
         .map(|(i, _)| Ident::new(&format!("___soa_derive_private_{}", i), Span::call_site()))
         .collect::<Vec<_>>();
 
+    let original_fields_names = &input.original_fields_names;
+    let construct_hygienic = input.construct(&fields_names_hygienic);
+    let drop_skipped_val = input.drop_skipped_fields(&quote! { val });
+
     let ref_fields_types = input.map_fields_nested_or(
         |_, field_type| {
-            let field_ptr_type = names::ref_name(field_type);
-            quote! { #field_ptr_type<'a> }
+            let field_ptr_type = names::nested_type(field_type, names::ref_name, Some("'a"));
+            quote! { #field_ptr_type }
         },
         |_, field_type| quote! { &'a #field_type },
     ).collect::<Vec<_>>();
 
     let ref_mut_fields_types = input.map_fields_nested_or(
         |_, field_type| {
-            let field_ptr_type = names::ref_mut_name(field_type);
-            quote! { #field_ptr_type<'a> }
+            let field_ptr_type = names::nested_type(field_type, names::ref_mut_name, Some("'a"));
+            quote! { #field_ptr_type }
         },
         |_, field_type| quote! { &'a mut #field_type },
     ).collect::<Vec<_>>();
 
-    let as_ref = input.map_fields_nested_or(
-        |ident, _| quote! { self.#ident.as_ref() },
-        |ident, _| quote! { &self.#ident },
-    ).collect::<Vec<_>>();
+    // these are used with `self` being the input struct, which might not use
+    // the same names as the generated types if some fields are skipped
+    let as_ref = input.original_fields_names.iter().zip(&input.field_is_nested).map(|(ident, &is_nested)| {
+        if is_nested {
+            quote! { self.#ident.as_ref() }
+        } else {
+            quote! { &self.#ident }
+        }
+    }).collect::<Vec<_>>();
 
-    let as_mut = input.map_fields_nested_or(
-        |ident, _| quote! { self.#ident.as_mut() },
-        |ident, _| quote! { &mut self.#ident },
-    ).collect::<Vec<_>>();
+    let as_mut = input.original_fields_names.iter().zip(&input.field_is_nested).map(|(ident, &is_nested)| {
+        if is_nested {
+            quote! { self.#ident.as_mut() }
+        } else {
+            quote! { &mut self.#ident }
+        }
+    }).collect::<Vec<_>>();
 
     let to_owned = input.map_fields_nested_or(
         |ident, _| quote! { self.#ident.to_owned() },
-        |ident, _| quote! { self.#ident.clone() },
+        |ident, _| quote! { ::std::clone::Clone::clone(&*self.#ident) },
     ).collect::<Vec<_>>();
+    let construct_to_owned = input.construct(&to_owned);
+
+    let ref_fields_docs = fields_names.iter().map(|field_name| quote! {
+        /// reference to the `
+        #[doc = stringify!(#field_name)]
+        ///` field of a single
+        #[doc = #doc_url]
+        /// inside a
+        #[doc = #vec_doc_url]
+    }).collect::<Vec<_>>();
+    let ref_body = input.struct_body(where_clause, &ref_fields_docs, |attrs| &attrs.ref_, &ref_fields_types);
+
+    let ref_mut_fields_docs = fields_names.iter().map(|field_name| quote! {
+        /// reference to the `
+        #[doc = stringify!(#field_name)]
+        ///` field of a single
+        #[doc = #doc_url]
+        /// inside a
+        #[doc = #vec_doc_url]
+    }).collect::<Vec<_>>();
+    let ref_mut_body = input.struct_body(where_clause, &ref_mut_fields_docs, |attrs| &attrs.ref_mut, &ref_mut_fields_types);
 
     let ref_replace = input.map_fields_nested_or(
         |ident, _| quote! { self.#ident.replace(field) },
@@ -72,45 +110,33 @@ pub fn derive(input: &Input) -> TokenStream {
         #[doc = #doc_url]
         /// with struct of array layout.
         #(#[#attrs])*
-        #[derive(Copy, Clone)]
-        #visibility struct #ref_name<'a> {
-            #(
-                /// reference to the `
-                #[doc = stringify!(#fields_names)]
-                ///` field of a single
-                #[doc = #doc_url]
-                /// inside a
-                #[doc = #vec_doc_url]
-                pub #fields_names: #ref_fields_types,
-            )*
+        #visibility struct #ref_name #impl_generics #ref_body
+
+        impl #impl_generics Copy for #ref_name #ty_generics #where_clause {}
+
+        impl #impl_generics Clone for #ref_name #ty_generics #where_clause {
+            fn clone(&self) -> Self {
+                *self
+            }
         }
 
         /// A mutable reference to a
         #[doc = #doc_url]
         /// with struct of array layout.
         #(#[#mut_attrs])*
-        #visibility struct #ref_mut_name<'a> {
-            #(
-                /// reference to the `
-                #[doc = stringify!(#fields_names)]
-                ///` field of a single
-                #[doc = #doc_url]
-                /// inside a
-                #[doc = #vec_doc_url]
-                pub #fields_names: #ref_mut_fields_types,
-            )*
-        }
+        #visibility struct #ref_mut_name #impl_generics #ref_mut_body
 
         #[allow(dead_code)]
-        impl #name {
+        impl #owned_impl_generics #name #owned_ty_generics #owned_where_clause {
             /// Create a
             #[doc = #ref_doc_url]
             /// from a borrowed
             #[doc = #doc_url]
             /// .
-            #visibility fn as_ref(&self) -> #ref_name {
+            #visibility fn as_ref(&self) -> #ref_name #ty_generics_anon {
                 #ref_name {
                     #( #fields_names: #as_ref, )*
+                    #marker
                 }
             }
 
@@ -119,79 +145,93 @@ pub fn derive(input: &Input) -> TokenStream {
             /// from a mutably borrowed
             #[doc = #doc_url]
             /// .
-            #visibility fn as_mut(&mut self) -> #ref_mut_name {
+            #visibility fn as_mut(&mut self) -> #ref_mut_name #ty_generics_anon {
                 #ref_mut_name {
                     #( #fields_names: #as_mut, )*
+                    #marker
                 }
             }
         }
 
-        impl<'a> #ref_name<'a> {
+        impl #impl_generics #ref_name #ty_generics #where_clause {
             /// Convert a reference to
             #[doc = #doc_url]
             /// into an owned value. This is only available if all fields
             /// implement `Clone`.
-            pub fn to_owned(&self) -> #name
+            pub fn to_owned(&self) -> #name #owned_ty_generics
                 // only expose to_owned if all fields are Clone
                 // https://github.com/rust-lang/rust/issues/48214#issuecomment-1150463333
                 where #( for<'b> #fields_types: Clone, )*
             {
-                #name {
-                    #( #fields_names: #to_owned, )*
-                }
+                #construct_to_owned
             }
         }
 
-        impl<'a>  From<#ref_name<'a>> for #name where #( for<'b> #fields_types: Clone, )* {
-            fn from(value: #ref_name<'a>) -> #name {
+        impl #impl_generics From<#ref_name #ty_generics> for #name #owned_ty_generics
+            where
+                #( #where_predicates, )*
+                #( for<'b> #fields_types: Clone, )*
+        {
+            fn from(value: #ref_name #ty_generics) -> #name #owned_ty_generics {
                 value.to_owned()
             }
         }
 
-        impl<'a>  From<&'a #ref_name<'a>> for #name where #( for<'b> #fields_types: Clone, )* {
-            fn from(value: &'a #ref_name<'a>) -> #name {
+        impl #impl_generics From<&'a #ref_name #ty_generics> for #name #owned_ty_generics
+            where
+                #( #where_predicates, )*
+                #( for<'b> #fields_types: Clone, )*
+        {
+            fn from(value: &'a #ref_name #ty_generics) -> #name #owned_ty_generics {
                 value.to_owned()
             }
         }
 
-        impl<'a> #ref_mut_name<'a> {
+        impl #impl_generics #ref_mut_name #ty_generics #where_clause {
             /// Convert a mutable reference to
             #[doc = #doc_url]
             /// into an owned value. This is only available if all fields
             /// implement `Clone`.
-            pub fn to_owned(&self) -> #name
+            pub fn to_owned(&self) -> #name #owned_ty_generics
                 // only expose to_owned if all fields are Clone
                 // https://github.com/rust-lang/rust/issues/48214#issuecomment-1150463333
                 where #( for<'b> #fields_types: Clone, )*
             {
-                #name {
-                    #( #fields_names: #to_owned, )*
-                }
+                #construct_to_owned
             }
 
             /// Similar to [`std::mem::replace()`](https://doc.rust-lang.org/std/mem/fn.replace.html).
             #[allow(clippy::forget_non_drop)]
-            pub fn replace(&mut self, val: #name) -> #name {
+            pub fn replace(&mut self, val: #name #owned_ty_generics) -> #name #owned_ty_generics {
                 #(
-                    let field = unsafe { ::std::ptr::read(&val.#fields_names) };
+                    let field = unsafe { ::std::ptr::read(&val.#original_fields_names) };
                     let #fields_names_hygienic = #ref_replace;
                 )*
+                #drop_skipped_val
                 // if val implements Drop, we don't want to run it here, only
                 // when the vec itself will be dropped
                 ::std::mem::forget(val);
 
-                #name{#(#fields_names: #fields_names_hygienic),*}
+                #construct_hygienic
             }
         }
 
-        impl<'a>  From<#ref_mut_name<'a>> for #name where #( for<'b> #fields_types: Clone, )* {
-            fn from(value: #ref_mut_name<'a>) -> #name {
+        impl #impl_generics From<#ref_mut_name #ty_generics> for #name #owned_ty_generics
+            where
+                #( #where_predicates, )*
+                #( for<'b> #fields_types: Clone, )*
+        {
+            fn from(value: #ref_mut_name #ty_generics) -> #name #owned_ty_generics {
                 value.to_owned()
             }
         }
 
-        impl<'a>  From<&'a #ref_mut_name<'a>> for #name where #( for<'b> #fields_types: Clone, )* {
-            fn from(value: &'a #ref_mut_name<'a>) -> #name {
+        impl #impl_generics From<&'a #ref_mut_name #ty_generics> for #name #owned_ty_generics
+            where
+                #( #where_predicates, )*
+                #( for<'b> #fields_types: Clone, )*
+        {
+            fn from(value: &'a #ref_mut_name #ty_generics) -> #name #owned_ty_generics {
                 value.to_owned()
             }
         }
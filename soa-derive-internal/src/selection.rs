@@ -0,0 +1,174 @@
+use proc_macro2::TokenStream;
+use quote::{quote, TokenStreamExt};
+
+use crate::input::Input;
+
+pub fn derive(input: &Input) -> TokenStream {
+    let krate = &input.attrs.crate_path;
+    let visibility = &input.visibility;
+    let vec_name = &input.attrs.names.vec;
+    let slice_name = &input.attrs.names.slice;
+    let ref_name = &input.attrs.names.ref_;
+    let selection_name = &input.attrs.names.selection;
+
+    let generics = input.generics_with_lifetime("'a");
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let ty_generics_anon = input.ty_generics_with_lifetime("'_");
+    let (owned_impl_generics, owned_ty_generics, owned_where_clause) = input.generics.split_for_impl();
+    let where_predicates = input.where_predicates();
+
+    let fields_types = &input.fields.iter()
+        .map(|field| &field.ty)
+        .collect::<Vec<_>>();
+
+    let slice_doc_url = format!("[`{0}`](struct.{0}.html)", slice_name);
+    let ref_doc_url = format!("[`{0}`](struct.{0}.html)", ref_name);
+
+    let mut generated = quote! {
+        /// A selection of rows inside a
+        #[doc = #slice_doc_url]
+        /// , storing the indices of the selected rows. The selected elements
+        /// can be accessed as
+        #[doc = #ref_doc_url]
+        /// without copying them.
+        #[allow(missing_debug_implementations)]
+        #visibility struct #selection_name #impl_generics #where_clause {
+            slice: #slice_name #ty_generics,
+            indices: Vec<usize>,
+        }
+
+        #[allow(dead_code)]
+        impl #impl_generics #selection_name #ty_generics #where_clause {
+            /// Get the number of selected elements
+            pub fn len(&self) -> usize {
+                self.indices.len()
+            }
+
+            /// Check if this selection is empty
+            pub fn is_empty(&self) -> bool {
+                self.indices.is_empty()
+            }
+
+            /// Get the indices of the selected elements inside the original
+            /// slice
+            pub fn indices(&self) -> &[usize] {
+                &self.indices
+            }
+
+            /// Get the indices of the selected elements inside the original
+            /// slice, consuming this selection
+            pub fn into_indices(self) -> Vec<usize> {
+                self.indices
+            }
+
+            /// Get the slice this selection refers to
+            pub fn slice(&self) -> #slice_name #ty_generics {
+                self.slice
+            }
+
+            /// Get the `index`-th selected element, or `None` if `index` is
+            /// out of bounds for this selection.
+            pub fn get(&self, index: usize) -> Option<#ref_name #ty_generics> {
+                let index = *self.indices.get(index)?;
+                Some(<usize as #krate::SoAIndex<#slice_name #ty_generics>>::index(index, self.slice))
+            }
+
+            /// Get the `index`-th selected element.
+            ///
+            /// # Panics
+            ///
+            /// If `index` is out of bounds for this selection.
+            pub fn index(&self, index: usize) -> #ref_name #ty_generics {
+                <usize as #krate::SoAIndex<#slice_name #ty_generics>>::index(self.indices[index], self.slice)
+            }
+
+            /// Iterate over the selected elements, in the order of the
+            /// indices
+            pub fn iter(&self) -> impl ExactSizeIterator<Item = #ref_name #ty_generics> + DoubleEndedIterator + '_ {
+                let slice = self.slice;
+                self.indices.iter().map(move |&index| {
+                    <usize as #krate::SoAIndex<#slice_name #ty_generics>>::index(index, slice)
+                })
+            }
+        }
+
+        #[allow(dead_code)]
+        impl #impl_generics #slice_name #ty_generics #where_clause {
+            /// Select the elements for which the corresponding entry in `mask`
+            /// is `true`, without copying them.
+            ///
+            /// # Panics
+            ///
+            /// If `mask` and the slice have different lengths.
+            pub fn select_by_mask(&self, mask: &[bool]) -> #selection_name #ty_generics {
+                assert_eq!(
+                    mask.len(), self.len(),
+                    "mask length ({}) does not match slice length ({})",
+                    mask.len(), self.len()
+                );
+                let indices = mask.iter().enumerate()
+                    .filter(|(_, &keep)| keep)
+                    .map(|(index, _)| index)
+                    .collect();
+                #selection_name { slice: *self, indices }
+            }
+
+            /// Select the elements at the given `indices`, without copying
+            /// them. Indices can be repeated.
+            ///
+            /// # Panics
+            ///
+            /// If any of the indices is out of bounds.
+            pub fn select(&self, indices: Vec<usize>) -> #selection_name #ty_generics {
+                let len = self.len();
+                for &index in &indices {
+                    assert!(index < len, "selection index {} is out of bounds for length {}", index, len);
+                }
+                #selection_name { slice: *self, indices }
+            }
+        }
+
+        #[allow(dead_code)]
+        impl #owned_impl_generics #vec_name #owned_ty_generics #owned_where_clause {
+            /// Select the elements for which the corresponding entry in `mask`
+            /// is `true`, without copying them. See `select_by_mask` on the
+            /// slice type.
+            ///
+            /// # Panics
+            ///
+            /// If `mask` and the vector have different lengths.
+            pub fn select_by_mask(&self, mask: &[bool]) -> #selection_name #ty_generics_anon {
+                self.as_slice().select_by_mask(mask)
+            }
+
+            /// Select the elements at the given `indices`, without copying
+            /// them. See `select` on the slice type.
+            ///
+            /// # Panics
+            ///
+            /// If any of the indices is out of bounds.
+            pub fn select(&self, indices: Vec<usize>) -> #selection_name #ty_generics_anon {
+                self.as_slice().select(indices)
+            }
+        }
+    };
+
+    if input.attrs.derive_clone {
+        generated.append_all(quote! {
+            #[allow(dead_code)]
+            impl #impl_generics #selection_name #ty_generics
+            where
+                #( #where_predicates, )*
+                #( for<'b> #fields_types: Clone, )*
+            {
+                /// Create a new vector containing a copy of the selected
+                /// elements
+                pub fn to_vec(&self) -> #vec_name #owned_ty_generics {
+                    unsafe { self.slice.gather_unchecked(&self.indices) }
+                }
+            }
+        });
+    }
+
+    return generated;
+}
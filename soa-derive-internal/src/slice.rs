@@ -7,20 +7,31 @@ use crate::input::Input;
 use crate::names;
 
 pub fn derive(input: &Input) -> TokenStream {
+    let krate = &input.attrs.crate_path;
     let name = &input.name;
     let visibility = &input.visibility;
-    let slice_name = names::slice_name(&input.name);
+    let slice_name = &input.attrs.names.slice;
     let attrs = &input.attrs.slice;
-    let vec_name = names::vec_name(&input.name);
-    let ref_name = names::ref_name(&input.name);
-    let ptr_name = names::ptr_name(&input.name);
+    let vec_name = &input.attrs.names.vec;
+    let ref_name = &input.attrs.names.ref_;
+    let ptr_name = &input.attrs.names.ptr;
+
+    let generics = input.generics_with_lifetime("'a");
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let ty_generics_b = input.ty_generics_with_lifetime("'b");
+    let ty_generics_anon = input.ty_generics_with_lifetime("'_");
+    let (_, owned_ty_generics, _) = input.generics.split_for_impl();
+    let where_predicates = input.where_predicates();
 
     let slice_name_str = format!("[{}]", input.name);
     let doc_url = format!("[`{0}`](struct.{0}.html)", input.name);
     let vec_doc_url = format!("[`{0}`](struct.{0}.html)", vec_name);
 
-    let fields_names = &input.fields.iter()
-        .map(|field| field.ident.as_ref().unwrap())
+    let fields_names = &input.fields_names();
+    let marker = input.marker_init();
+
+    let fields_types = &input.fields.iter()
+        .map(|field| &field.ty)
         .collect::<Vec<_>>();
 
     let first_field = &fields_names[0];
@@ -36,8 +47,8 @@ pub fn derive(input: &Input) -> TokenStream {
 
     let slice_fields_types = input.map_fields_nested_or(
         |_, field_type| {
-            let slice_type = names::slice_name(field_type);
-            quote! { #slice_type<'a> }
+            let slice_type = names::nested_type(field_type, names::slice_name, Some("'a"));
+            quote! { #slice_type }
         },
         |_, field_type| quote! { &'a [#field_type] },
     ).collect::<Vec<_>>();
@@ -49,12 +60,41 @@ pub fn derive(input: &Input) -> TokenStream {
 
     let slice_from_raw_parts = input.map_fields_nested_or(
         |ident, field_type| {
-            let slice_type = names::slice_name(field_type);
-            quote! { #slice_type::from_raw_parts(data.#ident, len) }
+            let slice_type = names::nested_type(field_type, names::slice_name, Some("'a"));
+            quote! { <#slice_type>::from_raw_parts(data.#ident, len) }
         },
         |ident, _| quote! { ::std::slice::from_raw_parts(data.#ident, len) },
     ).collect::<Vec<_>>();
 
+    let mut nested_ord = input.map_fields_nested_or(
+        |_, field_type| {
+            let field_ref_type = names::nested_type(field_type, names::ref_name, Some("'b"));
+            quote! { for<'b> #field_ref_type: Ord }
+        },
+        |_, _| quote! {},
+    ).filter(|stream| !stream.is_empty()).collect::<Vec<_>>();
+    nested_ord.push(quote! { for<'b> #ref_name #ty_generics_b: Ord });
+
+    let gather_unchecked = input.map_fields_nested_or(
+        |ident, _| quote! { self.#ident.gather_unchecked(indices) },
+        |ident, _| quote! { indices.iter().map(|&index| self.#ident.get_unchecked(index).clone()).collect() },
+    ).collect::<Vec<_>>();
+
+    let filter_by_mask = input.map_fields_nested_or(
+        |ident, _| quote! { self.#ident.filter_by_mask(mask) },
+        |ident, _| quote! {
+            self.#ident.iter().zip(mask).filter(|(_, &keep)| keep).map(|(value, _)| value.clone()).collect()
+        },
+    ).collect::<Vec<_>>();
+
+    let slice_fields_docs = fields_names.iter().map(|field_name| quote! {
+        /// slice of `
+        #[doc = stringify!(#field_name)]
+        ///` inside a
+        #[doc = #vec_doc_url]
+    }).collect::<Vec<_>>();
+    let slice_body = input.struct_body(where_clause, &slice_fields_docs, |attrs| &attrs.slice, &slice_fields_types);
+
     let mut generated = quote! {
         /// A slice of
         #[doc = #doc_url]
@@ -62,21 +102,28 @@ pub fn derive(input: &Input) -> TokenStream {
         #[doc = #vec_doc_url]
         /// .
         #[allow(dead_code)]
-        #[derive(Copy, Clone)]
         #(#[#attrs])*
-        #[derive(Default)]
-        #visibility struct #slice_name<'a> {
-            #(
-                /// slice of `
-                #[doc = stringify!(#fields_names)]
-                ///` inside a
-                #[doc = #vec_doc_url]
-                pub #fields_names: #slice_fields_types,
-            )*
+        #visibility struct #slice_name #impl_generics #slice_body
+
+        impl #impl_generics Copy for #slice_name #ty_generics #where_clause {}
+
+        impl #impl_generics Clone for #slice_name #ty_generics #where_clause {
+            fn clone(&self) -> Self {
+                *self
+            }
+        }
+
+        impl #impl_generics Default for #slice_name #ty_generics #where_clause {
+            fn default() -> Self {
+                #slice_name {
+                    #( #fields_names: Default::default(), )*
+                    #marker
+                }
+            }
         }
 
         #[allow(dead_code)]
-        impl<'a> #slice_name<'a> {
+        impl #impl_generics #slice_name #ty_generics #where_clause {
             /// Similar to [`&
             #[doc = #slice_name_str]
             /// ::len()`](https://doc.rust-lang.org/std/primitive.slice.html#method.len),
@@ -100,29 +147,29 @@ pub fn derive(input: &Input) -> TokenStream {
             /// Similar to [`&
             #[doc = #slice_name_str]
             /// ::first()`](https://doc.rust-lang.org/std/primitive.slice.html#method.first).
-            pub fn first(&self) -> Option<#ref_name<'a>> {
+            pub fn first(&self) -> Option<#ref_name #ty_generics> {
                 if self.is_empty() {
                     None
                 } else {
                     #(
                         let #fields_names_hygienic_1 = self.#fields_names.first().unwrap();
                     )*
-                    Some(#ref_name{#(#fields_names: #fields_names_hygienic_1),*})
+                    Some(#ref_name{#(#fields_names: #fields_names_hygienic_1,)* #marker})
                 }
             }
 
             /// Similar to [`&
             #[doc = #slice_name_str]
             /// ::split_first()`](https://doc.rust-lang.org/std/primitive.slice.html#method.split_first).
-            pub fn split_first(&self) -> Option<(#ref_name<'a>, #slice_name<'a>)> {
+            pub fn split_first(&self) -> Option<(#ref_name #ty_generics, #slice_name #ty_generics)> {
                 if self.is_empty() {
                     None
                 } else {
                     #(
                         let (#fields_names_hygienic_1, #fields_names_hygienic_2) = self.#fields_names.split_first().unwrap();
                     )*
-                    let ref_ = #ref_name{#(#fields_names: #fields_names_hygienic_1),*};
-                    let slice = #slice_name{#(#fields_names: #fields_names_hygienic_2),*};
+                    let ref_ = #ref_name{#(#fields_names: #fields_names_hygienic_1,)* #marker};
+                    let slice = #slice_name{#(#fields_names: #fields_names_hygienic_2,)* #marker};
                     Some((ref_, slice))
                 }
             }
@@ -130,29 +177,29 @@ pub fn derive(input: &Input) -> TokenStream {
             /// Similar to [`&
             #[doc = #slice_name_str]
             /// ::last()`](https://doc.rust-lang.org/std/primitive.slice.html#method.last).
-            pub fn last(&self) -> Option<#ref_name<'a>> {
+            pub fn last(&self) -> Option<#ref_name #ty_generics> {
                 if self.is_empty() {
                     None
                 } else {
                     #(
                         let #fields_names_hygienic_1 = self.#fields_names.last().unwrap();
                     )*
-                    Some(#ref_name{#(#fields_names: #fields_names_hygienic_1),*})
+                    Some(#ref_name{#(#fields_names: #fields_names_hygienic_1,)* #marker})
                 }
             }
 
             /// Similar to [`&
             #[doc = #slice_name_str]
             /// ::split_last()`](https://doc.rust-lang.org/std/primitive.slice.html#method.split_last).
-            pub fn split_last(&self) -> Option<(#ref_name<'a>, #slice_name<'a>)> {
+            pub fn split_last(&self) -> Option<(#ref_name #ty_generics, #slice_name #ty_generics)> {
                 if self.is_empty() {
                     None
                 } else {
                     #(
                         let (#fields_names_hygienic_1, #fields_names_hygienic_2) = self.#fields_names.split_last().unwrap();
                     )*
-                    let ref_ = #ref_name{#(#fields_names: #fields_names_hygienic_1),*};
-                    let slice = #slice_name{#(#fields_names: #fields_names_hygienic_2),*};
+                    let ref_ = #ref_name{#(#fields_names: #fields_names_hygienic_1,)* #marker};
+                    let slice = #slice_name{#(#fields_names: #fields_names_hygienic_2,)* #marker};
                     Some((ref_, slice))
                 }
             }
@@ -160,12 +207,12 @@ pub fn derive(input: &Input) -> TokenStream {
             /// Similar to [`&
             #[doc = #slice_name_str]
             /// ::split_at()`](https://doc.rust-lang.org/std/primitive.slice.html#method.split_at).
-            pub fn split_at(&self, mid: usize) -> (#slice_name<'a>, #slice_name<'a>) {
+            pub fn split_at(&self, mid: usize) -> (#slice_name #ty_generics, #slice_name #ty_generics) {
                 #(
                     let (#fields_names_hygienic_1, #fields_names_hygienic_2) = self.#fields_names.split_at(mid);
                 )*
-                let left = #slice_name{#(#fields_names: #fields_names_hygienic_1),*};
-                let right = #slice_name{#(#fields_names: #fields_names_hygienic_2),*};
+                let left = #slice_name{#(#fields_names: #fields_names_hygienic_1,)* #marker};
+                let right = #slice_name{#(#fields_names: #fields_names_hygienic_2,)* #marker};
                 (left, right)
             }
 
@@ -174,10 +221,10 @@ pub fn derive(input: &Input) -> TokenStream {
             /// ::get()`](https://doc.rust-lang.org/std/primitive.slice.html#method.get).
             pub fn get<'b, I>(&'b self, index: I) -> Option<I::RefOutput>
             where
-                I: ::soa_derive::SoAIndex<#slice_name<'b>>,
+                I: #krate::SoAIndex<#slice_name #ty_generics_b>,
                 'a: 'b
             {
-                let slice: #slice_name<'b> = self.reborrow();
+                let slice: #slice_name #ty_generics_b = self.reborrow();
                 index.get(slice)
             }
 
@@ -186,10 +233,10 @@ pub fn derive(input: &Input) -> TokenStream {
             /// ::get_unchecked()`](https://doc.rust-lang.org/std/primitive.slice.html#method.get_unchecked).
             pub unsafe fn get_unchecked<'b, I>(&'b self, index: I) -> I::RefOutput
             where
-                I: ::soa_derive::SoAIndex<#slice_name<'b>>,
+                I: #krate::SoAIndex<#slice_name #ty_generics_b>,
                 'a: 'b
             {
-                let slice: #slice_name<'b> = self.reborrow();
+                let slice: #slice_name #ty_generics_b = self.reborrow();
                 index.get_unchecked(slice)
             }
 
@@ -201,52 +248,196 @@ pub fn derive(input: &Input) -> TokenStream {
             /// This is required because we cannot implement `std::ops::Index` directly since it requires returning a reference.
             pub fn index<'b, I>(&'b self, index: I) -> I::RefOutput
             where
-                I: ::soa_derive::SoAIndex<#slice_name<'b>>,
+                I: #krate::SoAIndex<#slice_name #ty_generics_b>,
                 'a: 'b
             {
-                let slice: #slice_name<'b> = self.reborrow();
+                let slice: #slice_name #ty_generics_b = self.reborrow();
                 index.index(slice)
             }
 
             /// Reborrows the slices in a narrower lifetime
-            pub fn reborrow<'b>(&'b self) -> #slice_name<'b>
+            pub fn reborrow<'b>(&'b self) -> #slice_name #ty_generics_b
             where
                 'a: 'b
             {
                 #slice_name {
                     #( #fields_names: #slice_reborrow, )*
+                    #marker
                 }
             }
 
             /// Similar to [`&
             #[doc = #slice_name_str]
             /// ::as_ptr()`](https://doc.rust-lang.org/std/primitive.slice.html#method.as_ptr).
-            pub fn as_ptr(&self) -> #ptr_name {
+            pub fn as_ptr(&self) -> #ptr_name #owned_ty_generics {
                 #ptr_name {
                     #(#fields_names: self.#fields_names.as_ptr(),)*
+                    #marker
                 }
             }
 
             /// Similar to [`std::slice::from_raw_parts()`](https://doc.rust-lang.org/std/slice/fn.from_raw_parts.html).
-            pub unsafe fn from_raw_parts<'b>(data: #ptr_name, len: usize) -> #slice_name<'b> {
+            pub unsafe fn from_raw_parts<'b>(data: #ptr_name #owned_ty_generics, len: usize) -> #slice_name #ty_generics_b {
                 #slice_name {
                     #( #fields_names: #slice_from_raw_parts, )*
+                    #marker
                 }
             }
+
+            /// Similar to [`&
+            #[doc = #slice_name_str]
+            /// ::binary_search_by()`](https://doc.rust-lang.org/std/primitive.slice.html#method.binary_search_by).
+            pub fn binary_search_by<F>(&self, mut f: F) -> Result<usize, usize>
+            where
+                F: FnMut(#ref_name #ty_generics_anon) -> ::std::cmp::Ordering,
+            {
+                let mut left = 0;
+                let mut right = self.len();
+                while left < right {
+                    let mid = left + (right - left) / 2;
+                    match f(self.index(mid)) {
+                        ::std::cmp::Ordering::Less => left = mid + 1,
+                        ::std::cmp::Ordering::Greater => right = mid,
+                        ::std::cmp::Ordering::Equal => return Ok(mid),
+                    }
+                }
+                Err(left)
+            }
+
+            /// Similar to [`&
+            #[doc = #slice_name_str]
+            /// ::binary_search_by_key()`](https://doc.rust-lang.org/std/primitive.slice.html#method.binary_search_by_key).
+            pub fn binary_search_by_key<B, F>(&self, key: &B, mut f: F) -> Result<usize, usize>
+            where
+                F: FnMut(#ref_name #ty_generics_anon) -> B,
+                B: Ord,
+            {
+                self.binary_search_by(|element| f(element).cmp(key))
+            }
+
+            /// Similar to [`&
+            #[doc = #slice_name_str]
+            /// ::partition_point()`](https://doc.rust-lang.org/std/primitive.slice.html#method.partition_point).
+            pub fn partition_point<P>(&self, mut pred: P) -> usize
+            where
+                P: FnMut(#ref_name #ty_generics_anon) -> bool,
+            {
+                self.binary_search_by(|element| {
+                    if pred(element) {
+                        ::std::cmp::Ordering::Less
+                    } else {
+                        ::std::cmp::Ordering::Greater
+                    }
+                }).unwrap_or_else(|index| index)
+            }
+
+            /// Get the indices that would sort this slice according to the
+            /// comparator `f`, without moving any element. The sort is
+            /// stable.
+            ///
+            /// The returned indices can be passed to `permute` on this slice,
+            /// or on any other slice of the same length, to apply the
+            /// ordering.
+            pub fn argsort_by<F>(&self, mut f: F) -> Vec<usize>
+            where
+                F: FnMut(#ref_name #ty_generics_anon, #ref_name #ty_generics_anon) -> ::std::cmp::Ordering,
+            {
+                let mut indices: Vec<usize> = (0..self.len()).collect();
+                indices.sort_by(|j, k| f(self.index(*j), self.index(*k)));
+                indices
+            }
+
+            /// Get the indices that would sort this slice according to the
+            /// key extracted by `f`, without moving any element. The sort is
+            /// stable.
+            pub fn argsort_by_key<F, K>(&self, mut f: F) -> Vec<usize>
+            where
+                F: FnMut(#ref_name #ty_generics_anon) -> K,
+                K: Ord,
+            {
+                let mut indices: Vec<usize> = (0..self.len()).collect();
+                indices.sort_by_key(|i| f(self.index(*i)));
+                indices
+            }
         }
 
+        #[allow(dead_code)]
+        impl #impl_generics #slice_name #ty_generics
+        where
+            #( #where_predicates, )*
+            #( #nested_ord, )*
+        {
+            /// Similar to [`&
+            #[doc = #slice_name_str]
+            /// ::binary_search()`](https://doc.rust-lang.org/std/primitive.slice.html#method.binary_search).
+            pub fn binary_search(&self, value: &#name #owned_ty_generics) -> Result<usize, usize> {
+                self.binary_search_by(|element| element.cmp(&value.as_ref()))
+            }
+        }
     };
 
     if input.attrs.derive_clone {
         generated.append_all(quote!{
             #[allow(dead_code)]
-            impl<'a> #slice_name<'a> {
+            impl #impl_generics #slice_name #ty_generics
+            where
+                #( #where_predicates, )*
+                #( for<'b> #fields_types: Clone, )*
+            {
                 /// Similar to [`&
                 #[doc = #slice_name_str]
                 /// ::to_vec()`](https://doc.rust-lang.org/std/primitive.slice.html#method.to_vec).
-                pub fn to_vec(&self) -> #vec_name {
+                pub fn to_vec(&self) -> #vec_name #owned_ty_generics {
                     #vec_name {
                         #(#fields_names: self.#fields_names.to_vec(),)*
+                        #marker
+                    }
+                }
+
+                /// Create a new vector containing a copy of the elements at
+                /// the given `indices`, in the same order. Indices can be
+                /// repeated. Each field is copied in a separate pass.
+                ///
+                /// # Panics
+                ///
+                /// If any of the indices is out of bounds.
+                pub fn gather(&self, indices: &[usize]) -> #vec_name #owned_ty_generics {
+                    let len = self.len();
+                    for &index in indices {
+                        assert!(index < len, "gather index {} is out of bounds for length {}", index, len);
+                    }
+                    unsafe { self.gather_unchecked(indices) }
+                }
+
+                /// Same as `gather`, without checking the indices.
+                ///
+                /// # Safety
+                ///
+                /// All the indices must be smaller than `self.len()`.
+                pub unsafe fn gather_unchecked(&self, indices: &[usize]) -> #vec_name #owned_ty_generics {
+                    #vec_name {
+                        #(#fields_names: #gather_unchecked,)*
+                        #marker
+                    }
+                }
+
+                /// Create a new vector containing a copy of the elements for
+                /// which the corresponding entry in `mask` is `true`. Each
+                /// field is copied in a separate pass, so the mask can be
+                /// computed from a single field without loading the others.
+                ///
+                /// # Panics
+                ///
+                /// If `mask` and the slice have different lengths.
+                pub fn filter_by_mask(&self, mask: &[bool]) -> #vec_name #owned_ty_generics {
+                    assert_eq!(
+                        mask.len(), self.len(),
+                        "mask length ({}) does not match slice length ({})",
+                        mask.len(), self.len()
+                    );
+                    #vec_name {
+                        #(#fields_names: #filter_by_mask,)*
+                        #marker
                     }
                 }
             }
@@ -254,8 +445,12 @@ pub fn derive(input: &Input) -> TokenStream {
 
         {
             generated.append_all(quote! {
-                impl<'a> ::soa_derive::ToSoAVec<#name> for #slice_name<'a> {
-                    type SoAVecType = #vec_name;
+                impl #impl_generics #krate::ToSoAVec<#name #owned_ty_generics> for #slice_name #ty_generics
+                where
+                    #( #where_predicates, )*
+                    #( for<'b> #fields_types: Clone, )*
+                {
+                    type SoAVecType = #vec_name #owned_ty_generics;
 
                     fn to_vec(&self) -> Self::SoAVecType {
                         self.to_vec()
@@ -269,16 +464,24 @@ pub fn derive(input: &Input) -> TokenStream {
 }
 
 pub fn derive_mut(input: &Input) -> TokenStream {
+    let krate = &input.attrs.crate_path;
     let name = &input.name;
     let visibility = &input.visibility;
-    let slice_name = names::slice_name(&input.name);
-    let slice_mut_name = names::slice_mut_name(&input.name);
-    let vec_name = names::vec_name(&input.name);
+    let slice_name = &input.attrs.names.slice;
+    let slice_mut_name = &input.attrs.names.slice_mut;
+    let vec_name = &input.attrs.names.vec;
     let attrs = &input.attrs.slice_mut;
-    let ref_name = names::ref_name(&input.name);
-    let ref_mut_name = names::ref_mut_name(&input.name);
-    let ptr_name = names::ptr_name(&input.name);
-    let ptr_mut_name = names::ptr_mut_name(&input.name);
+    let ref_name = &input.attrs.names.ref_;
+    let ref_mut_name = &input.attrs.names.ref_mut;
+    let ptr_name = &input.attrs.names.ptr;
+    let ptr_mut_name = &input.attrs.names.ptr_mut;
+
+    let generics = input.generics_with_lifetime("'a");
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let ty_generics_b = input.ty_generics_with_lifetime("'b");
+    let ty_generics_anon = input.ty_generics_with_lifetime("'_");
+    let (_, owned_ty_generics, _) = input.generics.split_for_impl();
+    let where_predicates = input.where_predicates();
 
     let slice_name_str = format!("[{}]", input.name);
     let doc_url = format!("[`{0}`](struct.{0}.html)", input.name);
@@ -286,8 +489,11 @@ pub fn derive_mut(input: &Input) -> TokenStream {
     let slice_mut_doc_url = format!("[`{0}`](struct.{0}.html)", slice_mut_name);
     let vec_doc_url = format!("[`{0}`](struct.{0}.html)", vec_name);
 
-    let fields_names = &input.fields.iter()
-        .map(|field| field.ident.clone().unwrap())
+    let fields_names = &input.fields_names();
+    let marker = input.marker_init();
+
+    let fields_types = &input.fields.iter()
+        .map(|field| &field.ty)
         .collect::<Vec<_>>();
 
     let first_field = &fields_names[0];
@@ -302,8 +508,8 @@ pub fn derive_mut(input: &Input) -> TokenStream {
 
     let slice_mut_fields_types = input.map_fields_nested_or(
         |_, field_type| {
-            let slice_type = names::slice_mut_name(field_type);
-            quote! { #slice_type<'a> }
+            let slice_type = names::nested_type(field_type, names::slice_mut_name, Some("'a"));
+            quote! { #slice_type }
         },
         |_, field_type| quote! { &'a mut [#field_type] },
     ).collect::<Vec<_>>();
@@ -325,26 +531,48 @@ pub fn derive_mut(input: &Input) -> TokenStream {
 
     let slice_from_raw_parts_mut = input.map_fields_nested_or(
         |ident, field_type| {
-            let slice_type = names::slice_mut_name(field_type);
-            quote! { #slice_type::from_raw_parts_mut(data.#ident, len) }
+            let slice_type = names::nested_type(field_type, names::slice_mut_name, Some("'a"));
+            quote! { <#slice_type>::from_raw_parts_mut(data.#ident, len) }
         },
         |ident, _| quote! {::std::slice::from_raw_parts_mut(data.#ident, len) },
     ).collect::<Vec<_>>();
 
     let mut nested_ord = input.map_fields_nested_or(
         |_, field_type| {
-            let field_ref_type = names::ref_name(field_type);
-            quote! { for<'b> #field_ref_type<'b>: Ord }
+            let field_ref_type = names::nested_type(field_type, names::ref_name, Some("'b"));
+            quote! { for<'b> #field_ref_type: Ord }
         },
         |_, _| quote! {},
     ).filter(|stream| !stream.is_empty()).collect::<Vec<_>>();
-    nested_ord.push(quote! { for<'b> #ref_name<'b>: Ord });
+    nested_ord.push(quote! { for<'b> #ref_name #ty_generics_b: Ord });
+
+    let scatter_from_unchecked = input.map_fields_nested_or(
+        |ident, _| quote! { self.#ident.scatter_from_unchecked(indices, values.#ident) },
+        |ident, _| quote! {
+            for (&index, value) in indices.iter().zip(values.#ident) {
+                self.#ident.get_unchecked_mut(index).clone_from(value);
+            }
+        },
+    ).collect::<Vec<_>>();
 
     let apply_permutation = input.map_fields_nested_or(
         |ident, _| quote! { self.#ident.__private_apply_permutation(permutation) },
         |ident, _| quote! { permutation.apply_slice_in_place(&mut self.#ident) },
     ).collect::<Vec<_>>();
 
+    let apply_index = input.map_fields_nested_or(
+        |ident, _| quote! { self.#ident.__private_apply_index_in_place(indices, cycles) },
+        |ident, _| quote! { #krate::__private_apply_index_in_place(&mut *self.#ident, indices, cycles) },
+    ).collect::<Vec<_>>();
+
+    let slice_mut_fields_docs = fields_names.iter().map(|field_name| quote! {
+        /// slice of `
+        #[doc = stringify!(#field_name)]
+        ///` inside a
+        #[doc = #vec_doc_url]
+    }).collect::<Vec<_>>();
+    let slice_mut_body = input.struct_body(where_clause, &slice_mut_fields_docs, |attrs| &attrs.slice_mut, &slice_mut_fields_types);
+
     let mut generated = quote! {
         /// A mutable slice of
         #[doc = #doc_url]
@@ -353,28 +581,29 @@ pub fn derive_mut(input: &Input) -> TokenStream {
         /// .
         #[allow(dead_code)]
         #(#[#attrs])*
-        #[derive(Default)]
-        #visibility struct #slice_mut_name<'a> {
-            #(
-                /// slice of `
-                #[doc = stringify!(#fields_names)]
-                ///` inside a
-                #[doc = #vec_doc_url]
-                pub #fields_names: #slice_mut_fields_types,
-            )*
+        #visibility struct #slice_mut_name #impl_generics #slice_mut_body
+
+        impl #impl_generics Default for #slice_mut_name #ty_generics #where_clause {
+            fn default() -> Self {
+                #slice_mut_name {
+                    #( #fields_names: Default::default(), )*
+                    #marker
+                }
+            }
         }
 
         #[allow(dead_code)]
-        impl<'a> #slice_mut_name<'a> {
+        impl #impl_generics #slice_mut_name #ty_generics #where_clause {
             /// Convert a
             #[doc = #slice_mut_doc_url]
             /// to a
             #[doc = #slice_doc_url]
             /// in order to be able to use the methods on the non mutable
             /// version of the slices.
-            pub fn as_ref(&self) -> #slice_name {
+            pub fn as_ref(&self) -> #slice_name #ty_generics_anon {
                 #slice_name {
                     #( #fields_names: #slice_as_ref, )*
+                    #marker
                 }
             }
 
@@ -401,14 +630,14 @@ pub fn derive_mut(input: &Input) -> TokenStream {
             /// Similar to [`&mut
             #[doc = #slice_name_str]
             /// ::first_mut()`](https://doc.rust-lang.org/std/primitive.slice.html#method.first_mut).
-            pub fn first_mut(&mut self) -> Option<#ref_mut_name> {
+            pub fn first_mut(&mut self) -> Option<#ref_mut_name #ty_generics_anon> {
                 if self.is_empty() {
                     None
                 } else {
                     #(
-                        let #fields_names = self.#fields_names.first_mut().unwrap();
+                        let #fields_names_hygienic_1 = self.#fields_names.first_mut().unwrap();
                     )*
-                    Some(#ref_mut_name{#(#fields_names: #fields_names),*})
+                    Some(#ref_mut_name{#(#fields_names: #fields_names_hygienic_1,)* #marker})
                 }
             }
 
@@ -419,15 +648,15 @@ pub fn derive_mut(input: &Input) -> TokenStream {
             /// The main difference is that this function consumes the slice.
             /// You should use [`Self::reborrow()`] first if you want the
             /// returned values to have a shorter lifetime.
-            pub fn split_first_mut(mut self) -> Option<(#ref_mut_name<'a>, #slice_mut_name<'a>)> {
+            pub fn split_first_mut(mut self) -> Option<(#ref_mut_name #ty_generics, #slice_mut_name #ty_generics)> {
                 if self.is_empty() {
                     None
                 } else {
                     #(
-                        let (#fields_names, #fields_names_hygienic_1) = self.#fields_names.split_first_mut().unwrap();
+                        let (#fields_names_hygienic_1, #fields_names_hygienic_2) = self.#fields_names.split_first_mut().unwrap();
                     )*
-                    let ref_ = #ref_mut_name{#(#fields_names: #fields_names),*};
-                    let slice = #slice_mut_name{#(#fields_names: #fields_names_hygienic_1),*};
+                    let ref_ = #ref_mut_name{#(#fields_names: #fields_names_hygienic_1,)* #marker};
+                    let slice = #slice_mut_name{#(#fields_names: #fields_names_hygienic_2,)* #marker};
                     Some((ref_, slice))
                 }
             }
@@ -435,14 +664,14 @@ pub fn derive_mut(input: &Input) -> TokenStream {
             /// Similar to [`&mut
             #[doc = #slice_name_str]
             /// ::last_mut()`](https://doc.rust-lang.org/std/primitive.slice.html#method.last_mut).
-            pub fn last_mut(&mut self) -> Option<#ref_mut_name> {
+            pub fn last_mut(&mut self) -> Option<#ref_mut_name #ty_generics_anon> {
                 if self.is_empty() {
                     None
                 } else {
                     #(
-                        let #fields_names = self.#fields_names.last_mut().unwrap();
+                        let #fields_names_hygienic_1 = self.#fields_names.last_mut().unwrap();
                     )*
-                    Some(#ref_mut_name{#(#fields_names: #fields_names),*})
+                    Some(#ref_mut_name{#(#fields_names: #fields_names_hygienic_1,)* #marker})
                 }
             }
 
@@ -453,15 +682,15 @@ pub fn derive_mut(input: &Input) -> TokenStream {
             /// The main difference is that this function consumes the slice.
             /// You should use [`Self::reborrow()`] first if you want the
             /// returned values to have a shorter lifetime.
-            pub fn split_last_mut(mut self) -> Option<(#ref_mut_name<'a>, #slice_mut_name<'a>)> {
+            pub fn split_last_mut(mut self) -> Option<(#ref_mut_name #ty_generics, #slice_mut_name #ty_generics)> {
                 if self.is_empty() {
                     None
                 } else {
                     #(
-                        let (#fields_names, #fields_names_hygienic_1) = self.#fields_names.split_last_mut().unwrap();
+                        let (#fields_names_hygienic_1, #fields_names_hygienic_2) = self.#fields_names.split_last_mut().unwrap();
                     )*
-                    let ref_ = #ref_mut_name{#(#fields_names: #fields_names),*};
-                    let slice = #slice_mut_name{#(#fields_names: #fields_names_hygienic_1),*};
+                    let ref_ = #ref_mut_name{#(#fields_names: #fields_names_hygienic_1,)* #marker};
+                    let slice = #slice_mut_name{#(#fields_names: #fields_names_hygienic_2,)* #marker};
                     Some((ref_, slice))
                 }
             }
@@ -473,12 +702,12 @@ pub fn derive_mut(input: &Input) -> TokenStream {
             /// The main difference is that this function consumes the slice.
             /// You should use [`Self::reborrow()`] first if you want the
             /// returned values to have a shorter lifetime.
-            pub fn split_at_mut(mut self, mid: usize) -> (#slice_mut_name<'a>, #slice_mut_name<'a>) {
+            pub fn split_at_mut(mut self, mid: usize) -> (#slice_mut_name #ty_generics, #slice_mut_name #ty_generics) {
                 #(
                     let (#fields_names_hygienic_1, #fields_names_hygienic_2) = self.#fields_names.split_at_mut(mid);
                 )*
-                let left = #slice_mut_name{#(#fields_names: #fields_names_hygienic_1),*};
-                let right = #slice_mut_name{#(#fields_names: #fields_names_hygienic_2),*};
+                let left = #slice_mut_name{#(#fields_names: #fields_names_hygienic_1,)* #marker};
+                let right = #slice_mut_name{#(#fields_names: #fields_names_hygienic_2,)* #marker};
                 (left, right)
             }
 
@@ -491,15 +720,64 @@ pub fn derive_mut(input: &Input) -> TokenStream {
                 )*
             }
 
+            /// Similar to [`&mut
+            #[doc = #slice_name_str]
+            /// ::reverse()`](https://doc.rust-lang.org/std/primitive.slice.html#method.reverse).
+            pub fn reverse(&mut self) {
+                #(
+                    self.#fields_names.reverse();
+                )*
+            }
+
+            /// Similar to [`&mut
+            #[doc = #slice_name_str]
+            /// ::rotate_left()`](https://doc.rust-lang.org/std/primitive.slice.html#method.rotate_left).
+            pub fn rotate_left(&mut self, mid: usize) {
+                #(
+                    self.#fields_names.rotate_left(mid);
+                )*
+            }
+
+            /// Similar to [`&mut
+            #[doc = #slice_name_str]
+            /// ::rotate_right()`](https://doc.rust-lang.org/std/primitive.slice.html#method.rotate_right).
+            pub fn rotate_right(&mut self, k: usize) {
+                #(
+                    self.#fields_names.rotate_right(k);
+                )*
+            }
+
+            /// Similar to [`&mut
+            #[doc = #slice_name_str]
+            /// ::swap_with_slice()`](https://doc.rust-lang.org/std/primitive.slice.html#method.swap_with_slice).
+            pub fn swap_with_slice(&mut self, other: #slice_mut_name #ty_generics_anon) {
+                assert_eq!(self.len(), other.len(), "destination and source slices have different lengths");
+                #(
+                    self.#fields_names.swap_with_slice(other.#fields_names);
+                )*
+            }
+
+            /// Similar to [`&mut
+            #[doc = #slice_name_str]
+            /// ::fill_with()`](https://doc.rust-lang.org/std/primitive.slice.html#method.fill_with).
+            pub fn fill_with<F>(&mut self, mut f: F)
+            where
+                F: FnMut() -> #name #owned_ty_generics,
+            {
+                for mut element in self.iter_mut() {
+                    element.replace(f());
+                }
+            }
+
             /// Similar to [`&
             #[doc = #slice_name_str]
             /// ::get()`](https://doc.rust-lang.org/std/primitive.slice.html#method.get).
             pub fn get<'b, I>(&'b self, index: I) -> Option<I::RefOutput>
             where
-                I: ::soa_derive::SoAIndex<#slice_name<'b>>,
+                I: #krate::SoAIndex<#slice_name #ty_generics_b>,
                 'a: 'b
             {
-                let slice: #slice_name<'b> = self.as_slice();
+                let slice: #slice_name #ty_generics_b = self.as_slice();
                 index.get(slice)
             }
 
@@ -508,10 +786,10 @@ pub fn derive_mut(input: &Input) -> TokenStream {
             /// ::get_unchecked()`](https://doc.rust-lang.org/std/primitive.slice.html#method.get_unchecked).
             pub unsafe fn get_unchecked<'b, I>(&'b self, index: I) -> I::RefOutput
             where
-                I: ::soa_derive::SoAIndex<#slice_name<'b>>,
+                I: #krate::SoAIndex<#slice_name #ty_generics_b>,
                 'a: 'b
             {
-                let slice: #slice_name<'b> = self.as_slice();
+                let slice: #slice_name #ty_generics_b = self.as_slice();
                 index.get_unchecked(slice)
             }
 
@@ -524,10 +802,10 @@ pub fn derive_mut(input: &Input) -> TokenStream {
             /// This is required because we cannot implement that trait.
             pub fn index<'b, I>(&'b self, index: I) -> I::RefOutput
             where
-                I: ::soa_derive::SoAIndex<#slice_name<'b>>,
+                I: #krate::SoAIndex<#slice_name #ty_generics_b>,
                 'a: 'b
             {
-                let slice: #slice_name<'b> = self.as_slice();
+                let slice: #slice_name #ty_generics_b = self.as_slice();
                 index.index(slice)
             }
 
@@ -536,10 +814,10 @@ pub fn derive_mut(input: &Input) -> TokenStream {
             /// ::get_mut()`](https://doc.rust-lang.org/std/primitive.slice.html#method.get_mut).
             pub fn get_mut<'b, I>(&'b mut self, index: I) -> Option<I::MutOutput>
             where
-                I: ::soa_derive::SoAIndexMut<#slice_mut_name<'b>>,
+                I: #krate::SoAIndexMut<#slice_mut_name #ty_generics_b>,
                 'a: 'b
             {
-                let slice: #slice_mut_name<'b> = self.reborrow();
+                let slice: #slice_mut_name #ty_generics_b = self.reborrow();
                 index.get_mut(slice)
             }
 
@@ -548,10 +826,10 @@ pub fn derive_mut(input: &Input) -> TokenStream {
             /// ::get_unchecked_mut()`](https://doc.rust-lang.org/std/primitive.slice.html#method.get_unchecked_mut).
             pub unsafe fn get_unchecked_mut<'b, I>(&'b mut self, index: I) -> I::MutOutput
             where
-                I: ::soa_derive::SoAIndexMut<#slice_mut_name<'b>>,
+                I: #krate::SoAIndexMut<#slice_mut_name #ty_generics_b>,
                 'a: 'b
             {
-                let slice: #slice_mut_name<'b> = self.reborrow();
+                let slice: #slice_mut_name #ty_generics_b = self.reborrow();
                 index.get_unchecked_mut(slice)
             }
 
@@ -563,62 +841,67 @@ pub fn derive_mut(input: &Input) -> TokenStream {
             /// This is required because we cannot implement `std::ops::IndexMut` directly since it requires returning a mutable reference.
             pub fn index_mut<'b, I>(&'b mut self, index: I) -> I::MutOutput
             where
-                I: ::soa_derive::SoAIndexMut<#slice_mut_name<'b>>,
+                I: #krate::SoAIndexMut<#slice_mut_name #ty_generics_b>,
                 'a: 'b
             {
-                let slice: #slice_mut_name<'b> = self.reborrow();
+                let slice: #slice_mut_name #ty_generics_b = self.reborrow();
                 index.index_mut(slice)
             }
 
             /// Returns a non-mutable slice from this mutable slice.
-            pub fn as_slice<'b>(&'b self) -> #slice_name<'b>
+            pub fn as_slice<'b>(&'b self) -> #slice_name #ty_generics_b
             where
                 'a: 'b
             {
                 #slice_name {
                     #( #fields_names: #slice_as_slice, )*
+                    #marker
                 }
             }
 
             /// Reborrows the slices in a narrower lifetime
-            pub fn reborrow<'b>(&'b mut self) -> #slice_mut_name<'b>
+            pub fn reborrow<'b>(&'b mut self) -> #slice_mut_name #ty_generics_b
             where
                 'a: 'b
             {
                 #slice_mut_name {
                     #( #fields_names: #slice_reborrow, )*
+                    #marker
                 }
             }
 
             /// Similar to [`&
             #[doc = #slice_name_str]
             /// ::as_ptr()`](https://doc.rust-lang.org/std/primitive.slice.html#method.as_ptr).
-            pub fn as_ptr(&self) -> #ptr_name {
+            pub fn as_ptr(&self) -> #ptr_name #owned_ty_generics {
                 #ptr_name {
                     #(#fields_names: self.#fields_names.as_ptr(),)*
+                    #marker
                 }
             }
 
             /// Similar to [`&mut
             #[doc = #slice_name_str]
             /// ::as_mut_ptr()`](https://doc.rust-lang.org/std/primitive.slice.html#method.as_mut_ptr).
-            pub fn as_mut_ptr(&mut self) -> #ptr_mut_name {
+            pub fn as_mut_ptr(&mut self) -> #ptr_mut_name #owned_ty_generics {
                 #ptr_mut_name {
                     #(#fields_names: self.#fields_names.as_mut_ptr(),)*
+                    #marker
                 }
             }
 
             /// Similar to [`std::slice::from_raw_parts_mut()`](https://doc.rust-lang.org/std/slice/fn.from_raw_parts_mut.html).
-            pub unsafe fn from_raw_parts_mut<'b>(data: #ptr_mut_name, len: usize) -> #slice_mut_name<'b> {
+            pub unsafe fn from_raw_parts_mut<'b>(data: #ptr_mut_name #owned_ty_generics, len: usize) -> #slice_mut_name #ty_generics_b {
                 #slice_mut_name {
                     #( #fields_names: #slice_from_raw_parts_mut, )*
+                    #marker
                 }
             }
 
             #[doc(hidden)]
             /// This is `pub` due to there will be compile-error if `#[nested_soa]` is used.
             /// Do not use this method directly.
-            pub fn __private_apply_permutation(&mut self, permutation: &mut soa_derive::Permutation) {
+            pub fn __private_apply_permutation(&mut self, permutation: &mut #krate::Permutation) {
                 #( #apply_permutation; )*
             }
 
@@ -627,9 +910,9 @@ pub fn derive_mut(input: &Input) -> TokenStream {
             /// ::sort_by()`](https://doc.rust-lang.org/std/primitive.slice.html#method.sort_by).
             pub fn sort_by<F>(&mut self, mut f: F)
             where
-                F: FnMut(#ref_name, #ref_name) -> std::cmp::Ordering,
+                F: FnMut(#ref_name #ty_generics_anon, #ref_name #ty_generics_anon) -> std::cmp::Ordering,
             {
-                use soa_derive::Permutation;
+                use #krate::Permutation;
 
                 let mut permutation: Vec<usize> = (0..self.len()).collect();
                 permutation.sort_by(|j, k| f(self.index(*j), self.index(*k)));
@@ -643,10 +926,10 @@ pub fn derive_mut(input: &Input) -> TokenStream {
             /// ::sort_by_key()`](https://doc.rust-lang.org/std/primitive.slice.html#method.sort_by_key).
             pub fn sort_by_key<F, K>(&mut self, mut f: F)
             where
-                F: FnMut(#ref_name) -> K,
+                F: FnMut(#ref_name #ty_generics_anon) -> K,
                 K: Ord,
             {
-                use soa_derive::Permutation;
+                use #krate::Permutation;
 
                 let mut permutation: Vec<usize> = (0..self.len()).collect();
                 permutation.sort_by_key(|i| f(self.index(*i)));
@@ -654,18 +937,195 @@ pub fn derive_mut(input: &Input) -> TokenStream {
                 let mut permutation = Permutation::oneline(permutation).inverse();
                 self.__private_apply_permutation(&mut permutation);
             }
+
+            #[doc(hidden)]
+            /// Move the element at `indices[i]` to position `i` in every
+            /// column, without checking `indices`. This is `pub` to be called
+            /// on the nested slices of structs using this one with
+            /// `#[nested_soa]`.
+            /// Do not use this method directly.
+            ///
+            /// # Safety
+            ///
+            /// `indices` must be a permutation of `0..self.len()`, and `cycles`
+            /// must be the corresponding result of `__private_permutation_cycles`.
+            pub unsafe fn __private_apply_index_in_place(&mut self, indices: &[usize], cycles: &[usize]) {
+                #( #apply_index; )*
+            }
+
+            /// Similar to [`&mut
+            #[doc = #slice_name_str]
+            /// ::sort_unstable_by()`](https://doc.rust-lang.org/std/primitive.slice.html#method.sort_unstable_by).
+            pub fn sort_unstable_by<F>(&mut self, mut f: F)
+            where
+                F: FnMut(#ref_name #ty_generics_anon, #ref_name #ty_generics_anon) -> std::cmp::Ordering,
+            {
+                let mut indices: Vec<usize> = (0..self.len()).collect();
+                indices.sort_unstable_by(|j, k| f(self.index(*j), self.index(*k)));
+                self.permute(&indices);
+            }
+
+            /// Similar to [`&mut
+            #[doc = #slice_name_str]
+            /// ::sort_unstable_by_key()`](https://doc.rust-lang.org/std/primitive.slice.html#method.sort_unstable_by_key).
+            pub fn sort_unstable_by_key<F, K>(&mut self, mut f: F)
+            where
+                F: FnMut(#ref_name #ty_generics_anon) -> K,
+                K: Ord,
+            {
+                let mut indices: Vec<usize> = (0..self.len()).collect();
+                indices.sort_unstable_by_key(|i| f(self.index(*i)));
+                self.permute(&indices);
+            }
+
+            /// Similar to [`&mut
+            #[doc = #slice_name_str]
+            /// ::sort_by_cached_key()`](https://doc.rust-lang.org/std/primitive.slice.html#method.sort_by_cached_key).
+            pub fn sort_by_cached_key<F, K>(&mut self, mut f: F)
+            where
+                F: FnMut(#ref_name #ty_generics_anon) -> K,
+                K: Ord,
+            {
+                let mut indices: Vec<usize> = (0..self.len()).collect();
+                indices.sort_by_cached_key(|i| f(self.index(*i)));
+                self.permute(&indices);
+            }
+
+            /// Similar to [`&mut
+            #[doc = #slice_name_str]
+            /// ::select_nth_unstable_by()`](https://doc.rust-lang.org/std/primitive.slice.html#method.select_nth_unstable_by).
+            pub fn select_nth_unstable_by<F>(&mut self, index: usize, mut f: F) -> (#slice_mut_name #ty_generics_anon, #ref_mut_name #ty_generics_anon, #slice_mut_name #ty_generics_anon)
+            where
+                F: FnMut(#ref_name #ty_generics_anon, #ref_name #ty_generics_anon) -> std::cmp::Ordering,
+            {
+                let mut indices: Vec<usize> = (0..self.len()).collect();
+                indices.select_nth_unstable_by(index, |j, k| f(self.index(*j), self.index(*k)));
+                self.permute(&indices);
+
+                let (left, rest) = self.reborrow().split_at_mut(index);
+                let (nth, right) = rest.split_first_mut().expect("index is in bounds");
+                (left, nth, right)
+            }
+
+            /// Similar to [`&mut
+            #[doc = #slice_name_str]
+            /// ::binary_search_by()`](https://doc.rust-lang.org/std/primitive.slice.html#method.binary_search_by).
+            pub fn binary_search_by<F>(&self, f: F) -> Result<usize, usize>
+            where
+                F: FnMut(#ref_name #ty_generics_anon) -> std::cmp::Ordering,
+            {
+                self.as_slice().binary_search_by(f)
+            }
+
+            /// Similar to [`&mut
+            #[doc = #slice_name_str]
+            /// ::binary_search_by_key()`](https://doc.rust-lang.org/std/primitive.slice.html#method.binary_search_by_key).
+            pub fn binary_search_by_key<B, F>(&self, key: &B, f: F) -> Result<usize, usize>
+            where
+                F: FnMut(#ref_name #ty_generics_anon) -> B,
+                B: Ord,
+            {
+                self.as_slice().binary_search_by_key(key, f)
+            }
+
+            /// Similar to [`&mut
+            #[doc = #slice_name_str]
+            /// ::partition_point()`](https://doc.rust-lang.org/std/primitive.slice.html#method.partition_point).
+            pub fn partition_point<P>(&self, pred: P) -> usize
+            where
+                P: FnMut(#ref_name #ty_generics_anon) -> bool,
+            {
+                self.as_slice().partition_point(pred)
+            }
+
+            /// Get the indices that would stably sort this slice according to
+            /// the comparator `f`, without moving any element.
+            pub fn argsort_by<F>(&self, f: F) -> Vec<usize>
+            where
+                F: FnMut(#ref_name #ty_generics_anon, #ref_name #ty_generics_anon) -> std::cmp::Ordering,
+            {
+                self.as_slice().argsort_by(f)
+            }
+
+            /// Get the indices that would stably sort this slice according to
+            /// the key extracted by `f`, without moving any element.
+            pub fn argsort_by_key<F, K>(&self, f: F) -> Vec<usize>
+            where
+                F: FnMut(#ref_name #ty_generics_anon) -> K,
+                K: Ord,
+            {
+                self.as_slice().argsort_by_key(f)
+            }
+
+            /// Panic if `indices` is not a permutation of `0..self.len()`, this
+            /// is used by `permute_inverse`.
+            fn __private_check_permutation(&self, indices: &[usize]) {
+                assert_eq!(
+                    indices.len(), self.len(),
+                    "permutation length ({}) does not match slice length ({})",
+                    indices.len(), self.len()
+                );
+                let mut seen = vec![false; indices.len()];
+                for &index in indices {
+                    assert!(index < seen.len(), "permutation index {} is out of bounds", index);
+                    assert!(!seen[index], "permutation index {} is repeated", index);
+                    seen[index] = true;
+                }
+            }
+
+            /// Re-order the elements of this slice so that the element
+            /// previously at `indices[i]` ends up at position `i`. This is the
+            /// operation that sorts the slice when given the output of
+            /// `argsort_by`.
+            ///
+            /// # Panics
+            ///
+            /// If `indices` is not a permutation of `0..self.len()`.
+            pub fn permute(&mut self, indices: &[usize]) {
+                assert_eq!(
+                    indices.len(), self.len(),
+                    "permutation length ({}) does not match slice length ({})",
+                    indices.len(), self.len()
+                );
+                let cycles = #krate::__private_permutation_cycles(indices);
+                // SAFETY: `indices` is a permutation of `0..self.len()`, as
+                // checked by `__private_permutation_cycles`
+                unsafe {
+                    self.__private_apply_index_in_place(indices, &cycles);
+                }
+            }
+
+            /// Re-order the elements of this slice so that the element
+            /// previously at position `i` ends up at `indices[i]`. This undoes
+            /// `permute` called with the same `indices`.
+            ///
+            /// # Panics
+            ///
+            /// If `indices` is not a permutation of `0..self.len()`.
+            pub fn permute_inverse(&mut self, indices: &[usize]) {
+                self.__private_check_permutation(indices);
+                let mut indices = indices.to_vec();
+                for i in 0..indices.len() {
+                    while indices[i] != i {
+                        let target = indices[i];
+                        self.swap(i, target);
+                        indices.swap(i, target);
+                    }
+                }
+            }
         }
 
         #[allow(dead_code)]
-        impl<'a> #slice_mut_name<'a>
+        impl #impl_generics #slice_mut_name #ty_generics
         where
+            #( #where_predicates, )*
             #( #nested_ord, )*
         {
             /// Similar to [`&mut
             #[doc = #slice_name_str]
             /// ::sort()`](https://doc.rust-lang.org/std/primitive.slice.html#method.sort).
             pub fn sort(&mut self) {
-                use soa_derive::Permutation;
+                use #krate::Permutation;
 
                 let mut permutation: Vec<usize> = (0..self.len()).collect();
                 permutation.sort_by_key(|i| self.index(*i));
@@ -673,28 +1133,107 @@ pub fn derive_mut(input: &Input) -> TokenStream {
                 let mut permutation = Permutation::oneline(permutation).inverse();
                 self.__private_apply_permutation(&mut permutation);
             }
+
+            /// Similar to [`&mut
+            #[doc = #slice_name_str]
+            /// ::sort_unstable()`](https://doc.rust-lang.org/std/primitive.slice.html#method.sort_unstable).
+            pub fn sort_unstable(&mut self) {
+                let mut indices: Vec<usize> = (0..self.len()).collect();
+                indices.sort_unstable_by(|j, k| self.index(*j).cmp(&self.index(*k)));
+                self.permute(&indices);
+            }
+
+            /// Similar to [`&mut
+            #[doc = #slice_name_str]
+            /// ::binary_search()`](https://doc.rust-lang.org/std/primitive.slice.html#method.binary_search).
+            pub fn binary_search(&self, value: &#name #owned_ty_generics) -> Result<usize, usize> {
+                self.as_slice().binary_search(value)
+            }
         }
     };
 
     if input.attrs.derive_clone {
         generated.append_all(quote!{
             #[allow(dead_code)]
-            impl<'a> #slice_mut_name<'a> {
+            impl #impl_generics #slice_mut_name #ty_generics
+            where
+                #( #where_predicates, )*
+                #( for<'b> #fields_types: Clone, )*
+            {
                 /// Similar to [`&
                 #[doc = #slice_name_str]
                 /// ::to_vec()`](https://doc.rust-lang.org/std/primitive.slice.html#method.to_vec).
-                pub fn to_vec(&self) -> #vec_name {
+                pub fn to_vec(&self) -> #vec_name #owned_ty_generics {
                     #vec_name {
                         #(#fields_names: self.#fields_names.to_vec(),)*
+                        #marker
                     }
                 }
+
+                /// Create a new vector containing a copy of the elements at
+                /// the given `indices`, in the same order. Indices can be
+                /// repeated. Each field is copied in a separate pass.
+                ///
+                /// # Panics
+                ///
+                /// If any of the indices is out of bounds.
+                pub fn gather(&self, indices: &[usize]) -> #vec_name #owned_ty_generics {
+                    self.as_slice().gather(indices)
+                }
+
+                /// Same as `gather`, without checking the indices.
+                ///
+                /// # Safety
+                ///
+                /// All the indices must be smaller than `self.len()`.
+                pub unsafe fn gather_unchecked(&self, indices: &[usize]) -> #vec_name #owned_ty_generics {
+                    self.as_slice().gather_unchecked(indices)
+                }
+
+                /// Overwrite the elements at the given `indices` with a copy
+                /// of the corresponding element in `values`, *i.e.* the
+                /// element at `indices[i]` is set to `values[i]`. If an index
+                /// is repeated, the last corresponding value is kept. Each
+                /// field is written in a separate pass.
+                ///
+                /// # Panics
+                ///
+                /// If `indices` and `values` have different lengths, or if any
+                /// of the indices is out of bounds.
+                pub fn scatter_from(&mut self, indices: &[usize], values: #slice_name #ty_generics_anon) {
+                    assert_eq!(
+                        indices.len(), values.len(),
+                        "scatter indices length ({}) does not match values length ({})",
+                        indices.len(), values.len()
+                    );
+                    let len = self.len();
+                    for &index in indices {
+                        assert!(index < len, "scatter index {} is out of bounds for length {}", index, len);
+                    }
+                    unsafe { self.scatter_from_unchecked(indices, values) }
+                }
+
+                /// Same as `scatter_from`, without checking the indices.
+                ///
+                /// # Safety
+                ///
+                /// All the indices must be smaller than `self.len()`. If
+                /// `indices` and `values` have different lengths, the
+                /// additional entries in the longest one are ignored.
+                pub unsafe fn scatter_from_unchecked(&mut self, indices: &[usize], values: #slice_name #ty_generics_anon) {
+                    #( #scatter_from_unchecked; )*
+                }
             }
         });
 
         {
             generated.append_all(quote! {
-                impl<'a> ::soa_derive::ToSoAVec<#name> for #slice_mut_name<'a> {
-                    type SoAVecType = #vec_name;
+                impl #impl_generics #krate::ToSoAVec<#name #owned_ty_generics> for #slice_mut_name #ty_generics
+                where
+                    #( #where_predicates, )*
+                    #( for<'b> #fields_types: Clone, )*
+                {
+                    type SoAVecType = #vec_name #owned_ty_generics;
 
                     fn to_vec(&self) -> Self::SoAVecType {
                         self.to_vec()
@@ -1,46 +1,82 @@
 use proc_macro2::{Ident, Span, TokenStream};
-use quote::TokenStreamExt;
+use quote::{ToTokens, TokenStreamExt};
 use quote::quote;
+use syn::{parse_quote, GenericParam};
 
-use crate::input::Input;
+use crate::input::{Input, TokenStreamIterator};
 use crate::names;
 
 pub fn derive(input: &Input) -> TokenStream {
+    let krate = &input.attrs.crate_path;
     let name = &input.name;
     let vec_name_str = format!("Vec<{}>", name);
+    let slice_name_str = format!("[{}]", name);
     let attrs = &input.attrs.vec;
     let visibility = &input.visibility;
-    let vec_name = names::vec_name(&input.name);
-    let slice_name = names::slice_name(name);
-    let slice_mut_name = names::slice_mut_name(&input.name);
-    let ref_name = names::ref_name(&input.name);
-    let ref_mut_name = names::ref_mut_name(&input.name);
-    let ptr_name = names::ptr_name(&input.name);
-    let ptr_mut_name = names::ptr_mut_name(&input.name);
+    let vec_name = &input.attrs.names.vec;
+    let slice_name = &input.attrs.names.slice;
+    let slice_mut_name = &input.attrs.names.slice_mut;
+    let ref_name = &input.attrs.names.ref_;
+    let ref_mut_name = &input.attrs.names.ref_mut;
+    let ptr_name = &input.attrs.names.ptr;
+    let ptr_mut_name = &input.attrs.names.ptr_mut;
+    let drain_name = &input.attrs.names.drain;
+    let splice_iter_name = &input.attrs.names.splice;
+    let extract_if_name = &input.attrs.names.extract_if;
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let ty_generics_anon = input.ty_generics_with_lifetime("'_");
+    let ty_generics_t = input.ty_generics_with_lifetime("'t");
+    let generics_a = input.generics_with_lifetime("'a");
+    let (impl_generics_a, ty_generics_a, where_clause_a) = generics_a.split_for_impl();
+    let mut extract_if_generics = generics_a.clone();
+    extract_if_generics.params.push(parse_quote! { F });
+    extract_if_generics.make_where_clause().predicates.push(parse_quote! {
+        F: FnMut(#ref_mut_name #ty_generics_anon) -> bool
+    });
+    let (extract_if_impl_generics, extract_if_ty_generics, extract_if_where_clause) = extract_if_generics.split_for_impl();
+    // generic arguments of the input struct, to be followed by the filter
+    let ty_params = input.generics.params.iter().map(|param| match param {
+        GenericParam::Lifetime(param) => param.lifetime.to_token_stream(),
+        GenericParam::Type(param) => param.ident.to_token_stream(),
+        GenericParam::Const(param) => param.ident.to_token_stream(),
+    }).collect::<Vec<_>>();
+    let where_predicates = input.where_predicates();
 
     let doc_url = format!("[`{0}`](struct.{0}.html)", input.name);
 
-    let fields_names = &input.fields.iter()
-        .map(|field| field.ident.as_ref().unwrap())
-        .collect::<Vec<_>>();
+    let fields_names = &input.fields_names();
+    let marker = input.marker_init();
+    // the iterators wrapping the fields iterators also need the marker
+    let marker_field = input.marker.as_ref().map(|marker| quote! { , #marker });
+    let marker_value = input.marker.as_ref().map(|_| quote! { , ::std::marker::PhantomData });
 
     let fields_names_hygienic = input.fields.iter()
         .enumerate()
         .map(|(i, _)| Ident::new(&format!("___soa_derive_private_{}", i), Span::call_site()))
         .collect::<Vec<_>>();
 
+    let original_fields_names = &input.original_fields_names;
+    let construct_hygienic = input.construct(&fields_names_hygienic);
+    let drop_skipped_value = input.drop_skipped_fields(&quote! { value });
+    let drop_skipped_element = input.drop_skipped_fields(&quote! { element });
+
     let first_field = &fields_names[0];
 
+    let fields_types = &input.fields.iter()
+        .map(|field| &field.ty)
+        .collect::<Vec<_>>();
+
     let vec_fields_types = input.map_fields_nested_or(
         |_, field_type| {
-            let vec_type = names::vec_name(field_type);
+            let vec_type = names::nested_type(field_type, names::vec_name, None);
             quote! { #vec_type }
         },
         |_, field_type| quote! { Vec<#field_type> },
     ).collect::<Vec<_>>();
 
     let vec_with_capacity = input.map_fields_nested_or(
-        |_, field_type| quote! { <#field_type as StructOfArray>::Type::with_capacity(capacity) },
+        |_, field_type| quote! { <#field_type as #krate::StructOfArray>::Type::with_capacity(capacity) },
         |_, _| quote! { Vec::with_capacity(capacity) },
     ).collect::<Vec<_>>();
 
@@ -56,41 +92,122 @@ pub fn derive(input: &Input) -> TokenStream {
 
     let vec_from_raw_parts = input.map_fields_nested_or(
         |ident, field_type| {
-            let vec_type = names::vec_name(field_type);
-            quote! { #vec_type::from_raw_parts(data.#ident, len, capacity) }
+            let vec_type = names::nested_type(field_type, names::vec_name, None);
+            quote! { <#vec_type>::from_raw_parts(data.#ident, len, capacity) }
         },
         |ident, _| quote! { Vec::from_raw_parts(data.#ident, len, capacity) },
     ).collect::<Vec<_>>();
 
+    let range_from_bounds = range_from_bounds();
+
+    let drain_type = input.map_fields_nested_or(
+        |_, field_type| {
+            let drain_type = names::nested_type(field_type, names::drain_name, Some("'a"));
+            quote! { #drain_type }
+        },
+        |_, field_type| quote! { ::std::vec::Drain<'a, #field_type> },
+    ).concat_by(
+        |seq, next| { quote! { ::std::iter::Zip<#seq, #next> } }
+    );
+
+    let create_drain = fields_names.iter().fold(None, |seq, ident| {
+        if let Some(seq) = seq {
+            Some(quote! { #seq.zip(self.#ident.drain(range.clone())) })
+        } else {
+            Some(quote! { self.#ident.drain(range.clone()) })
+        }
+    }).expect("should be Some");
+
+    let splice_type = input.map_fields_nested_or(
+        |_, field_type| {
+            let splice_type = names::nested_type(field_type, names::splice_name, Some("'a"));
+            quote! { #splice_type }
+        },
+        |_, field_type| quote! { ::std::vec::Splice<'a, ::std::vec::IntoIter<#field_type>> },
+    ).concat_by(
+        |seq, next| { quote! { ::std::iter::Zip<#seq, #next> } }
+    );
+
+    let create_splice = fields_names.iter().fold(None, |seq, ident| {
+        let splice = quote! { self.#ident.splice(range.clone(), ::std::mem::take(&mut replace_with.#ident)) };
+        if let Some(seq) = seq {
+            Some(quote! { #seq.zip(#splice) })
+        } else {
+            Some(splice)
+        }
+    }).expect("should be Some");
+
+    let drain_pat = fields_names_hygienic.iter().fold(None, |seq, ident| {
+        if let Some(seq) = seq {
+            Some(quote! { (#seq, #ident) })
+        } else {
+            Some(quote!{ #ident })
+        }
+    }).expect("should be Some");
+
+    let partial_eq_bounds = input.map_fields_nested_or(
+        |_, field_type| {
+            let field_ref_mut_type = names::nested_type(field_type, names::ref_mut_name, Some("'b"));
+            quote! { for<'b> #field_ref_mut_type: PartialEq }
+        },
+        |_, field_type| quote! { for<'b> #field_type: PartialEq },
+    ).collect::<Vec<_>>();
+
+    let mut nested_ord = input.map_fields_nested_or(
+        |_, field_type| {
+            let field_ref_type = names::nested_type(field_type, names::ref_name, Some("'b"));
+            quote! { for<'b> #field_ref_type: Ord }
+        },
+        |_, _| quote! {},
+    ).filter(|stream| !stream.is_empty()).collect::<Vec<_>>();
+    let ref_ty_generics_b = input.ty_generics_with_lifetime("'b");
+    nested_ord.push(quote! { for<'b> #ref_name #ref_ty_generics_b: Ord });
+
+    let retain_by_mask = input.map_fields_nested_or(
+        |ident, _| quote! { self.#ident.retain_by_mask(mask) },
+        |ident, _| quote! {
+            let mut keep = mask.iter();
+            self.#ident.retain(|_| *keep.next().expect("mask has the same length as the vector"))
+        },
+    ).collect::<Vec<_>>();
+
     let vec_replace = input.map_fields_nested_or(
         |ident, _| quote! { self.#ident.replace(index, field) },
         |ident, _| quote! { ::std::mem::replace(&mut self.#ident[index], field) },
     ).collect::<Vec<_>>();
 
+    let vec_fields_docs = fields_names.iter().map(|field_name| quote! {
+        /// a vector of `
+        #[doc = stringify!(#field_name)]
+        ///` from a
+        #[doc = #doc_url]
+    }).collect::<Vec<_>>();
+    let vec_body = input.struct_body(where_clause, &vec_fields_docs, |attrs| &attrs.vec, &vec_fields_types);
+
     let mut generated = quote! {
         /// An analog to `
         #[doc = #vec_name_str]
         /// ` with Struct of Array (SoA) layout
         #[allow(dead_code)]
         #(#[#attrs])*
-        #[derive(Default)]
-        #visibility struct #vec_name {
-            #(
-                /// a vector of `
-                #[doc = stringify!(#fields_names)]
-                ///` from a
-                #[doc = #doc_url]
-                pub #fields_names: #vec_fields_types,
-            )*
+        #visibility struct #vec_name #impl_generics #vec_body
+
+        impl #impl_generics Default for #vec_name #ty_generics #where_clause {
+            fn default() -> Self {
+                #vec_name {
+                    #( #fields_names: Default::default(), )*
+                    #marker
+                }
+            }
         }
 
         #[allow(dead_code)]
         #[allow(clippy::forget_non_drop)]
-        impl #vec_name {
+        impl #impl_generics #vec_name #ty_generics #where_clause {
             /// Similar to [`
             #[doc = #vec_name_str]
             /// ::new()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.new)
-            pub fn new() -> #vec_name {
+            pub fn new() -> #vec_name #ty_generics {
                 Default::default()
             }
 
@@ -98,9 +215,10 @@ pub fn derive(input: &Input) -> TokenStream {
             #[doc = #vec_name_str]
             /// ::with_capacity()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.with_capacity),
             /// initializing all fields with the given `capacity`.
-            pub fn with_capacity(capacity: usize) -> #vec_name {
+            pub fn with_capacity(capacity: usize) -> #vec_name #ty_generics {
                 #vec_name {
                     #( #fields_names: #vec_with_capacity, )*
+                    #marker
                 }
             }
 
@@ -150,12 +268,13 @@ pub fn derive(input: &Input) -> TokenStream {
             #[doc = #vec_name_str]
             /// ::push()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.push).
             #[allow(clippy::forget_non_drop)]
-            pub fn push(&mut self, value: #name) {
+            pub fn push(&mut self, value: #name #ty_generics) {
                 // We need to use ptr read/write instead of moving out of the
                 // fields in case the value struct implements Drop.
                 unsafe {
-                    #(self.#fields_names.push(::std::ptr::read(&value.#fields_names));)*
+                    #(self.#fields_names.push(::std::ptr::read(&value.#original_fields_names));)*
                 }
+                #drop_skipped_value
                 // if value implements Drop, we don't want to run it here, only
                 // when the vec itself will be dropped.
                 ::std::mem::forget(value);
@@ -184,18 +303,18 @@ pub fn derive(input: &Input) -> TokenStream {
             /// Similar to [`
             #[doc = #vec_name_str]
             /// ::swap_remove()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.swap_remove).
-            pub fn swap_remove(&mut self, index: usize) -> #name {
+            pub fn swap_remove(&mut self, index: usize) -> #name #ty_generics {
                 #(
                     let #fields_names_hygienic = self.#fields_names.swap_remove(index);
                 )*
-                #name{#(#fields_names: #fields_names_hygienic),*}
+                #construct_hygienic
             }
 
             /// Similar to [`
             #[doc = #vec_name_str]
             /// ::insert()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.insert).
             #[allow(clippy::forget_non_drop)]
-            pub fn insert(&mut self, index: usize, element: #name) {
+            pub fn insert(&mut self, index: usize, element: #name #ty_generics) {
                 if index > self.len() {
                     panic!("index out of bounds: the len is {} but the index is {}", self.len(), index);
                 }
@@ -203,8 +322,9 @@ pub fn derive(input: &Input) -> TokenStream {
                 // similar to push, we can not use move and have to rely on ptr
                 // read/write
                 unsafe {
-                    #(self.#fields_names.insert(index, ::std::ptr::read(&element.#fields_names));)*
+                    #(self.#fields_names.insert(index, ::std::ptr::read(&element.#original_fields_names));)*
                 }
+                #drop_skipped_element
                 // if value implements Drop, we don't want to run it here, only
                 // when the vec itself will be dropped.
                 ::std::mem::forget(element);
@@ -212,7 +332,7 @@ pub fn derive(input: &Input) -> TokenStream {
 
             /// Similar to [`std::mem::replace()`](https://doc.rust-lang.org/std/mem/fn.replace.html).
             #[allow(clippy::forget_non_drop)]
-            pub fn replace(&mut self, index: usize, element: #name) -> #name {
+            pub fn replace(&mut self, index: usize, element: #name #ty_generics) -> #name #ty_generics {
                 if index > self.len() {
                     panic!("index out of bounds: the len is {} but the index is {}", self.len(), index);
                 }
@@ -220,44 +340,45 @@ pub fn derive(input: &Input) -> TokenStream {
                 // similar to push, we can not use move and have to rely on ptr
                 // read/write
                 #(
-                    let field = unsafe { ::std::ptr::read(&element.#fields_names) };
+                    let field = unsafe { ::std::ptr::read(&element.#original_fields_names) };
                     let #fields_names_hygienic = #vec_replace;
                 )*
+                #drop_skipped_element
                 // if value implements Drop, we don't want to run it here, only
                 // when the vec itself will be dropped.
                 ::std::mem::forget(element);
 
-                #name{#(#fields_names: #fields_names_hygienic),*}
+                #construct_hygienic
             }
 
             /// Similar to [`
             #[doc = #vec_name_str]
             /// ::remove()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.remove).
-            pub fn remove(&mut self, index: usize) -> #name {
+            pub fn remove(&mut self, index: usize) -> #name #ty_generics {
                 #(
                     let #fields_names_hygienic = self.#fields_names.remove(index);
                 )*
-                #name{#(#fields_names: #fields_names_hygienic),*}
+                #construct_hygienic
             }
 
             /// Similar to [`
             #[doc = #vec_name_str]
             /// ::pop()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.pop).
-            pub fn pop(&mut self) -> Option<#name> {
+            pub fn pop(&mut self) -> Option<#name #ty_generics> {
                 if self.is_empty() {
                     None
                 } else {
                     #(
                         let #fields_names_hygienic = self.#fields_names.pop().unwrap();
                     )*
-                    Some(#name{#(#fields_names: #fields_names_hygienic),*})
+                    Some(#construct_hygienic)
                 }
             }
 
             /// Similar to [`
             #[doc = #vec_name_str]
             /// ::append()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.append).
-            pub fn append(&mut self, other: &mut #vec_name) {
+            pub fn append(&mut self, other: &mut #vec_name #ty_generics) {
                 #(
                     self.#fields_names.append(&mut other.#fields_names);
                 )*
@@ -273,50 +394,204 @@ pub fn derive(input: &Input) -> TokenStream {
             /// Similar to [`
             #[doc = #vec_name_str]
             /// ::split_off()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.split_off).
-            pub fn split_off(&mut self, at: usize) -> #vec_name {
+            pub fn split_off(&mut self, at: usize) -> #vec_name #ty_generics {
                 #vec_name {
                     #(#fields_names: self.#fields_names.split_off(at), )*
+                    #marker
                 }
             }
 
+            /// Similar to [`
+            #[doc = #vec_name_str]
+            /// ::drain()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.drain),
+            /// removing the given `range` from all fields. If the returned
+            /// iterator is dropped before being fully consumed, the remaining
+            /// values in the range are dropped and removed as well.
+            pub fn drain<R>(&mut self, range: R) -> #drain_name #ty_generics_anon
+            where
+                R: ::std::ops::RangeBounds<usize>
+            {
+                #range_from_bounds
+                // the first field checks the range, so all fields are either
+                // drained together or not at all
+                #drain_name(#create_drain #marker_value)
+            }
+
+            /// Similar to [`
+            #[doc = #vec_name_str]
+            /// ::splice()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.splice),
+            /// replacing the given `range` with the values in `replace_with`
+            /// and returning the removed values. The new values are collected
+            /// before modifying this vector, so each field is only moved
+            /// once. As for `Vec`, the replacement happens when the returned
+            /// iterator is dropped.
+            pub fn splice<R, I>(&mut self, range: R, replace_with: I) -> #splice_iter_name #ty_generics_anon
+            where
+                R: ::std::ops::RangeBounds<usize>,
+                I: IntoIterator<Item = #name #ty_generics>,
+            {
+                #range_from_bounds
+                let mut replace_with = replace_with.into_iter().collect::<#vec_name #ty_generics>();
+                #splice_iter_name(#create_splice #marker_value)
+            }
+
+            /// Similar to [`
+            #[doc = #vec_name_str]
+            /// ::extract_if()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.extract_if),
+            /// returning an iterator which removes and yields the values in
+            /// `range` for which `filter` returns `true`. The other values are
+            /// moved in place in all fields during the iteration. If the
+            /// iterator is dropped before being fully consumed, the remaining
+            /// values are kept.
+            pub fn extract_if<F, R>(&mut self, range: R, filter: F) -> #extract_if_name<'_, #(#ty_params,)* F>
+            where
+                F: FnMut(#ref_mut_name #ty_generics_anon) -> bool,
+                R: ::std::ops::RangeBounds<usize>,
+            {
+                #range_from_bounds
+                let old_len = self.len();
+                if range.start > range.end || range.end > old_len {
+                    panic!("range {:?} out of bounds for length {}", range, old_len);
+                }
+
+                // set the length to 0 while iterating, if the iterator is
+                // leaked we might leak the values but never drop them twice
+                unsafe {
+                    self.set_len(0);
+                }
+
+                #extract_if_name {
+                    vec: self,
+                    idx: range.start,
+                    end: range.end,
+                    del: 0,
+                    old_len: old_len,
+                    filter: filter,
+                }
+            }
+
+            /// Similar to [`
+            #[doc = #vec_name_str]
+            /// ::set_len()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.set_len),
+            /// setting the length of all fields, with the same safety caveats.
+            pub unsafe fn set_len(&mut self, new_len: usize) {
+                #(self.#fields_names.set_len(new_len);)*
+            }
+
             /// Similar to [`
             #[doc = #vec_name_str]
             /// ::as_slice()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.as_slice).
-            pub fn as_slice(&self) -> #slice_name {
+            pub fn as_slice(&self) -> #slice_name #ty_generics_anon {
                 #slice_name {
                     #(#fields_names: self.#fields_names.as_slice(), )*
+                    #marker
                 }
             }
 
             /// Similar to [`
             #[doc = #vec_name_str]
             /// ::as_mut_slice()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.as_mut_slice).
-            pub fn as_mut_slice(&mut self) -> #slice_mut_name {
+            pub fn as_mut_slice(&mut self) -> #slice_mut_name #ty_generics_anon {
                 #slice_mut_name {
                     #(#fields_names: self.#fields_names.as_mut_slice(), )*
+                    #marker
                 }
             }
 
             /// Create a slice of this vector matching the given `range`. This
             /// is analogous to `Index<Range<usize>>`.
-            pub fn slice(&self, range: ::std::ops::Range<usize>) -> #slice_name {
+            pub fn slice(&self, range: ::std::ops::Range<usize>) -> #slice_name #ty_generics_anon {
                 #slice_name {
                     #( #fields_names: #vec_slice, )*
+                    #marker
                 }
             }
 
             /// Create a mutable slice of this vector matching the given
             /// `range`. This is analogous to `IndexMut<Range<usize>>`.
-            pub fn slice_mut(&mut self, range: ::std::ops::Range<usize>) -> #slice_mut_name {
+            pub fn slice_mut(&mut self, range: ::std::ops::Range<usize>) -> #slice_mut_name #ty_generics_anon {
                 #slice_mut_name {
                     #( #fields_names: #vec_slice_mut, )*
+                    #marker
                 }
             }
 
+            /// Similar to [`&
+            #[doc = #slice_name_str]
+            /// ::binary_search_by()`](https://doc.rust-lang.org/std/primitive.slice.html#method.binary_search_by).
+            pub fn binary_search_by<F>(&self, f: F) -> Result<usize, usize>
+            where
+                F: FnMut(#ref_name #ty_generics_anon) -> ::std::cmp::Ordering,
+            {
+                self.as_slice().binary_search_by(f)
+            }
+
+            /// Similar to [`&
+            #[doc = #slice_name_str]
+            /// ::binary_search_by_key()`](https://doc.rust-lang.org/std/primitive.slice.html#method.binary_search_by_key).
+            pub fn binary_search_by_key<B, F>(&self, key: &B, f: F) -> Result<usize, usize>
+            where
+                F: FnMut(#ref_name #ty_generics_anon) -> B,
+                B: Ord,
+            {
+                self.as_slice().binary_search_by_key(key, f)
+            }
+
+            /// Similar to [`&
+            #[doc = #slice_name_str]
+            /// ::partition_point()`](https://doc.rust-lang.org/std/primitive.slice.html#method.partition_point).
+            pub fn partition_point<P>(&self, pred: P) -> usize
+            where
+                P: FnMut(#ref_name #ty_generics_anon) -> bool,
+            {
+                self.as_slice().partition_point(pred)
+            }
+
+            /// Get the indices that would stably sort this vector according to
+            /// the comparator `f`, without moving any element.
+            pub fn argsort_by<F>(&self, f: F) -> Vec<usize>
+            where
+                F: FnMut(#ref_name #ty_generics_anon, #ref_name #ty_generics_anon) -> ::std::cmp::Ordering,
+            {
+                self.as_slice().argsort_by(f)
+            }
+
+            /// Get the indices that would stably sort this vector according to
+            /// the key extracted by `f`, without moving any element.
+            pub fn argsort_by_key<F, K>(&self, f: F) -> Vec<usize>
+            where
+                F: FnMut(#ref_name #ty_generics_anon) -> K,
+                K: Ord,
+            {
+                self.as_slice().argsort_by_key(f)
+            }
+
+            /// Re-order the elements of this vector so that the element
+            /// previously at `indices[i]` ends up at position `i`. See the
+            /// `permute` method on the mutable slice type.
+            ///
+            /// # Panics
+            ///
+            /// If `indices` is not a permutation of `0..self.len()`.
+            pub fn permute(&mut self, indices: &[usize]) {
+                self.as_mut_slice().permute(indices)
+            }
+
+            /// Re-order the elements of this vector so that the element
+            /// previously at position `i` ends up at `indices[i]`. See the
+            /// `permute_inverse` method on the mutable slice type.
+            ///
+            /// # Panics
+            ///
+            /// If `indices` is not a permutation of `0..self.len()`.
+            pub fn permute_inverse(&mut self, indices: &[usize]) {
+                self.as_mut_slice().permute_inverse(indices)
+            }
+
             /// Similar to [`
             #[doc = #vec_name_str]
             /// ::retain()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.retain).
-            pub fn retain<F>(&mut self, mut f: F) where F: FnMut(#ref_name) -> bool {
+            pub fn retain<F>(&mut self, mut f: F) where F: FnMut(#ref_name #ty_generics_anon) -> bool {
                 let len = self.len();
                 let mut del = 0;
 
@@ -338,7 +613,7 @@ pub fn derive(input: &Input) -> TokenStream {
             /// Similar to [`
             #[doc = #vec_name_str]
             /// ::retain_mut()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.retain_mut).
-            pub fn retain_mut<F>(&mut self, mut f: F) where F: FnMut(#ref_mut_name) -> bool {
+            pub fn retain_mut<F>(&mut self, mut f: F) where F: FnMut(#ref_mut_name #ty_generics_anon) -> bool {
                 let len = self.len();
                 let mut del = 0;
 
@@ -357,12 +632,63 @@ pub fn derive(input: &Input) -> TokenStream {
                 }
             }
 
+            /// Keep only the elements for which the corresponding entry in
+            /// `mask` is `true`, removing all the others. Each field is
+            /// filtered in a separate pass, so the mask can be computed from
+            /// a single field without loading the others.
+            ///
+            /// # Panics
+            ///
+            /// If `mask` and the vector have different lengths.
+            pub fn retain_by_mask(&mut self, mask: &[bool]) {
+                assert_eq!(
+                    mask.len(), self.len(),
+                    "mask length ({}) does not match vector length ({})",
+                    mask.len(), self.len()
+                );
+                #( #retain_by_mask; )*
+            }
+
+            /// Similar to [`
+            #[doc = #vec_name_str]
+            /// ::dedup_by()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.dedup_by).
+            /// The first argument to `same_bucket` is the current value and
+            /// the second one is the last value which was kept.
+            pub fn dedup_by<F>(&mut self, mut same_bucket: F) where F: for<'t> FnMut(#ref_mut_name #ty_generics_t, #ref_mut_name #ty_generics_t) -> bool {
+                let len = self.len();
+                if len <= 1 {
+                    return;
+                }
+
+                let mut kept = 1;
+                {
+                    let mut slice = self.as_mut_slice();
+                    for i in 1..len {
+                        let (mut head, mut tail) = slice.reborrow().split_at_mut(i);
+                        if !same_bucket(tail.index_mut(0), head.index_mut(kept - 1)) {
+                            if kept != i {
+                                slice.swap(kept, i);
+                            }
+                            kept += 1;
+                        }
+                    }
+                }
+                self.truncate(kept);
+            }
+
+            /// Similar to [`
+            #[doc = #vec_name_str]
+            /// ::dedup_by_key()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.dedup_by_key).
+            pub fn dedup_by_key<F, K>(&mut self, mut key: F) where F: FnMut(#ref_mut_name #ty_generics_anon) -> K, K: PartialEq {
+                self.dedup_by(|a, b| key(a) == key(b));
+            }
+
             /// Similar to [`
             #[doc = #vec_name_str]
             /// ::get<I>()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.get).
             pub fn get<'a, I>(&'a self, index: I) -> Option<I::RefOutput>
             where
-                I: ::soa_derive::SoAIndex<&'a #vec_name>
+                I: #krate::SoAIndex<&'a #vec_name #ty_generics>
             {
                 index.get(self)
             }
@@ -372,7 +698,7 @@ pub fn derive(input: &Input) -> TokenStream {
             /// ::get_unchecked<I>()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.get_unchecked).
             pub unsafe fn get_unchecked<'a, I>(&'a self, index: I) -> I::RefOutput
             where
-                I: ::soa_derive::SoAIndex<&'a #vec_name>
+                I: #krate::SoAIndex<&'a #vec_name #ty_generics>
             {
                 index.get_unchecked(self)
             }
@@ -382,7 +708,7 @@ pub fn derive(input: &Input) -> TokenStream {
             /// ::index<I>()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.index).
             pub fn index<'a, I>(&'a self, index: I) -> I::RefOutput
             where
-                I: ::soa_derive::SoAIndex<&'a #vec_name>
+                I: #krate::SoAIndex<&'a #vec_name #ty_generics>
             {
                 index.index(self)
             }
@@ -392,7 +718,7 @@ pub fn derive(input: &Input) -> TokenStream {
             /// ::get_mut<I>()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.get_mut).
             pub fn get_mut<'a, I>(&'a mut self, index: I) -> Option<I::MutOutput>
             where
-                I: ::soa_derive::SoAIndexMut<&'a mut #vec_name>
+                I: #krate::SoAIndexMut<&'a mut #vec_name #ty_generics>
             {
                 index.get_mut(self)
             }
@@ -402,7 +728,7 @@ pub fn derive(input: &Input) -> TokenStream {
             /// ::get_unchecked_mut<I>()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.get_unchecked_mut).
             pub unsafe fn get_unchecked_mut<'a, I>(&'a mut self, index: I) -> I::MutOutput
             where
-                I: ::soa_derive::SoAIndexMut<&'a mut #vec_name>
+                I: #krate::SoAIndexMut<&'a mut #vec_name #ty_generics>
             {
                 index.get_unchecked_mut(self)
             }
@@ -412,7 +738,7 @@ pub fn derive(input: &Input) -> TokenStream {
             /// ::index_mut<I>()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.index_mut).
             pub fn index_mut<'a, I>(&'a mut self, index: I) -> I::MutOutput
             where
-                I: ::soa_derive::SoAIndexMut<&'a mut #vec_name>
+                I: #krate::SoAIndexMut<&'a mut #vec_name #ty_generics>
             {
                 index.index_mut(self)
             }
@@ -420,33 +746,36 @@ pub fn derive(input: &Input) -> TokenStream {
             /// Similar to [`
             #[doc = #vec_name_str]
             /// ::as_ptr()`](https://doc.rust-lang.org/std/struct.Vec.html#method.as_ptr).
-            pub fn as_ptr(&self) -> #ptr_name {
+            pub fn as_ptr(&self) -> #ptr_name #ty_generics {
                 #ptr_name {
                     #(#fields_names: self.#fields_names.as_ptr(),)*
+                    #marker
                 }
             }
 
             /// Similar to [`
             #[doc = #vec_name_str]
             /// ::as_mut_ptr()`](https://doc.rust-lang.org/std/struct.Vec.html#method.as_mut_ptr).
-            pub fn as_mut_ptr(&mut self) -> #ptr_mut_name {
+            pub fn as_mut_ptr(&mut self) -> #ptr_mut_name #ty_generics {
                 #ptr_mut_name {
                     #(#fields_names: self.#fields_names.as_mut_ptr(),)*
+                    #marker
                 }
             }
 
             /// Similar to [`
             #[doc = #vec_name_str]
             /// ::from_raw_parts()`](https://doc.rust-lang.org/std/struct.Vec.html#method.from_raw_parts).
-            pub unsafe fn from_raw_parts(data: #ptr_mut_name, len: usize, capacity: usize) -> #vec_name {
+            pub unsafe fn from_raw_parts(data: #ptr_mut_name #ty_generics, len: usize, capacity: usize) -> #vec_name #ty_generics {
                 #vec_name {
                     #( #fields_names: #vec_from_raw_parts, )*
+                    #marker
                 }
             }
         }
 
         #[allow(clippy::drop_non_drop)]
-        impl Drop for #vec_name {
+        impl #impl_generics Drop for #vec_name #ty_generics #where_clause {
             fn drop(&mut self) {
                 while let Some(value) = self.pop() {
                     ::std::mem::drop(value);
@@ -455,21 +784,247 @@ pub fn derive(input: &Input) -> TokenStream {
         }
     };
 
+    generated.append_all(quote! {
+        #[allow(dead_code)]
+        impl #impl_generics #vec_name #ty_generics
+        where
+            #( #where_predicates, )*
+            #( #partial_eq_bounds, )*
+        {
+            /// Similar to [`
+            #[doc = #vec_name_str]
+            /// ::dedup()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.dedup),
+            /// removing consecutive values where all fields are equal.
+            pub fn dedup(&mut self) {
+                self.dedup_by(|a, b| #(a.#fields_names == b.#fields_names)&&*);
+            }
+        }
+    });
+
+    generated.append_all(quote! {
+        #[allow(dead_code)]
+        impl #impl_generics #vec_name #ty_generics
+        where
+            #( #where_predicates, )*
+            #( #nested_ord, )*
+        {
+            /// Similar to [`&
+            #[doc = #slice_name_str]
+            /// ::binary_search()`](https://doc.rust-lang.org/std/primitive.slice.html#method.binary_search).
+            pub fn binary_search(&self, value: &#name #ty_generics) -> Result<usize, usize> {
+                self.as_slice().binary_search(value)
+            }
+        }
+    });
+
+    let drain_doc_url = format!("[`{0}::drain()`](struct.{0}.html#method.drain)", vec_name);
+    generated.append_all(quote! {
+        /// Draining iterator over
+        #[doc = #doc_url]
+        /// , created by calling
+        #[doc = #drain_doc_url]
+        #[allow(missing_debug_implementations)]
+        #visibility struct #drain_name #impl_generics_a (#drain_type #marker_field) #where_clause_a;
+
+        impl #impl_generics_a Iterator for #drain_name #ty_generics_a #where_clause_a {
+            type Item = #name #ty_generics;
+
+            #[inline]
+            fn next(&mut self) -> Option<#name #ty_generics> {
+                self.0.next().map(|#drain_pat| #construct_hygienic)
+            }
+
+            #[inline]
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.0.size_hint()
+            }
+        }
+
+        impl #impl_generics_a DoubleEndedIterator for #drain_name #ty_generics_a #where_clause_a {
+            #[inline]
+            fn next_back(&mut self) -> Option<#name #ty_generics> {
+                self.0.next_back().map(|#drain_pat| #construct_hygienic)
+            }
+        }
+
+        impl #impl_generics_a ExactSizeIterator for #drain_name #ty_generics_a #where_clause_a {
+            fn len(&self) -> usize {
+                self.0.len()
+            }
+        }
+
+        impl #impl_generics_a ::std::iter::FusedIterator for #drain_name #ty_generics_a #where_clause_a {}
+
+        #[allow(clippy::drop_non_drop)]
+        impl #impl_generics_a Drop for #drain_name #ty_generics_a #where_clause_a {
+            fn drop(&mut self) {
+                // re-create the remaining values to make sure their Drop
+                // implementation runs, the fields then remove the drained
+                // range when their own `Drain` is dropped
+                for value in self {
+                    ::std::mem::drop(value);
+                }
+            }
+        }
+    });
+
+    let splice_doc_url = format!("[`{0}::splice()`](struct.{0}.html#method.splice)", vec_name);
+    generated.append_all(quote! {
+        /// Splicing iterator over
+        #[doc = #doc_url]
+        /// , created by calling
+        #[doc = #splice_doc_url]
+        #[allow(missing_debug_implementations)]
+        #visibility struct #splice_iter_name #impl_generics_a (#splice_type #marker_field) #where_clause_a;
+
+        impl #impl_generics_a Iterator for #splice_iter_name #ty_generics_a #where_clause_a {
+            type Item = #name #ty_generics;
+
+            #[inline]
+            fn next(&mut self) -> Option<#name #ty_generics> {
+                self.0.next().map(|#drain_pat| #construct_hygienic)
+            }
+
+            #[inline]
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.0.size_hint()
+            }
+        }
+
+        impl #impl_generics_a DoubleEndedIterator for #splice_iter_name #ty_generics_a #where_clause_a {
+            #[inline]
+            fn next_back(&mut self) -> Option<#name #ty_generics> {
+                self.0.next_back().map(|#drain_pat| #construct_hygienic)
+            }
+        }
+
+        impl #impl_generics_a ExactSizeIterator for #splice_iter_name #ty_generics_a #where_clause_a {
+            fn len(&self) -> usize {
+                self.0.len()
+            }
+        }
+
+        #[allow(clippy::drop_non_drop)]
+        impl #impl_generics_a Drop for #splice_iter_name #ty_generics_a #where_clause_a {
+            fn drop(&mut self) {
+                // re-create the removed values which were not consumed to
+                // make sure their Drop implementation runs, the fields then
+                // insert the new values when their own `Splice` is dropped
+                for value in self {
+                    ::std::mem::drop(value);
+                }
+            }
+        }
+    });
+
+    let extract_if_doc_url = format!("[`{0}::extract_if()`](struct.{0}.html#method.extract_if)", vec_name);
+    generated.append_all(quote! {
+        /// Iterator removing the
+        #[doc = #doc_url]
+        /// matching a predicate, created by calling
+        #[doc = #extract_if_doc_url]
+        #[allow(missing_debug_implementations)]
+        #visibility struct #extract_if_name #extract_if_impl_generics #extract_if_where_clause {
+            vec: &'a mut #vec_name #ty_generics,
+            /// index of the next value to give to the filter
+            idx: usize,
+            /// end of the range to look at
+            end: usize,
+            /// number of values removed so far
+            del: usize,
+            /// length of the vector before the iteration started
+            old_len: usize,
+            filter: F,
+        }
+
+        impl #extract_if_impl_generics Iterator for #extract_if_name #extract_if_ty_generics #extract_if_where_clause {
+            type Item = #name #ty_generics;
+
+            fn next(&mut self) -> Option<#name #ty_generics> {
+                unsafe {
+                    let ptr = self.vec.as_mut_ptr();
+                    while self.idx < self.end {
+                        let i = self.idx;
+                        let extracted = (self.filter)(ptr.add(i).as_mut().expect("should not be null"));
+                        // update the index after calling the filter, to keep
+                        // the current value if the filter panics
+                        self.idx += 1;
+                        if extracted {
+                            self.del += 1;
+                            return Some(ptr.add(i).read());
+                        } else if self.del > 0 {
+                            ptr.add(i - self.del).copy_from_nonoverlapping(ptr.add(i).as_ptr(), 1);
+                        }
+                    }
+                    None
+                }
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (0, Some(self.end - self.idx))
+            }
+        }
+
+        impl #extract_if_impl_generics Drop for #extract_if_name #extract_if_ty_generics #extract_if_where_clause {
+            fn drop(&mut self) {
+                unsafe {
+                    // move the values which were not looked at after the
+                    // values which were kept
+                    if self.idx < self.old_len && self.del > 0 {
+                        let ptr = self.vec.as_mut_ptr();
+                        ptr.add(self.idx - self.del).copy_from(ptr.add(self.idx).as_ptr(), self.old_len - self.idx);
+                    }
+                    self.vec.set_len(self.old_len - self.del);
+                }
+            }
+        }
+    });
+
     if input.attrs.derive_clone {
         generated.append_all(quote!{
             #[allow(dead_code)]
-            impl #vec_name {
+            impl #impl_generics #vec_name #ty_generics
+            where
+                #( #where_predicates, )*
+                #( for<'b> #fields_types: Clone, )*
+            {
                 /// Similar to [`
                 #[doc = #vec_name_str]
                 /// ::resize()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.resize).
-                pub fn resize(&mut self, new_len: usize, value: #name) {
+                pub fn resize(&mut self, new_len: usize, value: #name #ty_generics) {
                     #(
-                        self.#fields_names.resize(new_len, value.#fields_names);
+                        self.#fields_names.resize(new_len, value.#original_fields_names);
                     )*
                 }
+
+                /// Similar to [`
+                #[doc = #vec_name_str]
+                /// ::extend_from_within()`](https://doc.rust-lang.org/std/vec/struct.Vec.html#method.extend_from_within),
+                /// cloning the values in `range` to the end of all fields.
+                pub fn extend_from_within<R>(&mut self, range: R) where R: ::std::ops::RangeBounds<usize> {
+                    #range_from_bounds
+                    #(
+                        self.#fields_names.extend_from_within(range.clone());
+                    )*
+                }
+
+                /// Create a new vector containing a copy of the elements for
+                /// which the corresponding entry in `mask` is `true`. See
+                /// `filter_by_mask` on the slice type.
+                ///
+                /// # Panics
+                ///
+                /// If `mask` and the vector have different lengths.
+                pub fn filter_by_mask(&self, mask: &[bool]) -> #vec_name #ty_generics {
+                    self.as_slice().filter_by_mask(mask)
+                }
             }
 
-            impl ::soa_derive::SoAAppendVec<#name> for #vec_name {
+            impl #impl_generics #krate::SoAAppendVec<#name #ty_generics> for #vec_name #ty_generics
+            where
+                #( #where_predicates, )*
+                #( for<'b> #fields_types: Clone, )*
+            {
                 fn extend_from_slice(&mut self, other: Self::Slice<'_>) {
                     #(
                         self.#fields_names.extend_from_slice(other.#fields_names);
@@ -481,3 +1036,22 @@ pub fn derive(input: &Input) -> TokenStream {
 
     return generated;
 }
+
+/// Generate code converting the `range` variable, implementing
+/// `RangeBounds<usize>`, into a `Range<usize>` which can be cloned and given
+/// to every field in turn.
+fn range_from_bounds() -> TokenStream {
+    return quote! {
+        let start = match range.start_bound() {
+            ::std::ops::Bound::Included(&start) => start,
+            ::std::ops::Bound::Excluded(&start) => start.checked_add(1).expect("range start overflows usize"),
+            ::std::ops::Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            ::std::ops::Bound::Included(&end) => end.checked_add(1).expect("range end overflows usize"),
+            ::std::ops::Bound::Excluded(&end) => end,
+            ::std::ops::Bound::Unbounded => self.len(),
+        };
+        let range = start..end;
+    };
+}
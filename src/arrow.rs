@@ -0,0 +1,345 @@
+//! Conversion of struct of arrays vectors to and from [Apache
+//! Arrow](https://arrow.apache.org/) record batches, available with the
+//! `arrow` feature.
+//!
+//! Adding `#[soa(arrow)]` to a struct deriving [`StructOfArray`] generates
+//! `CheeseVec::arrow_schema()`, `CheeseVec::to_record_batch()`,
+//! `CheeseVec::into_record_batch()` and `TryFrom<RecordBatch> for
+//! CheeseVec`. Each field becomes a column with the same name, and the column
+//! type is given by the [`ArrowColumn`] implementation for the field type.
+//! Fields marked with `#[nested_soa]` become struct arrays, and the nested
+//! struct must also use `#[soa(arrow)]`.
+//!
+//! Columns of primitive types (integers and floating point numbers) are moved
+//! without copying the data when converting a vector into a record batch, and
+//! when converting a record batch that is the only owner of its buffers into a
+//! vector.
+//!
+//! ```
+//! # #[macro_use] extern crate soa_derive;
+//! # mod cheese {
+//! use std::convert::TryFrom;
+//! use soa_derive::arrow::RecordBatch;
+//!
+//! #[derive(StructOfArray)]
+//! #[soa(arrow)]
+//! struct Cheese {
+//!     name: String,
+//!     smell: f64,
+//!     aged: Option<u32>,
+//! }
+//!
+//! # fn main() {
+//! let mut cheeses = CheeseVec::new();
+//! cheeses.push(Cheese { name: "Brie".into(), smell: 4.5, aged: None });
+//! cheeses.push(Cheese { name: "Comté".into(), smell: 2.0, aged: Some(18) });
+//!
+//! let batch = cheeses.into_record_batch();
+//! assert_eq!(batch.num_rows(), 2);
+//! assert_eq!(batch.schema().field(2).name(), "aged");
+//! assert!(batch.schema().field(2).is_nullable());
+//!
+//! let cheeses = CheeseVec::try_from(batch).unwrap();
+//! assert_eq!(cheeses.name[1], "Comté");
+//! assert_eq!(cheeses.aged, [None, Some(18)]);
+//! # }
+//! # }
+//! ```
+//!
+//! [`StructOfArray`]: crate::StructOfArray
+
+use std::sync::Arc;
+
+use arrow_array::types::{
+    Float32Type, Float64Type, Int16Type, Int32Type, Int64Type, Int8Type,
+    UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+};
+use arrow_array::{Array, ArrayRef, BooleanArray, PrimitiveArray, StringArray, StructArray};
+use arrow_schema::{ArrowError, DataType, Fields};
+
+pub use arrow_array::RecordBatch;
+pub use arrow_schema::{Field, Schema};
+
+// Re-export the arrow crates used in the generated code, to allow users to
+// get the matching versions
+pub use arrow_array;
+pub use arrow_schema;
+
+/// Types which can be stored in a column of an Arrow record batch, used for
+/// the fields of structs with `#[soa(arrow)]`.
+///
+/// This is implemented for integers, floating point numbers, `bool` and
+/// `String`, as well as `Option<T>` of all of these. `None` values are stored
+/// as nulls.
+pub trait ArrowColumn: Sized {
+    /// Get the Arrow data type of a column containing values of this type
+    fn data_type() -> DataType;
+
+    /// Can a column containing values of this type contain nulls?
+    fn is_nullable() -> bool {
+        false
+    }
+
+    /// Create an Arrow array containing a copy of the `values`
+    fn to_array(values: &[Self]) -> ArrayRef;
+
+    /// Convert `values` to an Arrow array, re-using the allocation when the
+    /// memory layout allows it
+    fn into_array(values: Vec<Self>) -> ArrayRef {
+        Self::to_array(&values)
+    }
+
+    /// Convert an Arrow `array` back to a vector of values, re-using the
+    /// allocation when the memory layout allows it and the array is the only
+    /// owner of its data.
+    ///
+    /// # Errors
+    ///
+    /// If the array has the wrong data type, or contains nulls for a
+    /// non-nullable type.
+    fn from_array(array: ArrayRef) -> Result<Vec<Self>, ArrowError>;
+}
+
+/// Implemented by the vectors generated for structs with `#[soa(arrow)]`, to
+/// convert them to and from a set of Arrow columns.
+pub trait ArrowStructOfArray: Sized {
+    /// Get the Arrow fields corresponding to the columns of this vector
+    fn arrow_fields() -> Fields;
+
+    /// Create Arrow arrays containing a copy of the columns of this vector,
+    /// in the same order as `arrow_fields()`
+    fn to_arrow_columns(&self) -> Vec<ArrayRef>;
+
+    /// Convert this vector to Arrow arrays, in the same order as
+    /// `arrow_fields()`, moving the data when possible
+    fn into_arrow_columns(self) -> Vec<ArrayRef>;
+
+    /// Create a vector from Arrow `columns` described by `fields`. The columns
+    /// are matched to the fields of this vector by name, and additional
+    /// columns are ignored.
+    ///
+    /// # Errors
+    ///
+    /// If a column is missing, has the wrong data type or contains unexpected
+    /// nulls.
+    fn try_from_arrow_columns(fields: &Fields, columns: Vec<ArrayRef>) -> Result<Self, ArrowError>;
+}
+
+/// Check that `array` does not contain any null value
+fn check_no_nulls(array: &dyn Array) -> Result<(), ArrowError> {
+    if array.null_count() != 0 {
+        return Err(ArrowError::InvalidArgumentError(format!(
+            "expected an array without nulls, got {} null values", array.null_count()
+        )));
+    }
+    Ok(())
+}
+
+/// Check that `array` has the data type `expected`
+fn check_data_type(array: &dyn Array, expected: &DataType) -> Result<(), ArrowError> {
+    if array.data_type() != expected {
+        return Err(ArrowError::InvalidArgumentError(format!(
+            "expected an array of {}, got an array of {}", expected, array.data_type()
+        )));
+    }
+    Ok(())
+}
+
+macro_rules! impl_arrow_primitive {
+    ($($rust: ty => $arrow: ty,)*) => {$(
+        impl ArrowColumn for $rust {
+            fn data_type() -> DataType {
+                <$arrow as arrow_array::types::ArrowPrimitiveType>::DATA_TYPE
+            }
+
+            fn to_array(values: &[Self]) -> ArrayRef {
+                Arc::new(PrimitiveArray::<$arrow>::from_iter_values(values.iter().copied()))
+            }
+
+            fn into_array(values: Vec<Self>) -> ArrayRef {
+                Arc::new(PrimitiveArray::<$arrow>::new(values.into(), None))
+            }
+
+            fn from_array(array: ArrayRef) -> Result<Vec<Self>, ArrowError> {
+                check_data_type(&array, &Self::data_type())?;
+                check_no_nulls(&array)?;
+                // release the reference held by `array` so the buffer can be
+                // re-used if nothing else points to it
+                let data = array.to_data();
+                std::mem::drop(array);
+
+                let (_, values, _) = PrimitiveArray::<$arrow>::from(data).into_parts();
+                match values.into_inner().into_vec::<$rust>() {
+                    Ok(values) => Ok(values),
+                    Err(buffer) => Ok(buffer.typed_data::<$rust>().to_vec()),
+                }
+            }
+        }
+
+        impl ArrowColumn for Option<$rust> {
+            fn data_type() -> DataType {
+                <$rust as ArrowColumn>::data_type()
+            }
+
+            fn is_nullable() -> bool {
+                true
+            }
+
+            fn to_array(values: &[Self]) -> ArrayRef {
+                Arc::new(values.iter().copied().collect::<PrimitiveArray<$arrow>>())
+            }
+
+            fn from_array(array: ArrayRef) -> Result<Vec<Self>, ArrowError> {
+                check_data_type(&array, &Self::data_type())?;
+                let array = PrimitiveArray::<$arrow>::from(array.to_data());
+                Ok(array.iter().collect())
+            }
+        }
+    )*};
+}
+
+impl_arrow_primitive!(
+    i8 => Int8Type,
+    i16 => Int16Type,
+    i32 => Int32Type,
+    i64 => Int64Type,
+    u8 => UInt8Type,
+    u16 => UInt16Type,
+    u32 => UInt32Type,
+    u64 => UInt64Type,
+    f32 => Float32Type,
+    f64 => Float64Type,
+);
+
+impl ArrowColumn for bool {
+    fn data_type() -> DataType {
+        DataType::Boolean
+    }
+
+    fn to_array(values: &[Self]) -> ArrayRef {
+        Arc::new(BooleanArray::from(values.to_vec()))
+    }
+
+    fn from_array(array: ArrayRef) -> Result<Vec<Self>, ArrowError> {
+        check_data_type(&array, &Self::data_type())?;
+        check_no_nulls(&array)?;
+        let array = BooleanArray::from(array.to_data());
+        Ok(array.values().iter().collect())
+    }
+}
+
+impl ArrowColumn for Option<bool> {
+    fn data_type() -> DataType {
+        DataType::Boolean
+    }
+
+    fn is_nullable() -> bool {
+        true
+    }
+
+    fn to_array(values: &[Self]) -> ArrayRef {
+        Arc::new(BooleanArray::from(values.to_vec()))
+    }
+
+    fn from_array(array: ArrayRef) -> Result<Vec<Self>, ArrowError> {
+        check_data_type(&array, &Self::data_type())?;
+        let array = BooleanArray::from(array.to_data());
+        Ok(array.iter().collect())
+    }
+}
+
+impl ArrowColumn for String {
+    fn data_type() -> DataType {
+        DataType::Utf8
+    }
+
+    fn to_array(values: &[Self]) -> ArrayRef {
+        Arc::new(StringArray::from_iter_values(values))
+    }
+
+    fn from_array(array: ArrayRef) -> Result<Vec<Self>, ArrowError> {
+        check_data_type(&array, &Self::data_type())?;
+        check_no_nulls(&array)?;
+        let array = StringArray::from(array.to_data());
+        Ok(array.iter().map(|value| value.unwrap_or_default().to_owned()).collect())
+    }
+}
+
+impl ArrowColumn for Option<String> {
+    fn data_type() -> DataType {
+        DataType::Utf8
+    }
+
+    fn is_nullable() -> bool {
+        true
+    }
+
+    fn to_array(values: &[Self]) -> ArrayRef {
+        Arc::new(values.iter().map(Option::as_deref).collect::<StringArray>())
+    }
+
+    fn from_array(array: ArrayRef) -> Result<Vec<Self>, ArrowError> {
+        check_data_type(&array, &Self::data_type())?;
+        let array = StringArray::from(array.to_data());
+        Ok(array.iter().map(|value| value.map(str::to_owned)).collect())
+    }
+}
+
+/// Remove the column called `name` from `columns`
+fn take_column(fields: &Fields, columns: &mut [Option<ArrayRef>], name: &str) -> Result<ArrayRef, ArrowError> {
+    let index = fields.iter()
+        .position(|field| field.name() == name)
+        .ok_or_else(|| ArrowError::SchemaError(format!("missing column `{}`", name)))?;
+    Ok(columns[index].take().expect("each column is only taken once"))
+}
+
+/// Add the name of the column to an error created while converting it
+fn column_error(name: &str, error: ArrowError) -> ArrowError {
+    match error {
+        ArrowError::InvalidArgumentError(message) => {
+            ArrowError::InvalidArgumentError(format!("in column `{}`: {}", name, message))
+        }
+        error => error,
+    }
+}
+
+#[doc(hidden)]
+/// Convert the column called `name` to a vector, this is used by the
+/// generated code
+pub fn __private_read_column<T: ArrowColumn>(
+    fields: &Fields,
+    columns: &mut [Option<ArrayRef>],
+    name: &str,
+) -> Result<Vec<T>, ArrowError> {
+    let column = take_column(fields, columns, name)?;
+    T::from_array(column).map_err(|error| column_error(name, error))
+}
+
+#[doc(hidden)]
+/// Convert the struct column called `name` to a vector, this is used by the
+/// generated code for `#[nested_soa]` fields
+pub fn __private_read_struct_column<V: ArrowStructOfArray>(
+    fields: &Fields,
+    columns: &mut [Option<ArrayRef>],
+    name: &str,
+) -> Result<V, ArrowError> {
+    let column = take_column(fields, columns, name)?;
+    if !matches!(column.data_type(), DataType::Struct(_)) {
+        return Err(ArrowError::InvalidArgumentError(format!(
+            "in column `{}`: expected a struct array, got an array of {}", name, column.data_type()
+        )));
+    }
+    check_no_nulls(&column).map_err(|error| column_error(name, error))?;
+
+    let data = column.to_data();
+    std::mem::drop(column);
+    let (fields, columns, _) = StructArray::from(data).into_parts();
+    V::try_from_arrow_columns(&fields, columns).map_err(|error| column_error(name, error))
+}
+
+#[doc(hidden)]
+/// Create a struct array from `columns` described by `fields`, this is used by
+/// the generated code for `#[nested_soa]` fields
+pub fn __private_struct_array(columns: Vec<ArrayRef>, fields: Fields) -> ArrayRef {
+    Arc::new(StructArray::new(fields, columns, None))
+}
@@ -80,6 +80,32 @@
 //! * `Ptr` => `CheesePtr`
 //! * `PtrMut` => `CheesePtrMut`
 //!
+//! Attributes can also be added to the fields of the generated structs, with
+//! `#[soa_field_attr(<Type>, <attr>)]` on the corresponding field. The first
+//! argument uses the same mapping as `soa_attr`. This can for example be used
+//! to rename the fields when serializing `CheeseVec` with serde:
+//!
+//! ```
+//! # #[macro_use] extern crate soa_derive;
+//! # mod cheese {
+//! # use serde::{Serialize, Deserialize};
+//! #[derive(Debug, PartialEq, StructOfArray)]
+//! #[soa_derive(Serialize, Deserialize)]
+//! pub struct Cheese {
+//!     #[soa_field_attr(Vec, serde(rename = "smells"))]
+//!     pub smell: f64,
+//!     #[soa_field_attr(Vec, doc = "Names of all the cheeses")]
+//!     pub name: String,
+//! }
+//! # }
+//! ```
+//!
+//! Doc comments on the fields are forwarded to the corresponding field in all
+//! the generated types.
+//! 
+//! Fields disabled with `#[cfg(...)]` are removed before the code is
+//! generated, and are not present in any of the generated types.
+//!
 //! # Usage and API
 //!
 //! All the generated code have some generated documentation with it, so you
@@ -90,6 +116,10 @@
 //! `CheeseVec`, with exception of code using direct indexing in the vector and
 //! a few other caveats listed below.
 //!
+//! Tuple structs are also supported, in which case all the generated structs
+//! are tuple structs as well: `struct Point(f32, f32)` will give a `PointVec`
+//! with a `Vec<f32>` in `.0` and `.1`.
+//!
 //! ## Caveats and limitations
 //!
 //! `Vec<T>` functionalities rely a lot on references and automatic *deref*
@@ -134,6 +164,30 @@
 //! # }
 //! ```
 //!
+//! A `CheeseVec` can also be consumed to get back the `Cheese` values, using a
+//! `CheeseIntoIter` iterator:
+//!
+//! ```no_run
+//! # #[macro_use] extern crate soa_derive;
+//! # mod cheese {
+//! # #[derive(Debug, PartialEq, StructOfArray)]
+//! # pub struct Cheese {
+//! #     pub smell: f64,
+//! #     pub color: (f64, f64, f64),
+//! #     pub with_mushrooms: bool,
+//! #     pub name: String,
+//! # }
+//! # impl Cheese { fn new(name: &str) -> Cheese { unimplemented!() } }
+//! # fn main() {
+//! # let mut vec = CheeseVec::new();
+//! # vec.push(Cheese::new("stilton"));
+//! for cheese in vec {
+//!     let typeof_cheese: Cheese = cheese;
+//! }
+//! # }
+//! # }
+//! ```
+//!
 //! One of the main advantage of the SoA layout is to be able to only load some
 //! fields from memory when iterating over the vector. In order to do so, one
 //! can manually pick the needed fields:
@@ -227,6 +281,204 @@
 //!
 //! All helper structs will be also nested, for example `PointSlice` will be nested in `ParticleSlice`.
 //!
+//! The nested types are found by name, so the nested struct must use the
+//! default names for its generated types (`PointVec`, `PointSlice`, *etc.*),
+//! and can not use the custom names described below.
+//!
+//! ## Skipping fields
+//!
+//! Fields marked with `#[soa(skip)]` do not get their own column, and are
+//! not part of `CheeseVec`, `CheeseRef` or any other generated type. This is
+//! useful for cached values or markers such as `PhantomData`. When a value is
+//! re-created from the columns (in `pop`, `remove`, `Ref::to_owned`, *etc.*),
+//! skipped fields are set to `Default::default()`, or to the result of
+//! calling the function given with `#[soa(skip, default = "path")]`:
+//!
+//! ```
+//! # mod cheese {
+//! # use soa_derive::StructOfArray;
+//! fn no_hash() -> u64 { 0 }
+//!
+//! #[derive(Debug, PartialEq, StructOfArray)]
+//! pub struct Cheese {
+//!     pub smell: f64,
+//!     #[soa(skip)]
+//!     pub cached_name: Option<String>,
+//!     #[soa(skip, default = "no_hash")]
+//!     pub hash: u64,
+//! }
+//! # fn main() {
+//! let mut vec = CheeseVec::new();
+//! vec.push(Cheese { smell: 10.0, cached_name: Some("Brie".into()), hash: 42 });
+//! assert_eq!(vec.smell, [10.0]);
+//! assert_eq!(vec.pop(), Some(Cheese { smell: 10.0, cached_name: None, hash: 0 }));
+//! # }
+//! # }
+//! ```
+//!
+//! Generic parameters which are only used by skipped fields, such as a
+//! `PhantomData<U>` marker, are kept in the generated types with an
+//! additional hidden `PhantomData` field. The generated types never contain
+//! values of the skipped fields types, and do not require them to be `Send`
+//! or `Sync`.
+//!
+//! ## Custom names and crate path
+//!
+//! The names of the generated types can be changed with struct-level
+//! `#[soa(...)]` options, for example when `CheeseVec` already exists. The
+//! available options are `vec`, `slice`, `slice_mut`, `ref`, `ref_mut`,
+//! `ptr`, `ptr_mut`, `iter`, `iter_mut`, `into_iter`, `drain`, `splice`,
+//! `extract_if`, `chunks`, `chunks_exact`, `chunks_mut`, `rchunks`,
+//! `windows`, `column`, `selection`, `par_iter` and `par_iter_mut`. Enums
+//! only accept `vec`, `ref`, `ref_mut` and `iter`, and can not use
+//! `#[soa(arrow)]` or `#[soa(polars)]`.
+//!
+//! The generated code refers to this crate as `::soa_derive`. When it is
+//! re-exported from another crate, the path to use can be given with
+//! `#[soa(crate = "path")]`:
+//!
+//! ```
+//! # mod cheese {
+//! # mod my_facade { pub mod soa { pub use soa_derive::*; } }
+//! #[derive(my_facade::soa::StructOfArray)]
+//! #[soa(vec = "CheeseColumns", slice = "CheeseColumnsView")]
+//! #[soa(crate = "my_facade::soa")]
+//! pub struct Cheese {
+//!     pub smell: f64,
+//!     pub name: String,
+//! }
+//! # fn main() {
+//! let mut cheeses = CheeseColumns::new();
+//! cheeses.push(Cheese { smell: 10.0, name: "Brie".into() });
+//! let view: CheeseColumnsView = cheeses.as_slice();
+//! assert_eq!(view.smell, [10.0]);
+//! # }
+//! # }
+//! ```
+//!
+//! `#[nested_soa]` fields always use the default names of the nested struct,
+//! which can not use custom names in this case. Using a struct with custom
+//! names in a `#[nested_soa]` field fails to compile, with an error about
+//! the missing `PointVec` (or other default name) pointing to the field.
+//!
+//! ## Generic structs
+//!
+//! Structs with type, lifetime or const generic parameters are supported. The
+//! generated types carry the same parameters and bounds as the original struct,
+//! with an additional leading lifetime for the borrowing types:
+//!
+//! ```
+//! # mod cheese {
+//! # use soa_derive::StructOfArray;
+//! #[derive(StructOfArray)]
+//! pub struct Particle<T: Copy, const N: usize> {
+//!     position: [T; N],
+//!     mass: T,
+//! }
+//! # fn main() {
+//! let mut particles = ParticleVec::<f64, 3>::new();
+//! particles.push(Particle { position: [0.0; 3], mass: 1.0 });
+//! let slice: ParticleSlice<'_, f64, 3> = particles.as_slice();
+//! assert_eq!(slice.mass, [1.0]);
+//! # }
+//! # }
+//! ```
+//!
+//! ## Sorting by columns
+//!
+//! A `CheeseColumn` enum is generated with one variant per field, which can be
+//! used to sort a `CheeseVec` or `CheeseSliceMut` by several columns in
+//! lexicographic order. The comparisons only load the arrays of the given
+//! columns from memory:
+//!
+//! ```
+//! # mod cheese {
+//! # use soa_derive::StructOfArray;
+//! #[derive(StructOfArray)]
+//! pub struct Cheese {
+//!     pub smell: f64,
+//!     pub name: String,
+//! }
+//! # fn main() {
+//! let mut cheeses = CheeseVec::new();
+//! cheeses.push(Cheese { smell: 3.0, name: "Brie".into() });
+//! cheeses.push(Cheese { smell: 8.0, name: "Stilton".into() });
+//! cheeses.push(Cheese { smell: 8.0, name: "Munster".into() });
+//!
+//! cheeses.sort_by_columns(&[CheeseColumn::Smell.desc(), CheeseColumn::Name.asc()]);
+//! assert_eq!(cheeses.name, ["Munster", "Stilton", "Brie"]);
+//! # }
+//! # }
+//! ```
+//!
+//! All the fields must implement `PartialOrd`, even the ones which are not
+//! used to sort, otherwise `sort_by_columns` is not available: a single field
+//! such as a `HashMap` removes the method for the whole struct. Values which
+//! can not be compared to themselves (such as `NaN`) are placed after all the
+//! other values, in both ascending and descending order.
+//!
+//! The variants are the field names converted to camel case (`Field0`,
+//! `Field1`, ... for tuple structs), and the derive fails if two fields get
+//! the same variant, such as `foo_bar` and `fooBar`.
+//!
+//! ## Enums
+//!
+//! Enums can also be derived, creating a tagged union with struct of arrays
+//! layout. The fields of each variant are stored in a separate struct of
+//! arrays, and the generated vector keeps the kind of variant and the position
+//! in this storage for each element:
+//!
+//! ```
+//! # mod cheese {
+//! # use soa_derive::StructOfArray;
+//! #[derive(StructOfArray)]
+//! pub enum Shape {
+//!     Circle { r: f32 },
+//!     Rect { w: f32, h: f32 },
+//!     Empty,
+//! }
+//! # }
+//! ```
+//!
+//! will generate code that looks like this:
+//!
+//! ```
+//! # struct ShapeCircleVec; struct ShapeRectVec; struct ShapeCircleRef<'a>(&'a ()); struct ShapeRectRef<'a>(&'a ());
+//! pub struct ShapeCircle {
+//!     pub r: f32,
+//! }
+//! // with ShapeCircleVec, ShapeCircleRef, ... as for any other struct
+//!
+//! pub struct ShapeRect {
+//!     pub w: f32,
+//!     pub h: f32,
+//! }
+//!
+//! pub enum ShapeKind {
+//!     Circle,
+//!     Rect,
+//!     Empty,
+//! }
+//!
+//! pub struct ShapeVec {
+//!     pub kinds: Vec<ShapeKind>,
+//!     pub indices: Vec<usize>,
+//!     pub circle: ShapeCircleVec,
+//!     pub rect: ShapeRectVec,
+//! }
+//!
+//! pub enum ShapeRef<'a> {
+//!     Circle(ShapeCircleRef<'a>),
+//!     Rect(ShapeRectRef<'a>),
+//!     Empty,
+//! }
+//! ```
+//!
+//! `ShapeVec` provides `push`, `pop`, `get`, `get_mut`, `iter` and `retain`.
+//! Generic enums are not supported, and variants can not use names which would
+//! make the generated types conflict, such as `Kind` or `Vec` (creating a
+//! `ShapeKind` struct next to the `ShapeKind` enum).
+//!
 //! # Use in a generic context
 //!
 //! `StructOfArray` does not provide a set of common operations by default. Thus if you wanted to use a `StructOfArray`
@@ -260,6 +512,88 @@ pub use soa_derive_internal::StructOfArray;
 #[doc(hidden)]
 pub use permutation::permutation::*;
 
+#[doc(hidden)]
+/// Get the first position of each cycle of the permutation `indices`, skipping
+/// the positions which are not moved. This is used by the generated code to
+/// check `indices` once, before moving the elements of all the columns of a
+/// slice with [`__private_apply_index_in_place`].
+///
+/// # Panics
+///
+/// If `indices` is not a permutation of `0..indices.len()`.
+pub fn __private_permutation_cycles(indices: &[usize]) -> Vec<usize> {
+    let mut pending = vec![false; indices.len()];
+    for &index in indices {
+        assert!(index < pending.len(), "permutation index {} is out of bounds", index);
+        assert!(!pending[index], "permutation index {} is repeated", index);
+        pending[index] = true;
+    }
+
+    let mut cycles = Vec::new();
+    for start in 0..indices.len() {
+        if !pending[start] {
+            continue;
+        }
+        pending[start] = false;
+        if indices[start] == start {
+            continue;
+        }
+
+        cycles.push(start);
+        let mut next = indices[start];
+        while next != start {
+            pending[next] = false;
+            next = indices[next];
+        }
+    }
+    cycles
+}
+
+#[doc(hidden)]
+/// Move the element at `indices[i]` to position `i` in `column`, moving a
+/// single value along each of the `cycles` of the permutation. This is used
+/// by the generated code to re-order each column of a slice, and does not
+/// check `indices`.
+///
+/// # Safety
+///
+/// `indices` must be a permutation of `0..column.len()`, and `cycles` must be
+/// the result of [`__private_permutation_cycles`] for these `indices`.
+pub unsafe fn __private_apply_index_in_place<T>(column: &mut [T], indices: &[usize], cycles: &[usize]) {
+    debug_assert_eq!(column.len(), indices.len());
+    let column = column.as_mut_ptr();
+    for &start in cycles {
+        // SAFETY: all the positions are in bounds, and each cycle is visited
+        // once. The value at `start` is moved out, every other position in
+        // the cycle is filled with the value of the next one, and the last
+        // position receives the value from `start`. No code that could panic
+        // or observe the column runs in between.
+        let value = std::ptr::read(column.add(start));
+        let mut current = start;
+        let mut next = indices[current];
+        while next != start {
+            std::ptr::copy_nonoverlapping(column.add(next), column.add(current), 1);
+            current = next;
+            next = indices[current];
+        }
+        std::ptr::write(column.add(current), value);
+    }
+}
+
+// Used by the macro-generated code for the parallel iterators
+#[cfg(feature = "rayon")]
+#[doc(hidden)]
+pub use rayon;
+
+#[cfg(feature = "arrow")]
+pub mod arrow;
+
+#[cfg(feature = "polars")]
+pub mod polars;
+
+#[cfg(feature = "ndarray")]
+pub mod ndarray;
+
 /// Any struct derived by StructOfArray will auto impl this trait You can use
 /// `<Cheese as StructOfArray>::Type` instead of explicit named type
 /// `CheeseVec`; This will helpful in generics programing that generate struct
@@ -282,6 +616,31 @@ pub trait SoAIter<'a> {
     type IterMut: 'a + Iterator<Item=Self::RefMut>;
 }
 
+/// Implemented by the generated mutable slices with the `rayon` feature, to
+/// re-order all the columns concurrently, one task per column. This is used
+/// to implement the parallel sorts with `#[nested_soa]` fields.
+#[cfg(feature = "rayon")]
+#[doc(hidden)]
+pub trait SoAParallelPermute: Send {
+    /// Move the element at `indices[i]` to position `i` in every column,
+    /// without checking `indices`
+    ///
+    /// # Safety
+    ///
+    /// `indices` must be a permutation of `0..self.len()`, and `cycles` must
+    /// be the result of [`__private_permutation_cycles`] for these `indices`.
+    unsafe fn __private_par_apply_index(&mut self, indices: &[usize], cycles: &[usize]);
+}
+
+/// Implemented by the generated vectors with the `rayon` feature, to filter
+/// all the columns concurrently, one task per column. This is used to
+/// implement `par_retain` with `#[nested_soa]` fields.
+#[cfg(feature = "rayon")]
+#[doc(hidden)]
+pub trait SoAParallelRetain: Send {
+    fn __private_par_retain_by_mask(&mut self, mask: &[bool]);
+}
+
 mod private_soa_indexes {
     // From [`std::slice::SliceIndex`](https://doc.rust-lang.org/std/slice/trait.SliceIndex.html) code.
     // Limits the types that may implement the SoA index traits.
@@ -376,6 +735,25 @@ pub trait SoAIndexMut<T>: private_soa_indexes::Sealed {
 /// # }
 /// ```
 ///
+/// Fields of tuple structs are referred to by their index:
+///
+/// ```
+/// # #[macro_use] extern crate soa_derive;
+/// # mod cheese {
+/// #[derive(StructOfArray)]
+/// struct Point(f64, f64);
+///
+/// # fn main() {
+/// let mut vec = PointVec::new();
+/// // fill the vector
+///
+/// for (x, y) in soa_zip!(&mut vec, [mut 0, 1]) {
+///     *x += y;
+/// }
+/// # }
+/// # }
+/// ```
+///
 /// The iterator can also work with external iterators. In this case, the
 /// iterator will yields elements until any of the fields or one external
 /// iterator returns None.
@@ -410,6 +788,45 @@ macro_rules! soa_zip {
 }
 
 
+/// Create a parallel iterator over multiple fields in a Struct of array style
+/// vector. This is the parallel version of [`soa_zip!`], available with the
+/// `rayon` feature, and takes the same arguments. The resulting iterator is a
+/// rayon `IndexedParallelIterator`.
+///
+/// ```
+/// # #[macro_use] extern crate soa_derive;
+/// # mod cheese {
+/// # use rayon::prelude::*;
+/// #[derive(StructOfArray)]
+/// struct Cheese {
+///     size: f64,
+///     mass: f64,
+///     name: String,
+/// }
+///
+/// # fn main() {
+/// let mut vec = CheeseVec::new();
+/// vec.push(Cheese { size: 3.0, mass: 2.0, name: "Brie".into() });
+///
+/// par_soa_zip!(&mut vec, [mut mass, size]).for_each(|(mass, size)| {
+///     *mass *= size;
+/// });
+///
+/// let total: f64 = par_soa_zip!(&vec, [mass]).sum();
+/// assert_eq!(total, 6.0);
+/// # }
+/// # }
+/// ```
+#[cfg(feature = "rayon")]
+#[macro_export]
+macro_rules! par_soa_zip {
+    ($self: expr, [$($fields: tt)*] $(, $external: expr)* $(,)*) => {{
+        let this = $self;
+        $crate::par_soa_zip_impl!(@munch this, {$($fields)*} -> [] $($external ,)*)
+    }};
+}
+
+
 /// This trait is automatically implemented by the relevant generated by [`StructOfArray`].
 ///
 /// Links a [`StructOfArray`] type to its raw pointer types, which is useful for generic
@@ -421,6 +838,55 @@ pub trait SoAPointers {
     type MutPtr;
 }
 
+/// The direction in which a single column is sorted by `sort_by_columns`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SortOrder {
+    /// Smallest values first
+    Ascending,
+    /// Largest values first
+    Descending,
+}
+
+impl SortOrder {
+    /// Apply this order to the result of comparing two values in ascending
+    /// order
+    pub fn apply(self, ordering: core::cmp::Ordering) -> core::cmp::Ordering {
+        match self {
+            SortOrder::Ascending => ordering,
+            SortOrder::Descending => ordering.reverse(),
+        }
+    }
+}
+
+/// One entry in the specification given to `sort_by_columns`, built from the
+/// generated column selectors with `CheeseColumn::Smell.asc()` or
+/// `CheeseColumn::Name.desc()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SortKey<C> {
+    /// The column to compare
+    pub column: C,
+    /// The direction in which to sort this column
+    pub order: SortOrder,
+}
+
+#[doc(hidden)]
+/// Compare two values of a column for `sort_by_columns`, this is used by the
+/// generated code. Values which can not be compared to themselves (such as
+/// `NaN`) are placed after all the other values regardless of `order`, so that
+/// the comparison is a total order.
+pub fn __private_compare_column<T: PartialOrd>(a: &T, b: &T, order: SortOrder) -> core::cmp::Ordering {
+    use core::cmp::Ordering;
+
+    let a_unordered = a.partial_cmp(a).is_none();
+    let b_unordered = b.partial_cmp(b).is_none();
+    match (a_unordered, b_unordered) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => order.apply(a.partial_cmp(b).unwrap_or(Ordering::Equal)),
+    }
+}
+
 
 mod generics {
     use super::*;
@@ -473,6 +939,37 @@ mod generics {
             self.get(self.len().saturating_sub(1))
         }
 
+        /// Analogous to [`slice::binary_search_by()`](https://doc.rust-lang.org/std/primitive.slice.html#method.binary_search_by)
+        fn binary_search_by<'a, F>(&'a self, mut f: F) -> Result<usize, usize> where F: FnMut(Self::Ref<'a>) -> core::cmp::Ordering {
+            let mut left = 0;
+            let mut right = self.len();
+            while left < right {
+                let mid = left + (right - left) / 2;
+                match f(self.index(mid)) {
+                    core::cmp::Ordering::Less => left = mid + 1,
+                    core::cmp::Ordering::Greater => right = mid,
+                    core::cmp::Ordering::Equal => return Ok(mid),
+                }
+            }
+            Err(left)
+        }
+
+        /// Analogous to [`slice::binary_search_by_key()`](https://doc.rust-lang.org/std/primitive.slice.html#method.binary_search_by_key)
+        fn binary_search_by_key<'a, B, F>(&'a self, key: &B, mut f: F) -> Result<usize, usize> where F: FnMut(Self::Ref<'a>) -> B, B: Ord {
+            self.binary_search_by(|element| f(element).cmp(key))
+        }
+
+        /// Analogous to [`slice::partition_point()`](https://doc.rust-lang.org/std/primitive.slice.html#method.partition_point)
+        fn partition_point<'a, P>(&'a self, mut pred: P) -> usize where P: FnMut(Self::Ref<'a>) -> bool {
+            self.binary_search_by(|element| {
+                if pred(element) {
+                    core::cmp::Ordering::Less
+                } else {
+                    core::cmp::Ordering::Greater
+                }
+            }).unwrap_or_else(|index| index)
+        }
+
         /// Obtain a `const` pointer type for this data
         fn as_ptr(&self) -> Self::Ptr;
     }
@@ -626,6 +1123,9 @@ mod generics {
         /// The mut pointer type interface
         type PtrMut;
 
+        /// The type used for draining a range of values out of the arrays
+        type Drain<'t>: Iterator<Item=T> where Self: 't;
+
         /// Returns the number of elements in the arrays
         fn len(&self) -> usize;
 
@@ -761,6 +1261,50 @@ mod generics {
 
         /// Analogous to [`Vec::split_off`]
         fn split_off(&mut self, at: usize) -> Self;
+
+        /// Analogous to [`Vec::drain`]
+        fn drain(&mut self, range: impl core::ops::RangeBounds<usize>) -> Self::Drain<'_>;
+
+        /// Analogous to [`slice::swap()`](https://doc.rust-lang.org/std/primitive.slice.html#method.swap)
+        fn swap(&mut self, a: usize, b: usize);
+
+        #[doc(hidden)]
+        /// Get mutable references to the elements at `a` and `b`, with
+        /// `a < b`. This is used to implement [`SoAVec::dedup_by`].
+        fn __private_get_pair_mut(&mut self, a: usize, b: usize) -> (Self::RefMut<'_>, Self::RefMut<'_>);
+
+        /// Analogous to [`Vec::dedup_by`]
+        fn dedup_by<F>(&mut self, mut same_bucket: F) where F: for<'t> FnMut(Self::RefMut<'t>, Self::RefMut<'t>) -> bool {
+            let len = self.len();
+            if len <= 1 {
+                return;
+            }
+
+            let mut kept = 1;
+            for i in 1..len {
+                let (previous, current) = self.__private_get_pair_mut(kept - 1, i);
+                if !same_bucket(current, previous) {
+                    if kept != i {
+                        self.swap(kept, i);
+                    }
+                    kept += 1;
+                }
+            }
+            self.truncate(kept);
+        }
+
+        /// Analogous to [`Vec::dedup_by_key`]
+        fn dedup_by_key<F, K>(&mut self, mut key: F) where
+            F: FnMut(Self::RefMut<'_>) -> K,
+            K: PartialEq,
+        {
+            self.dedup_by(|a, b| key(a) == key(b));
+        }
+
+        /// Analogous to [`Vec::dedup`]
+        fn dedup(&mut self) where for<'t> Self::RefMut<'t>: PartialEq {
+            self.dedup_by(|a, b| a == b);
+        }
     }
 
     /// A trait to implement `Clone`-dependent behavior to convert a non-owning SoA type into an
@@ -812,20 +1356,60 @@ macro_rules! soa_zip_impl {
     };
 
     // Eat the last `mut $field` and then emit code
-    (@munch $self: expr, {mut $field: ident} -> [$($output: tt)*] $($ext: expr ,)*) => {
+    (@munch $self: expr, {mut $field: tt} -> [$($output: tt)*] $($ext: expr ,)*) => {
         $crate::soa_zip_impl!(@last $($output)*, $self.$field.iter_mut(), $($ext, )*)
     };
     // Eat the last `$field` and then emit code
-    (@munch $self: expr, {$field: ident} -> [$($output: tt)*] $($ext: expr ,)*) => {
+    (@munch $self: expr, {$field: tt} -> [$($output: tt)*] $($ext: expr ,)*) => {
         $crate::soa_zip_impl!(@last $($output)*, $self.$field.iter(), $($ext, )*)
     };
 
     // Eat the next `mut $field` and then recurse
-    (@munch $self: expr, {mut $field: ident, $($tail: tt)*} -> [$($output: tt)*] $($ext: expr ,)*) => {
+    (@munch $self: expr, {mut $field: tt, $($tail: tt)*} -> [$($output: tt)*] $($ext: expr ,)*) => {
         $crate::soa_zip_impl!(@munch $self, {$($tail)*} -> [$($output)*, $self.$field.iter_mut()] $($ext, )*)
     };
     // Eat the next `$field` and then recurse
-    (@munch $self: expr, {$field: ident, $($tail: tt)*} -> [$($output: tt)*] $($ext: expr ,)*) => {
+    (@munch $self: expr, {$field: tt, $($tail: tt)*} -> [$($output: tt)*] $($ext: expr ,)*) => {
         $crate::soa_zip_impl!(@munch $self, {$($tail)*} -> [$($output)*, $self.$field.iter()] $($ext, )*)
     };
 }
+
+#[cfg(feature = "rayon")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! par_soa_zip_impl {
+    // The main code is emmited here: we create a parallel iterator, zip it and
+    // then map the zipped iterator to flatten it, re-using the closure from
+    // soa_zip_impl
+    (@last , $first: expr, $($tail: expr,)*) => {{
+        #[allow(unused_imports)]
+        use $crate::rayon::iter::{
+            IndexedParallelIterator, IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelIterator
+        };
+        $first
+            $(
+                .zip($tail)
+            )*
+            .map(
+                $crate::soa_zip_impl!(@flatten a => (a) $( , $tail )*)
+            )
+    }};
+
+    // Eat the last `mut $field` and then emit code
+    (@munch $self: expr, {mut $field: tt} -> [$($output: tt)*] $($ext: expr ,)*) => {
+        $crate::par_soa_zip_impl!(@last $($output)*, $self.$field.par_iter_mut(), $($ext, )*)
+    };
+    // Eat the last `$field` and then emit code
+    (@munch $self: expr, {$field: tt} -> [$($output: tt)*] $($ext: expr ,)*) => {
+        $crate::par_soa_zip_impl!(@last $($output)*, $self.$field.par_iter(), $($ext, )*)
+    };
+
+    // Eat the next `mut $field` and then recurse
+    (@munch $self: expr, {mut $field: tt, $($tail: tt)*} -> [$($output: tt)*] $($ext: expr ,)*) => {
+        $crate::par_soa_zip_impl!(@munch $self, {$($tail)*} -> [$($output)*, $self.$field.par_iter_mut()] $($ext, )*)
+    };
+    // Eat the next `$field` and then recurse
+    (@munch $self: expr, {$field: tt, $($tail: tt)*} -> [$($output: tt)*] $($ext: expr ,)*) => {
+        $crate::par_soa_zip_impl!(@munch $self, {$($tail)*} -> [$($output)*, $self.$field.par_iter()] $($ext, )*)
+    };
+}
@@ -0,0 +1,102 @@
+//! Views of struct of arrays slices as [ndarray](https://docs.rs/ndarray)
+//! arrays, available with the `ndarray` feature.
+//!
+//! With this feature, the generated `CheeseSlice` and `CheeseSliceMut` get
+//! one `<field>_view()` method for each field (and `<field>_view_mut()` for
+//! `CheeseSliceMut`), returning an ndarray view of the column without copying
+//! the data. Columns of scalar types become one dimensional views, and columns
+//! of `[T; N]` become two dimensional views with shape `(len, N)`. These
+//! methods are only available for fields implementing [`NdColumn`], and are
+//! not generated for fields marked with `#[nested_soa]`.
+//!
+//! ```
+//! # #[macro_use] extern crate soa_derive;
+//! # mod particle {
+//! #[derive(StructOfArray)]
+//! struct Particle {
+//!     mass: f64,
+//!     position: [f64; 3],
+//!     name: String,
+//! }
+//!
+//! # fn main() {
+//! let mut particles = ParticleVec::new();
+//! particles.push(Particle { mass: 1.0, position: [0.0, 1.0, 2.0], name: "H".into() });
+//! particles.push(Particle { mass: 16.0, position: [3.0, 4.0, 5.0], name: "O".into() });
+//!
+//! let mut slice = particles.as_mut_slice();
+//! assert_eq!(slice.mass_view().sum(), 17.0);
+//!
+//! let mut positions = slice.position_view_mut();
+//! assert_eq!(positions.shape(), [2, 3]);
+//! positions.column_mut(2).fill(-1.0);
+//!
+//! assert_eq!(particles.position[1], [3.0, 4.0, -1.0]);
+//! # }
+//! # }
+//! ```
+
+use ndarray::{Dimension, Ix1, Ix2};
+
+pub use ndarray::{ArrayView, ArrayView1, ArrayView2, ArrayViewMut, ArrayViewMut1, ArrayViewMut2};
+
+// Re-export the ndarray crate used in the generated code, to allow users to
+// get the matching version
+pub use ndarray;
+
+/// Types which can be viewed as ndarray arrays when stored in a column, used
+/// for the `<field>_view()` and `<field>_view_mut()` methods of the generated
+/// slices.
+///
+/// This is implemented for integers, floating point numbers and `bool`, with
+/// one dimensional views; and for arrays `[T; N]` of these, with two
+/// dimensional views of shape `(len, N)`.
+pub trait NdColumn: Sized {
+    /// Type of the elements of the view
+    type Elem;
+    /// Dimension of the view
+    type Dim: Dimension;
+
+    /// Get a view of `column`, without copying the data
+    fn view(column: &[Self]) -> ArrayView<'_, Self::Elem, Self::Dim>;
+
+    /// Get a mutable view of `column`, without copying the data
+    fn view_mut(column: &mut [Self]) -> ArrayViewMut<'_, Self::Elem, Self::Dim>;
+}
+
+macro_rules! impl_nd_column {
+    ($($scalar: ty,)*) => {$(
+        impl NdColumn for $scalar {
+            type Elem = $scalar;
+            type Dim = Ix1;
+
+            fn view(column: &[Self]) -> ArrayView1<'_, $scalar> {
+                ArrayView1::from(column)
+            }
+
+            fn view_mut(column: &mut [Self]) -> ArrayViewMut1<'_, $scalar> {
+                ArrayViewMut1::from(column)
+            }
+        }
+
+        impl<const N: usize> NdColumn for [$scalar; N] {
+            type Elem = $scalar;
+            type Dim = Ix2;
+
+            fn view(column: &[Self]) -> ArrayView2<'_, $scalar> {
+                ArrayView2::from(column)
+            }
+
+            fn view_mut(column: &mut [Self]) -> ArrayViewMut2<'_, $scalar> {
+                ArrayViewMut2::from(column)
+            }
+        }
+    )*};
+}
+
+impl_nd_column!(
+    i8, i16, i32, i64, isize,
+    u8, u16, u32, u64, usize,
+    f32, f64,
+    bool,
+);
@@ -0,0 +1,278 @@
+//! Conversion of struct of arrays vectors to and from
+//! [Polars](https://pola.rs/) data frames, available with the `polars`
+//! feature.
+//!
+//! Adding `#[soa(polars)]` to a struct deriving [`StructOfArray`] generates
+//! `CheeseVec::into_dataframe()` and `CheeseVec::try_from_dataframe()`. Each
+//! field becomes a column with the same name, and the column type is given by
+//! the [`PolarsColumn`] implementation for the field type. Fields marked with
+//! `#[nested_soa]` become struct columns, and the nested struct must also use
+//! `#[soa(polars)]`.
+//!
+//! ```
+//! # #[macro_use] extern crate soa_derive;
+//! # mod cheese {
+//! #[derive(StructOfArray)]
+//! #[soa(polars)]
+//! struct Cheese {
+//!     name: String,
+//!     smell: f64,
+//!     aged: Option<u32>,
+//! }
+//!
+//! # fn main() {
+//! let mut cheeses = CheeseVec::new();
+//! cheeses.push(Cheese { name: "Brie".into(), smell: 4.5, aged: None });
+//! cheeses.push(Cheese { name: "Comté".into(), smell: 2.0, aged: Some(18) });
+//!
+//! let dataframe = cheeses.into_dataframe();
+//! assert_eq!(dataframe.shape(), (2, 3));
+//! assert_eq!(dataframe.get_column_names(), ["name", "smell", "aged"]);
+//!
+//! let cheeses = CheeseVec::try_from_dataframe(&dataframe).unwrap();
+//! assert_eq!(cheeses.name[1], "Comté");
+//! assert_eq!(cheeses.aged, [None, Some(18)]);
+//! # }
+//! # }
+//! ```
+//!
+//! [`StructOfArray`]: crate::StructOfArray
+
+use polars_core::prelude::{
+    ChunkedArray, Column, DataType, IntoColumn, IntoSeries, NamedFrom,
+    PolarsError, PolarsResult, Series, StructChunked,
+};
+use polars_core::datatypes::{
+    Float32Type, Float64Type, Int16Type, Int32Type, Int64Type, Int8Type,
+    UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+};
+
+pub use polars_core::frame::DataFrame;
+
+// Re-export the polars crate used in the generated code, to allow users to
+// get the matching version
+pub use polars_core;
+
+/// Types which can be stored in a column of a Polars data frame, used for the
+/// fields of structs with `#[soa(polars)]`.
+///
+/// This is implemented for integers, floating point numbers, `bool` and
+/// `String`, as well as `Option<T>` of all of these. `None` values are stored
+/// as nulls.
+pub trait PolarsColumn: Sized {
+    /// Get the Polars data type of a column containing values of this type
+    fn dtype() -> DataType;
+
+    /// Convert `values` to a series called `name`
+    fn into_series(name: &str, values: Vec<Self>) -> Series;
+
+    /// Get a copy of the values in `series`.
+    ///
+    /// # Errors
+    ///
+    /// If the series has the wrong data type, or contains nulls for a
+    /// non-nullable type.
+    fn from_series(series: &Series) -> PolarsResult<Vec<Self>>;
+}
+
+/// Implemented by the vectors generated for structs with `#[soa(polars)]`,
+/// to convert them to and from a set of Polars columns.
+pub trait PolarsStructOfArray: Sized {
+    /// Convert this vector to Polars columns named after the fields, moving
+    /// the data when possible
+    fn into_polars_columns(self) -> Vec<Column>;
+
+    /// Create a vector from the columns of `dataframe` with the same names as
+    /// the fields. Additional columns are ignored.
+    ///
+    /// # Errors
+    ///
+    /// If a column is missing, has the wrong data type or contains unexpected
+    /// nulls.
+    fn try_from_polars_columns(dataframe: &DataFrame) -> PolarsResult<Self>;
+}
+
+/// Check that `series` does not contain any null value
+fn check_no_nulls(series: &Series) -> PolarsResult<()> {
+    if series.null_count() != 0 {
+        return Err(PolarsError::SchemaMismatch(format!(
+            "expected a series without nulls, got {} null values", series.null_count()
+        ).into()));
+    }
+    Ok(())
+}
+
+/// Check that `series` has the data type `expected`
+fn check_dtype(series: &Series, expected: &DataType) -> PolarsResult<()> {
+    if series.dtype() != expected {
+        return Err(PolarsError::SchemaMismatch(format!(
+            "expected a series of {}, got a series of {}", expected, series.dtype()
+        ).into()));
+    }
+    Ok(())
+}
+
+macro_rules! impl_polars_primitive {
+    ($($rust: ty => $polars: ty,)*) => {$(
+        impl PolarsColumn for $rust {
+            fn dtype() -> DataType {
+                <$polars as polars_core::datatypes::PolarsDataType>::get_static_dtype()
+            }
+
+            fn into_series(name: &str, values: Vec<Self>) -> Series {
+                ChunkedArray::<$polars>::from_vec(name.into(), values).into_series()
+            }
+
+            fn from_series(series: &Series) -> PolarsResult<Vec<Self>> {
+                check_dtype(series, &Self::dtype())?;
+                check_no_nulls(series)?;
+                Ok(series.unpack::<$polars>()?.into_no_null_iter().collect())
+            }
+        }
+
+        impl PolarsColumn for Option<$rust> {
+            fn dtype() -> DataType {
+                <$rust as PolarsColumn>::dtype()
+            }
+
+            fn into_series(name: &str, values: Vec<Self>) -> Series {
+                Series::new(name.into(), values)
+            }
+
+            fn from_series(series: &Series) -> PolarsResult<Vec<Self>> {
+                check_dtype(series, &Self::dtype())?;
+                Ok(series.unpack::<$polars>()?.iter().collect())
+            }
+        }
+    )*};
+}
+
+impl_polars_primitive!(
+    i8 => Int8Type,
+    i16 => Int16Type,
+    i32 => Int32Type,
+    i64 => Int64Type,
+    u8 => UInt8Type,
+    u16 => UInt16Type,
+    u32 => UInt32Type,
+    u64 => UInt64Type,
+    f32 => Float32Type,
+    f64 => Float64Type,
+);
+
+impl PolarsColumn for bool {
+    fn dtype() -> DataType {
+        DataType::Boolean
+    }
+
+    fn into_series(name: &str, values: Vec<Self>) -> Series {
+        Series::new(name.into(), values)
+    }
+
+    fn from_series(series: &Series) -> PolarsResult<Vec<Self>> {
+        check_dtype(series, &Self::dtype())?;
+        check_no_nulls(series)?;
+        Ok(series.bool()?.into_no_null_iter().collect())
+    }
+}
+
+impl PolarsColumn for Option<bool> {
+    fn dtype() -> DataType {
+        DataType::Boolean
+    }
+
+    fn into_series(name: &str, values: Vec<Self>) -> Series {
+        Series::new(name.into(), values)
+    }
+
+    fn from_series(series: &Series) -> PolarsResult<Vec<Self>> {
+        check_dtype(series, &Self::dtype())?;
+        Ok(series.bool()?.iter().collect())
+    }
+}
+
+impl PolarsColumn for String {
+    fn dtype() -> DataType {
+        DataType::String
+    }
+
+    fn into_series(name: &str, values: Vec<Self>) -> Series {
+        Series::new(name.into(), values)
+    }
+
+    fn from_series(series: &Series) -> PolarsResult<Vec<Self>> {
+        check_dtype(series, &Self::dtype())?;
+        check_no_nulls(series)?;
+        Ok(series.str()?.into_no_null_iter().map(str::to_owned).collect())
+    }
+}
+
+impl PolarsColumn for Option<String> {
+    fn dtype() -> DataType {
+        DataType::String
+    }
+
+    fn into_series(name: &str, values: Vec<Self>) -> Series {
+        Series::new(name.into(), values)
+    }
+
+    fn from_series(series: &Series) -> PolarsResult<Vec<Self>> {
+        check_dtype(series, &Self::dtype())?;
+        Ok(series.str()?.iter().map(|value| value.map(str::to_owned)).collect())
+    }
+}
+
+/// Add the name of the column to an error created while converting it
+fn column_error(name: &str, error: PolarsError) -> PolarsError {
+    match error {
+        PolarsError::SchemaMismatch(message) => {
+            PolarsError::SchemaMismatch(format!("in column `{}`: {}", name, message).into())
+        }
+        error => error,
+    }
+}
+
+#[doc(hidden)]
+/// Create a column called `name` from `values`, this is used by the generated
+/// code
+pub fn __private_column<T: PolarsColumn>(name: &str, values: Vec<T>) -> Column {
+    T::into_series(name, values).into_column()
+}
+
+#[doc(hidden)]
+/// Create a struct column called `name` from the columns of `vec`, this is
+/// used by the generated code for `#[nested_soa]` fields
+pub fn __private_struct_column<V: PolarsStructOfArray>(name: &str, vec: V) -> Column {
+    let columns = vec.into_polars_columns();
+    // generated vectors always have at least one field
+    let len = columns[0].len();
+    let fields = columns.iter().map(Column::as_materialized_series);
+    StructChunked::from_series(name.into(), len, fields)
+        .expect("the fields should have the same length")
+        .into_column()
+}
+
+#[doc(hidden)]
+/// Get a copy of the column called `name` in `dataframe`, this is used by the
+/// generated code
+pub fn __private_read_column<T: PolarsColumn>(dataframe: &DataFrame, name: &str) -> PolarsResult<Vec<T>> {
+    let series = dataframe.column(name)?.as_materialized_series();
+    T::from_series(series).map_err(|error| column_error(name, error))
+}
+
+#[doc(hidden)]
+/// Get a copy of the struct column called `name` in `dataframe`, this is
+/// used by the generated code for `#[nested_soa]` fields
+pub fn __private_read_struct_column<V: PolarsStructOfArray>(dataframe: &DataFrame, name: &str) -> PolarsResult<V> {
+    let series = dataframe.column(name)?.as_materialized_series();
+    if !matches!(series.dtype(), DataType::Struct(_)) {
+        return Err(PolarsError::SchemaMismatch(format!(
+            "in column `{}`: expected a struct series, got a series of {}", name, series.dtype()
+        ).into()));
+    }
+    check_no_nulls(series).map_err(|error| column_error(name, error))?;
+
+    let fields = series.struct_()?.fields_as_series();
+    let nested = DataFrame::new(fields.into_iter().map(IntoColumn::into_column).collect())?;
+    V::try_from_polars_columns(&nested).map_err(|error| column_error(name, error))
+}
@@ -0,0 +1,170 @@
+#![cfg(feature = "arrow")]
+#![allow(clippy::float_cmp)]
+
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+use soa_derive::arrow::{ArrowStructOfArray, RecordBatch};
+use soa_derive::StructOfArray;
+
+use soa_derive::arrow::arrow_array::{Array, ArrayRef, Float64Array, Int32Array, StringArray, StructArray};
+use soa_derive::arrow::arrow_schema::{DataType, Field, Schema};
+
+#[derive(Debug, Clone, PartialEq, StructOfArray)]
+#[soa_derive(Debug)]
+#[soa(arrow)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, StructOfArray)]
+#[soa(arrow)]
+pub struct Atom {
+    pub name: String,
+    pub charge: i32,
+    pub charged: bool,
+    pub mass: Option<f32>,
+    pub symbol: Option<String>,
+    #[nested_soa]
+    pub position: Point,
+}
+
+fn atoms() -> AtomVec {
+    let mut atoms = AtomVec::new();
+    atoms.push(Atom {
+        name: "H1".into(), charge: 1, charged: true, mass: Some(1.0),
+        symbol: Some("H".into()), position: Point { x: 0.0, y: 1.0 },
+    });
+    atoms.push(Atom {
+        name: "X".into(), charge: 0, charged: false, mass: None,
+        symbol: None, position: Point { x: 2.0, y: 3.0 },
+    });
+    atoms
+}
+
+#[test]
+fn schema() {
+    let schema = AtomVec::arrow_schema();
+    let fields = schema.fields();
+    assert_eq!(fields.len(), 6);
+
+    assert_eq!(fields[0].as_ref(), &Field::new("name", DataType::Utf8, false));
+    assert_eq!(fields[1].as_ref(), &Field::new("charge", DataType::Int32, false));
+    assert_eq!(fields[2].as_ref(), &Field::new("charged", DataType::Boolean, false));
+    assert_eq!(fields[3].as_ref(), &Field::new("mass", DataType::Float32, true));
+    assert_eq!(fields[4].as_ref(), &Field::new("symbol", DataType::Utf8, true));
+    assert_eq!(fields[5].as_ref(), &Field::new("position", DataType::Struct(PointVec::arrow_fields()), false));
+}
+
+#[test]
+fn round_trip() {
+    let atoms = atoms();
+
+    let batch = atoms.to_record_batch();
+    assert_eq!(batch.num_rows(), 2);
+    assert_eq!(batch.column(3).null_count(), 1);
+    let position = batch.column(5).as_any().downcast_ref::<StructArray>().unwrap();
+    let y = position.column_by_name("y").unwrap().as_any().downcast_ref::<Float64Array>().unwrap();
+    assert_eq!(y.values(), &[1.0, 3.0]);
+
+    let copy = AtomVec::try_from(batch).unwrap();
+    assert_eq!(copy.name, atoms.name);
+    assert_eq!(copy.charge, atoms.charge);
+    assert_eq!(copy.charged, atoms.charged);
+    assert_eq!(copy.mass, atoms.mass);
+    assert_eq!(copy.symbol, atoms.symbol);
+    assert_eq!(copy.position.x, atoms.position.x);
+    assert_eq!(copy.position.y, atoms.position.y);
+
+    let copy = AtomVec::try_from(atoms.into_record_batch()).unwrap();
+    assert_eq!(copy.get(1).unwrap().to_owned().position, Point { x: 2.0, y: 3.0 });
+}
+
+#[test]
+fn zero_copy() {
+    let mut points = PointVec::new();
+    for i in 0..100 {
+        points.push(Point { x: i as f64, y: 0.0 });
+    }
+    let pointer = points.x.as_ptr();
+
+    let batch = points.into_record_batch();
+    let x = batch.column(0).as_any().downcast_ref::<Float64Array>().unwrap();
+    assert_eq!(x.values().as_ptr(), pointer);
+
+    let points = PointVec::try_from(batch).unwrap();
+    assert_eq!(points.x.as_ptr(), pointer);
+    assert_eq!(points.x[42], 42.0);
+
+    // the data is copied if the record batch does not own it
+    let batch = points.into_record_batch();
+    let shared = batch.clone();
+    let points = PointVec::try_from(batch).unwrap();
+    assert_ne!(points.x.as_ptr(), pointer);
+    assert_eq!(points.x[42], 42.0);
+    drop(shared);
+}
+
+#[test]
+fn from_external_batch() {
+    // columns are matched by name, and additional columns are ignored
+    let schema = Schema::new(vec![
+        Field::new("y", DataType::Float64, true),
+        Field::new("label", DataType::Utf8, false),
+        Field::new("x", DataType::Float64, false),
+    ]);
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(Float64Array::from(vec![1.0, 2.0])),
+        Arc::new(StringArray::from(vec!["a", "b"])),
+        Arc::new(Float64Array::from(vec![3.0, 4.0])),
+    ];
+    let batch = RecordBatch::try_new(Arc::new(schema), columns).unwrap();
+
+    let points = PointVec::try_from(batch.clone()).unwrap();
+    assert_eq!(points.x, [3.0, 4.0]);
+    assert_eq!(points.y, [1.0, 2.0]);
+
+    let sliced = PointVec::try_from(batch.slice(1, 1)).unwrap();
+    assert_eq!(sliced.x, [4.0]);
+    assert_eq!(sliced.y, [2.0]);
+}
+
+#[test]
+fn errors() {
+    let schema = Schema::new(vec![Field::new("x", DataType::Float64, false)]);
+    let columns: Vec<ArrayRef> = vec![Arc::new(Float64Array::from(vec![1.0]))];
+    let batch = RecordBatch::try_new(Arc::new(schema), columns).unwrap();
+    let error = PointVec::try_from(batch).unwrap_err();
+    assert_eq!(error.to_string(), "Schema error: missing column `y`");
+
+    let schema = Schema::new(vec![
+        Field::new("x", DataType::Float64, false),
+        Field::new("y", DataType::Int32, false),
+    ]);
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(Float64Array::from(vec![1.0])),
+        Arc::new(Int32Array::from(vec![1])),
+    ];
+    let batch = RecordBatch::try_new(Arc::new(schema), columns).unwrap();
+    let error = PointVec::try_from(batch).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Invalid argument error: in column `y`: expected an array of Float64, got an array of Int32"
+    );
+
+    let schema = Schema::new(vec![
+        Field::new("x", DataType::Float64, true),
+        Field::new("y", DataType::Float64, true),
+    ]);
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(Float64Array::from(vec![Some(1.0), None])),
+        Arc::new(Float64Array::from(vec![1.0, 2.0])),
+    ];
+    let batch = RecordBatch::try_new(Arc::new(schema), columns).unwrap();
+    let error = PointVec::try_from(batch).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Invalid argument error: in column `x`: expected an array without nulls, got 1 null values"
+    );
+}
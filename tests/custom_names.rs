@@ -0,0 +1,79 @@
+use soa_derive::StructOfArray;
+
+// an existing type which would clash with the default generated names
+#[allow(dead_code)]
+pub struct PointVec;
+
+#[derive(Debug, Clone, PartialEq, StructOfArray)]
+#[soa_derive(Debug, Clone, PartialEq)]
+#[soa(vec = "PointColumns", slice = "PointColumnsView", slice_mut = "PointColumnsViewMut")]
+#[soa(ref = "PointRow", ref_mut = "PointRowMut", ptr = "PointColumnsPtr", ptr_mut = "PointColumnsPtrMut")]
+#[soa(iter = "PointRows", iter_mut = "PointRowsMut")]
+pub struct Point {
+    pub x: f32,
+    pub y: f32,
+}
+
+#[test]
+fn custom_names() {
+    let mut points = PointColumns::new();
+    points.push(Point { x: 1.0, y: 2.0 });
+    points.push(Point { x: 3.0, y: 4.0 });
+
+    let view: PointColumnsView<'_> = points.as_slice();
+    assert_eq!(view.x, [1.0, 3.0]);
+
+    let row: PointRow<'_> = points.index(1);
+    assert_eq!(row.to_owned(), Point { x: 3.0, y: 4.0 });
+
+    let mut rows: PointRows<'_> = points.iter();
+    assert_eq!(rows.next().unwrap().y, &2.0);
+
+    let mut view_mut: PointColumnsViewMut<'_> = points.as_mut_slice();
+    let row: PointRowMut<'_> = view_mut.index_mut(0);
+    *row.x = 10.0;
+    let rows: PointRowsMut<'_> = points.iter_mut();
+    assert_eq!(rows.count(), 2);
+
+    let ptr: PointColumnsPtr = points.as_ptr();
+    let ptr_mut: PointColumnsPtrMut = ptr.as_mut_ptr();
+    assert_eq!(unsafe { ptr_mut.read() }, Point { x: 10.0, y: 2.0 });
+
+    assert_eq!(points.x, [10.0, 3.0]);
+}
+
+mod facade {
+    pub mod soa {
+        pub use soa_derive::*;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, facade::soa::StructOfArray)]
+#[soa_derive(Debug, Clone, PartialEq)]
+#[soa(crate = "crate::facade::soa")]
+pub struct Particle {
+    pub name: String,
+    pub mass: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, facade::soa::StructOfArray)]
+#[soa(crate = "crate::facade::soa", vec = "ShapeColumns")]
+pub enum Shape {
+    Circle { r: f32 },
+    Empty,
+}
+
+#[test]
+fn crate_path() {
+    use facade::soa::{SoAVec, StructOfArray};
+
+    let mut particles = <Particle as StructOfArray>::Type::new();
+    SoAVec::push(&mut particles, Particle { name: "Na".into(), mass: 22.99 });
+    assert_eq!(particles.name, ["Na"]);
+    assert_eq!(particles.as_slice().get(0).unwrap().mass, &22.99);
+
+    let mut shapes = ShapeColumns::new();
+    shapes.push(Shape::Circle { r: 1.0 });
+    shapes.push(Shape::Empty);
+    assert_eq!(shapes.len(), 2);
+}
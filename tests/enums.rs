@@ -0,0 +1,147 @@
+use soa_derive::StructOfArray;
+
+#[derive(Debug, Clone, PartialEq, StructOfArray)]
+#[soa_derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    Circle { r: f32 },
+    Rect { w: f32, h: f32 },
+    Point(f32, f32),
+    Empty,
+}
+
+fn shapes() -> ShapeVec {
+    let mut shapes = ShapeVec::new();
+    shapes.push(Shape::Circle { r: 1.0 });
+    shapes.push(Shape::Rect { w: 2.0, h: 3.0 });
+    shapes.push(Shape::Empty);
+    shapes.push(Shape::Circle { r: 4.0 });
+    shapes.push(Shape::Point(5.0, 6.0));
+    shapes
+}
+
+#[test]
+fn push() {
+    let shapes = shapes();
+    assert_eq!(shapes.len(), 5);
+    assert!(!shapes.is_empty());
+
+    assert_eq!(shapes.kinds, [ShapeKind::Circle, ShapeKind::Rect, ShapeKind::Empty, ShapeKind::Circle, ShapeKind::Point]);
+    assert_eq!(shapes.indices, [0, 0, 0, 1, 0]);
+
+    assert_eq!(shapes.circle.r, [1.0, 4.0]);
+    assert_eq!(shapes.rect.w, [2.0]);
+    assert_eq!(shapes.rect.h, [3.0]);
+    assert_eq!(shapes.point.0, [5.0]);
+    assert_eq!(shapes.point.1, [6.0]);
+}
+
+#[test]
+fn get() {
+    let mut shapes = shapes();
+
+    match shapes.get(3).unwrap() {
+        ShapeRef::Circle(circle) => assert_eq!(circle.r, &4.0),
+        other => panic!("expected a circle, got {:?}", other),
+    }
+    assert!(matches!(shapes.get(2), Some(ShapeRef::Empty)));
+    assert!(shapes.get(5).is_none());
+
+    assert_eq!(shapes.get(1).unwrap().to_owned(), Shape::Rect { w: 2.0, h: 3.0 });
+    assert_eq!(shapes.get(4).unwrap().to_owned(), Shape::Point(5.0, 6.0));
+    assert_eq!(shapes.get(2).unwrap().to_owned(), Shape::Empty);
+
+    if let Some(ShapeRefMut::Rect(rect)) = shapes.get_mut(1) {
+        *rect.w = 10.0;
+    }
+    assert_eq!(shapes.rect.w, [10.0]);
+}
+
+#[test]
+fn iter() {
+    let shapes = shapes();
+    let owned = shapes.iter().map(|shape| shape.to_owned()).collect::<Vec<_>>();
+    assert_eq!(owned, [
+        Shape::Circle { r: 1.0 },
+        Shape::Rect { w: 2.0, h: 3.0 },
+        Shape::Empty,
+        Shape::Circle { r: 4.0 },
+        Shape::Point(5.0, 6.0),
+    ]);
+
+    assert_eq!(shapes.iter().len(), 5);
+    assert_eq!(shapes.iter().next_back().unwrap().to_owned(), Shape::Point(5.0, 6.0));
+
+    let collected = owned.into_iter().collect::<ShapeVec>();
+    assert_eq!(collected, shapes);
+
+    let mut circles = 0;
+    for shape in &shapes {
+        if let ShapeRef::Circle(_) = shape {
+            circles += 1;
+        }
+    }
+    assert_eq!(circles, 2);
+}
+
+#[test]
+fn retain() {
+    let mut shapes = shapes();
+    shapes.retain(|shape| match shape {
+        ShapeRef::Circle(circle) => *circle.r > 2.0,
+        ShapeRef::Empty => false,
+        _ => true,
+    });
+
+    assert_eq!(shapes.len(), 3);
+    assert_eq!(shapes.kinds, [ShapeKind::Rect, ShapeKind::Circle, ShapeKind::Point]);
+    assert_eq!(shapes.indices, [0, 0, 0]);
+    assert_eq!(shapes.circle.r, [4.0]);
+    assert_eq!(shapes.get(1).unwrap().to_owned(), Shape::Circle { r: 4.0 });
+
+    shapes.retain(|_| false);
+    assert!(shapes.is_empty());
+    assert!(shapes.circle.is_empty());
+    assert!(shapes.rect.is_empty());
+}
+
+#[test]
+fn pop() {
+    let mut shapes = shapes();
+    assert_eq!(shapes.pop(), Some(Shape::Point(5.0, 6.0)));
+    assert_eq!(shapes.pop(), Some(Shape::Circle { r: 4.0 }));
+    assert_eq!(shapes.pop(), Some(Shape::Empty));
+    assert_eq!(shapes.len(), 2);
+    assert_eq!(shapes.circle.r, [1.0]);
+
+    shapes.clear();
+    assert_eq!(shapes.pop(), None);
+}
+
+#[derive(Debug, Clone, PartialEq, StructOfArray)]
+#[soa_derive(Debug, Clone, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, StructOfArray)]
+#[soa_derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    Move {
+        #[nested_soa]
+        to: Point,
+        speed: f64,
+    },
+    Type(String),
+}
+
+#[test]
+fn nested() {
+    let mut events = EventVec::new();
+    events.push(Event::Type("hello".into()));
+    events.push(Event::Move { to: Point { x: 1.0, y: 2.0 }, speed: 3.0 });
+
+    assert_eq!(events.r#move.to.x, [1.0]);
+    assert_eq!(events.r#type.0, ["hello"]);
+    assert_eq!(events.get(1).unwrap().to_owned(), Event::Move { to: Point { x: 1.0, y: 2.0 }, speed: 3.0 });
+}
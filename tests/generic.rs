@@ -18,6 +18,18 @@ fn may_push<T: StructOfArray, V: SoAVec<T>>(vec: &mut V, val: T) {
     vec.push(val)
 }
 
+fn may_drain<T: StructOfArray, V: SoAVec<T>>(vec: &mut V) -> Vec<T> {
+    vec.drain(1..).collect()
+}
+
+fn may_dedup<T: StructOfArray, V: SoAVec<T>>(vec: &mut V) where for<'t> V::RefMut<'t>: PartialEq {
+    vec.dedup()
+}
+
+fn may_partition_point<'a, T: StructOfArray, S: SoASlice<T>, P>(slice: &'a S, pred: P) -> usize where P: FnMut(S::Ref<'a>) -> bool {
+    slice.partition_point(pred)
+}
+
 fn may_sort_generic<T: StructOfArray, V: SoAVec<T>>(vec: &mut V) where for<'t> V::Ref<'t> : PartialOrd {
     let mut indices: Vec<_> = (0..vec.len()).collect();
 
@@ -71,6 +83,14 @@ fn test_generic_type_behavior() {
     let a = x.index(0);
     let b = x.index(1);
     assert!(a.mass < b.mass);
+
+    may_push(&mut x, Particle::new("bar".into(), 1000.0));
+    may_dedup(&mut x);
+    assert_eq!(x.len(), 2);
+
+    let drained = may_drain(&mut x);
+    assert_eq!(drained, [Particle::new("bar".into(), 1000.0)]);
+    assert_eq!(x.len(), 1);
 }
 
 
@@ -183,6 +203,13 @@ fn test_ops() {
     let n = view.iter().count();
     assert!(n > 0);
 
+    let mut vec = ParticleVec::new();
+    vec.push(Particle::new("foo".into(), 50.0));
+    vec.push(Particle::new("bar".into(), 100.0));
+    vec.push(Particle::new("baz".into(), 1000.0));
+    let index = may_partition_point(&vec.as_slice(), |particle| *particle.mass < 500.0);
+    assert_eq!(index, 2);
+
     let mut vec = VecWrap::<Particle, ParticleVec>::new();
     vec.push(Particle::new("foo".into(), 100.0));
     vec.push(Particle::new("bar".into(), 1000.0));
@@ -0,0 +1,216 @@
+use std::fmt::Debug;
+use std::ops::Add;
+
+use soa_derive::{StructOfArray, SoAVec, SoASlice};
+
+pub trait Float: Copy + Debug + PartialEq + PartialOrd + Add<Output = Self> + Default {}
+impl Float for f32 {}
+impl Float for f64 {}
+
+#[derive(Debug, Clone, PartialEq, StructOfArray)]
+#[soa_derive(Debug, Clone, PartialEq)]
+pub struct Particle<T: Float> {
+    pub pos: [T; 3],
+    pub mass: T,
+}
+
+#[test]
+fn type_parameters() {
+    let mut particles = ParticleVec::<f32>::new();
+    particles.push(Particle { pos: [1.0, 2.0, 3.0], mass: 1.0 });
+    particles.push(Particle { pos: [4.0, 5.0, 6.0], mass: 2.0 });
+
+    assert_eq!(particles.len(), 2);
+    assert_eq!(particles.mass, [1.0, 2.0]);
+    assert_eq!(particles.index(1).pos, &[4.0, 5.0, 6.0]);
+
+    let total = particles.iter().fold(0.0, |acc, p| acc + *p.mass);
+    assert_eq!(total, 3.0);
+
+    for particle in particles.iter_mut() {
+        *particle.mass += 1.0;
+    }
+    assert_eq!(particles.mass, [2.0, 3.0]);
+
+    let slice = particles.slice(1..2);
+    assert_eq!(slice.len(), 1);
+    assert_eq!(slice.get(0).unwrap().mass, &3.0);
+
+    let cloned = particles.clone();
+    assert_eq!(cloned, particles);
+    assert_eq!(particles.pop(), Some(Particle { pos: [4.0, 5.0, 6.0], mass: 3.0 }));
+
+    let mut double = ParticleVec::<f64>::with_capacity(4);
+    double.extend(vec![Particle { pos: [0.5; 3], mass: 0.25 }]);
+    assert_eq!(double.mass, [0.25]);
+}
+
+fn total_mass<T: Float>(particles: ParticleSlice<'_, T>) -> T {
+    particles.iter().fold(T::default(), |acc, p| acc + *p.mass)
+}
+
+fn push_all<T: Float, V: SoAVec<Particle<T>>>(vec: &mut V, values: Vec<Particle<T>>) {
+    for value in values {
+        vec.push(value);
+    }
+}
+
+#[test]
+fn generic_functions() {
+    let mut particles = ParticleVec::<f64>::new();
+    push_all(&mut particles, vec![
+        Particle { pos: [0.0; 3], mass: 1.5 },
+        Particle { pos: [0.0; 3], mass: 2.5 },
+    ]);
+    assert_eq!(SoAVec::len(&particles), 2);
+    assert_eq!(total_mass(particles.as_slice()), 4.0);
+    assert_eq!(SoASlice::len(&particles.slice(1..2)), 1);
+}
+
+#[derive(Debug, Clone, PartialEq, StructOfArray)]
+#[soa_derive(Debug, Clone, PartialEq)]
+pub struct Labelled<'a, T> where T: Clone + Debug + PartialEq {
+    pub label: &'a str,
+    pub value: T,
+}
+
+#[test]
+fn lifetime_parameters() {
+    let names = String::from("first second");
+    let mut split = names.split(' ');
+
+    let mut labelled = LabelledVec::new();
+    labelled.push(Labelled { label: split.next().unwrap(), value: 1u8 });
+    labelled.push(Labelled { label: split.next().unwrap(), value: 2u8 });
+
+    assert_eq!(labelled.label, ["first", "second"]);
+    assert_eq!(labelled.get(1).unwrap().label, &"second");
+
+    let slice = labelled.as_slice();
+    let labels = slice.iter().map(|item| *item.label).collect::<Vec<_>>();
+    assert_eq!(labels, ["first", "second"]);
+
+    labelled.as_mut_slice().value.reverse();
+    assert_eq!(labelled.value, [2, 1]);
+}
+
+#[derive(Debug, Clone, PartialEq, StructOfArray)]
+#[soa_derive(Debug, Clone, PartialEq)]
+pub struct Vector<T: Copy + Debug + PartialEq, const N: usize> {
+    pub data: [T; N],
+    pub norm: T,
+}
+
+#[test]
+fn const_parameters() {
+    let mut vectors = VectorVec::<i32, 2>::new();
+    vectors.push(Vector { data: [3, 4], norm: 5 });
+    vectors.push(Vector { data: [6, 8], norm: 10 });
+
+    assert_eq!(vectors.data, [[3, 4], [6, 8]]);
+    assert_eq!(vectors.iter().map(|v| *v.norm).sum::<i32>(), 15);
+
+    let removed = vectors.remove(0);
+    assert_eq!(removed, Vector { data: [3, 4], norm: 5 });
+    assert_eq!(vectors.len(), 1);
+}
+
+// Use the same names as the generated code internally, to check they do not
+// collide with the ones used by the derive.
+#[derive(Debug, Clone, PartialEq, StructOfArray)]
+#[soa_derive(Debug, Clone, PartialEq)]
+pub struct Collision<'a, 'b, T: Clone + Debug + PartialEq, F: Clone + Debug + PartialEq> {
+    pub first: &'a T,
+    pub second: &'b F,
+}
+
+#[test]
+fn reserved_names() {
+    let first = 1u32;
+    let second = String::from("two");
+
+    let mut collisions = CollisionVec::new();
+    collisions.push(Collision { first: &first, second: &second });
+    assert_eq!(collisions.index(0).first, &&1);
+    assert_eq!(*collisions.index(0).second, &second);
+}
+
+// `R` is the type of the range given to `drain`, `splice`,
+// `extend_from_within` and `extract_if`
+#[derive(Debug, Clone, PartialEq, StructOfArray)]
+#[soa_derive(Debug, Clone, PartialEq)]
+pub struct Ranged<R: Clone + Debug + PartialEq> {
+    pub value: R,
+}
+
+#[test]
+fn reserved_range_name() {
+    let mut values = RangedVec::new();
+    for value in 0..5 {
+        values.push(Ranged { value });
+    }
+
+    let drained = values.drain(1..3).map(|ranged| ranged.value).collect::<Vec<_>>();
+    assert_eq!(drained, [1, 2]);
+    assert_eq!(values.value, [0, 3, 4]);
+
+    let replaced = values.splice(0..1, vec![Ranged { value: 7 }, Ranged { value: 8 }]);
+    assert_eq!(replaced.map(|ranged| ranged.value).collect::<Vec<_>>(), [0]);
+    assert_eq!(values.value, [7, 8, 3, 4]);
+
+    values.extend_from_within(2..);
+    assert_eq!(values.value, [7, 8, 3, 4, 3, 4]);
+
+    let extracted = values.extract_if(1..5, |ranged| *ranged.value == 3).map(|ranged| ranged.value).collect::<Vec<_>>();
+    assert_eq!(extracted, [3, 3]);
+    assert_eq!(values.value, [7, 8, 4, 4]);
+}
+
+// `B` and `P` are used by `binary_search_by_key` and `partition_point`
+#[derive(Debug, Clone, PartialEq, StructOfArray)]
+#[soa_derive(Debug, Clone, PartialEq)]
+pub struct Searched<B: Clone + Debug + PartialEq, P: Clone + Debug + PartialEq> {
+    pub key: B,
+    pub payload: P,
+}
+
+#[test]
+fn reserved_search_names() {
+    let mut values = SearchedVec::new();
+    for key in [1, 3, 5, 7] {
+        values.push(Searched { key, payload: key.to_string() });
+    }
+
+    assert_eq!(values.binary_search_by_key(&5, |value| *value.key), Ok(2));
+    assert_eq!(values.as_slice().binary_search_by_key(&4, |value| *value.key), Err(2));
+    assert_eq!(values.partition_point(|value| *value.key < 6), 3);
+    assert_eq!(values.as_mut_slice().partition_point(|value| *value.key < 2), 1);
+}
+
+#[derive(Debug, Clone, PartialEq, StructOfArray)]
+#[soa_derive(Debug, Clone, PartialEq)]
+pub struct Body<T: Float> {
+    #[nested_soa]
+    pub particle: Particle<T>,
+    pub velocity: [T; 3],
+}
+
+#[test]
+fn nested_generic() {
+    let mut bodies = BodyVec::<f32>::new();
+    bodies.push(Body {
+        particle: Particle { pos: [0.0; 3], mass: 1.0 },
+        velocity: [1.0, 0.0, 0.0],
+    });
+    bodies.push(Body {
+        particle: Particle { pos: [1.0; 3], mass: 2.0 },
+        velocity: [0.0, 1.0, 0.0],
+    });
+
+    assert_eq!(bodies.particle.mass, [1.0, 2.0]);
+    assert_eq!(bodies.index(1).particle.mass, &2.0);
+
+    let body = bodies.swap_remove(0);
+    assert_eq!(body.particle.mass, 1.0);
+    assert_eq!(bodies.len(), 1);
+}
@@ -93,3 +93,59 @@ fn extend() {
     particles.extend(&particles_from_iter);
     assert_eq!(particles, particles_from_iter);
 }
+
+#[test]
+fn into_iter() {
+    let mut particles = ParticleVec::new();
+    particles.push(Particle::new(String::from("Na"), 22.0));
+    particles.push(Particle::new(String::from("Cl"), 35.0));
+    particles.push(Particle::new(String::from("Zn"), 65.0));
+
+    let mut iter = particles.clone().into_iter();
+    assert_eq!(iter.len(), 3);
+    assert_eq!(iter.next(), Some(Particle::new(String::from("Na"), 22.0)));
+    assert_eq!(iter.next_back(), Some(Particle::new(String::from("Zn"), 65.0)));
+    assert_eq!(iter.len(), 1);
+    assert_eq!(iter.next(), Some(Particle::new(String::from("Cl"), 35.0)));
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+
+    let mut names = Vec::new();
+    for particle in particles {
+        names.push(particle.name);
+    }
+    assert_eq!(names, ["Na", "Cl", "Zn"]);
+}
+
+#[test]
+fn into_iter_drop() {
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use soa_derive::StructOfArray;
+
+    #[derive(StructOfArray)]
+    pub struct Counted {
+        pub value: u32,
+        pub drops: Rc<Cell<usize>>,
+    }
+
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            self.drops.set(self.drops.get() + 1);
+        }
+    }
+
+    let drops = Rc::new(Cell::new(0));
+    let mut vec = CountedVec::new();
+    for value in 0..3 {
+        vec.push(Counted { value, drops: drops.clone() });
+    }
+
+    let mut iter = vec.into_iter();
+    assert_eq!(iter.next().map(|item| item.value), Some(0));
+    assert_eq!(drops.get(), 1);
+
+    // the remaining values are dropped with the iterator
+    drop(iter);
+    assert_eq!(drops.get(), 3);
+}
@@ -0,0 +1,110 @@
+#![cfg(feature = "ndarray")]
+#![allow(clippy::float_cmp)]
+
+use soa_derive::ndarray::ndarray::{array, Axis};
+use soa_derive::StructOfArray;
+
+#[derive(Debug, Clone, PartialEq, StructOfArray)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, StructOfArray)]
+pub struct Particle {
+    pub name: String,
+    pub mass: f64,
+    pub kind: usize,
+    pub position: [f64; 3],
+    #[nested_soa]
+    pub velocity: Point,
+}
+
+#[derive(Debug, Clone, PartialEq, StructOfArray)]
+pub struct Pair(pub u32, pub [i8; 2]);
+
+#[derive(Debug, Clone, PartialEq, StructOfArray)]
+pub struct Generic<T> {
+    pub value: T,
+}
+
+fn particles() -> ParticleVec {
+    let mut particles = ParticleVec::new();
+    particles.push(Particle {
+        name: "H".into(), mass: 1.0, kind: 0, position: [0.0, 1.0, 2.0],
+        velocity: Point { x: 0.0, y: 0.0 },
+    });
+    particles.push(Particle {
+        name: "O".into(), mass: 16.0, kind: 1, position: [3.0, 4.0, 5.0],
+        velocity: Point { x: 1.0, y: 0.0 },
+    });
+    particles.push(Particle {
+        name: "C".into(), mass: 12.0, kind: 2, position: [6.0, 7.0, 8.0],
+        velocity: Point { x: 0.0, y: 1.0 },
+    });
+    particles
+}
+
+#[test]
+fn slice_views() {
+    let particles = particles();
+    let slice = particles.as_slice();
+
+    let mass = slice.mass_view();
+    assert_eq!(mass, array![1.0, 16.0, 12.0]);
+    assert_eq!(mass.as_ptr(), particles.mass.as_ptr());
+    assert_eq!(slice.kind_view(), array![0, 1, 2]);
+
+    let position = slice.position_view();
+    assert_eq!(position.shape(), [3, 3]);
+    assert_eq!(position, array![[0.0, 1.0, 2.0], [3.0, 4.0, 5.0], [6.0, 7.0, 8.0]]);
+    assert_eq!(position.as_ptr(), particles.position.as_ptr().cast());
+    assert_eq!(position.sum_axis(Axis(0)), array![9.0, 12.0, 15.0]);
+
+    assert_eq!(slice.velocity.x_view(), array![0.0, 1.0, 0.0]);
+
+    let sub = particles.slice(1..3);
+    assert_eq!(sub.position_view(), array![[3.0, 4.0, 5.0], [6.0, 7.0, 8.0]]);
+}
+
+#[test]
+fn slice_mut_views() {
+    let mut particles = particles();
+    let mut slice = particles.as_mut_slice();
+
+    assert_eq!(slice.mass_view(), array![1.0, 16.0, 12.0]);
+    slice.mass_view_mut().mapv_inplace(|mass| 2.0 * mass);
+
+    let mut position = slice.position_view_mut();
+    position.column_mut(1).fill(-1.0);
+    position.row_mut(2).assign(&array![10.0, 11.0, 12.0]);
+
+    slice.velocity.y_view_mut()[0] = 5.0;
+
+    assert_eq!(particles.mass, [2.0, 32.0, 24.0]);
+    assert_eq!(particles.position, [[0.0, -1.0, 2.0], [3.0, -1.0, 5.0], [10.0, 11.0, 12.0]]);
+    assert_eq!(particles.velocity.y, [5.0, 0.0, 1.0]);
+}
+
+#[test]
+fn tuple_structs() {
+    let mut pairs = PairVec::new();
+    pairs.push(Pair(1, [2, 3]));
+    pairs.push(Pair(4, [5, 6]));
+
+    assert_eq!(pairs.as_slice().field0_view(), array![1, 4]);
+    pairs.as_mut_slice().field1_view_mut()[[1, 0]] = 7;
+    assert_eq!(pairs.as_slice().field1_view(), array![[2, 3], [7, 6]]);
+}
+
+#[test]
+fn generic() {
+    let mut values = GenericVec::new();
+    values.push(Generic { value: [1.0f32, 2.0] });
+    values.push(Generic { value: [3.0f32, 4.0] });
+    assert_eq!(values.as_slice().value_view(), array![[1.0, 2.0], [3.0, 4.0]]);
+
+    let mut empty = GenericVec::<[f32; 0]>::new();
+    empty.push(Generic { value: [] });
+    assert_eq!(empty.as_slice().value_view().shape(), [1, 0]);
+}
@@ -1,6 +1,6 @@
 use soa_derive::StructOfArray;
 
-#[derive(Debug, Clone, PartialEq, StructOfArray)]
+#[derive(Debug, Clone, PartialEq, PartialOrd, StructOfArray)]
 pub struct Point {
     x: f32,
     y: f32,
@@ -10,7 +10,7 @@ mod other_mod {
     use soa_derive::StructOfArray;
 
     #[derive(Debug, Clone, PartialEq, StructOfArray)]
-    #[soa_derive(Debug, Clone, PartialEq)]
+    #[soa_derive(Debug, Clone, PartialEq, PartialOrd)]
     pub struct Color {
         pub r: u8,
         pub g: u8,
@@ -64,3 +64,266 @@ fn nested_soa() {
         a: vec![255, 23],
     });
 }
+
+#[test]
+fn into_iter() {
+    let mut particle_vec = ParticleVec::new();
+    particle_vec.push(Particle {
+        point: Point { x: 1.0, y: 2.0 },
+        color: Color { r: 255, g: 0, b: 0, a: 255 },
+        mass: 1.0,
+    });
+    particle_vec.push(Particle {
+        point: Point { x: 2.0, y: 3.0 },
+        color: Color { r: 0, g: 0, b: 255, a: 255 },
+        mass: 2.0,
+    });
+
+    let particles = particle_vec.into_iter().rev().collect::<Vec<_>>();
+    assert_eq!(particles, [
+        Particle {
+            point: Point { x: 2.0, y: 3.0 },
+            color: Color { r: 0, g: 0, b: 255, a: 255 },
+            mass: 2.0,
+        },
+        Particle {
+            point: Point { x: 1.0, y: 2.0 },
+            color: Color { r: 255, g: 0, b: 0, a: 255 },
+            mass: 1.0,
+        },
+    ]);
+}
+
+#[test]
+fn drain() {
+    let mut particle_vec = ParticleVec::new();
+    for i in 0..4 {
+        particle_vec.push(Particle {
+            point: Point { x: i as f32, y: 0.0 },
+            color: Color { r: i, g: 0, b: 0, a: 255 },
+            mass: i as f32,
+        });
+    }
+
+    let drained = particle_vec.drain(1..3).map(|particle| particle.color.r).collect::<Vec<_>>();
+    assert_eq!(drained, [1, 2]);
+    assert_eq!(particle_vec.len(), 2);
+    assert_eq!(particle_vec.point, [Point { x: 0.0, y: 0.0 }, Point { x: 3.0, y: 0.0 }]);
+    assert_eq!(particle_vec.color.r, [0, 3]);
+    assert_eq!(particle_vec.mass, [0.0, 3.0]);
+}
+
+#[test]
+fn splice() {
+    let mut particle_vec = ParticleVec::new();
+    for i in 0..3 {
+        particle_vec.push(Particle {
+            point: Point { x: i as f32, y: 0.0 },
+            color: Color { r: i, g: 0, b: 0, a: 255 },
+            mass: i as f32,
+        });
+    }
+
+    let new = Particle {
+        point: Point { x: 10.0, y: 0.0 },
+        color: Color { r: 10, g: 0, b: 0, a: 255 },
+        mass: 10.0,
+    };
+    let removed = particle_vec.splice(1..2, vec![new.clone(), new]).map(|particle| particle.color.r).collect::<Vec<_>>();
+    assert_eq!(removed, [1]);
+    assert_eq!(particle_vec.color.r, [0, 10, 10, 2]);
+    assert_eq!(particle_vec.mass, [0.0, 10.0, 10.0, 2.0]);
+}
+
+#[test]
+fn dedup() {
+    let mut particle_vec = ParticleVec::new();
+    for &(r, mass) in &[(0, 0.0), (0, 0.0), (1, 0.0), (1, 1.0), (1, 1.0)] {
+        particle_vec.push(Particle {
+            point: Point { x: 0.0, y: 0.0 },
+            color: Color { r, g: 0, b: 0, a: 255 },
+            mass,
+        });
+    }
+
+    particle_vec.dedup();
+    assert_eq!(particle_vec.color.r, [0, 1, 1]);
+    assert_eq!(particle_vec.mass, [0.0, 0.0, 1.0]);
+
+    particle_vec.dedup_by_key(|particle| *particle.color.r);
+    assert_eq!(particle_vec.color.r, [0, 1]);
+    assert_eq!(particle_vec.mass, [0.0, 0.0]);
+}
+
+#[test]
+fn extract_if() {
+    let mut particle_vec = ParticleVec::new();
+    for i in 0..5 {
+        particle_vec.push(Particle {
+            point: Point { x: i as f32, y: 0.0 },
+            color: Color { r: i, g: 0, b: 0, a: 255 },
+            mass: i as f32,
+        });
+    }
+
+    let extracted = particle_vec.extract_if(.., |particle| *particle.color.r % 2 == 1)
+        .map(|particle| particle.color.r)
+        .collect::<Vec<_>>();
+    assert_eq!(extracted, [1, 3]);
+    assert_eq!(particle_vec.color.r, [0, 2, 4]);
+    assert_eq!(particle_vec.mass, [0.0, 2.0, 4.0]);
+}
+
+#[test]
+fn chunks() {
+    let mut particle_vec = ParticleVec::new();
+    for i in 0..5 {
+        particle_vec.push(Particle {
+            point: Point { x: i as f32, y: 0.0 },
+            color: Color { r: i, g: 0, b: 0, a: 255 },
+            mass: i as f32,
+        });
+    }
+
+    let reds = particle_vec.chunks(2).map(|chunk| chunk.color.r.to_vec()).collect::<Vec<_>>();
+    assert_eq!(reds, [vec![0, 1], vec![2, 3], vec![4]]);
+
+    let reds = particle_vec.windows(4).map(|window| window.color.r.to_vec()).collect::<Vec<_>>();
+    assert_eq!(reds, [vec![0, 1, 2, 3], vec![1, 2, 3, 4]]);
+
+    for chunk in particle_vec.chunks_mut(3) {
+        for r in chunk.color.r.iter_mut() {
+            *r *= 2;
+        }
+    }
+    assert_eq!(particle_vec.color.r, [0, 2, 4, 6, 8]);
+}
+
+#[test]
+fn binary_search() {
+    let mut particle_vec = ParticleVec::new();
+    for i in 0..5 {
+        particle_vec.push(Particle {
+            point: Point { x: i as f32, y: 0.0 },
+            color: Color { r: 2 * i, g: 0, b: 0, a: 255 },
+            mass: i as f32,
+        });
+    }
+
+    assert_eq!(particle_vec.binary_search_by_key(&4, |particle| *particle.color.r), Ok(2));
+    assert_eq!(particle_vec.binary_search_by_key(&5, |particle| *particle.color.r), Err(3));
+    assert_eq!(particle_vec.as_slice().partition_point(|particle| particle.point.x < 3.0), 3);
+}
+
+#[test]
+fn reorder() {
+    let mut particle_vec = ParticleVec::new();
+    for i in 0..4 {
+        particle_vec.push(Particle {
+            point: Point { x: i as f32, y: 0.0 },
+            color: Color { r: i, g: 0, b: 0, a: 255 },
+            mass: i as f32,
+        });
+    }
+
+    particle_vec.as_mut_slice().reverse();
+    assert_eq!(particle_vec.color.r, [3, 2, 1, 0]);
+    assert_eq!(particle_vec.mass, [3.0, 2.0, 1.0, 0.0]);
+
+    particle_vec.as_mut_slice().rotate_left(1);
+    assert_eq!(particle_vec.color.r, [2, 1, 0, 3]);
+    particle_vec.as_mut_slice().rotate_right(1);
+    assert_eq!(particle_vec.color.r, [3, 2, 1, 0]);
+
+    let (mut start, end) = particle_vec.as_mut_slice().split_at_mut(2);
+    start.swap_with_slice(end);
+    assert_eq!(particle_vec.color.r, [1, 0, 3, 2]);
+    assert_eq!(particle_vec.point[0], Point { x: 1.0, y: 0.0 });
+
+    particle_vec.as_mut_slice().fill_with(|| Particle {
+        point: Point { x: 0.0, y: 0.0 },
+        color: Color { r: 7, g: 0, b: 0, a: 255 },
+        mass: 1.0,
+    });
+    assert_eq!(particle_vec.color.r, [7, 7, 7, 7]);
+    assert_eq!(particle_vec.mass, [1.0, 1.0, 1.0, 1.0]);
+}
+
+#[test]
+fn sort_unstable() {
+    let mut particle_vec = ParticleVec::new();
+    for i in [3, 0, 4, 1, 2] {
+        particle_vec.push(Particle {
+            point: Point { x: i as f32, y: 0.0 },
+            color: Color { r: i, g: 0, b: 0, a: 255 },
+            mass: i as f32,
+        });
+    }
+
+    particle_vec.as_mut_slice().sort_unstable_by_key(|particle| *particle.color.r);
+    assert_eq!(particle_vec.color.r, [0, 1, 2, 3, 4]);
+    assert_eq!(particle_vec.mass, [0.0, 1.0, 2.0, 3.0, 4.0]);
+    assert_eq!(particle_vec.point[3], Point { x: 3.0, y: 0.0 });
+}
+
+#[test]
+fn sort_by_columns() {
+    let mut particle_vec = ParticleVec::new();
+    for (x, r) in [(1.0, 2), (0.0, 1), (2.0, 1), (3.0, 2)] {
+        particle_vec.push(Particle {
+            point: Point { x, y: 0.0 },
+            color: Color { r, g: 0, b: 0, a: 255 },
+            mass: x,
+        });
+    }
+
+    particle_vec.sort_by_columns(&[ParticleColumn::Color.desc(), ParticleColumn::Mass.asc()]);
+    assert_eq!(particle_vec.color.r, [2, 2, 1, 1]);
+    assert_eq!(particle_vec.mass, [1.0, 3.0, 0.0, 2.0]);
+    assert_eq!(particle_vec.point[1], Point { x: 3.0, y: 0.0 });
+}
+
+#[test]
+fn gather_scatter() {
+    let mut particle_vec = ParticleVec::new();
+    for i in 0..4 {
+        particle_vec.push(Particle {
+            point: Point { x: i as f32, y: 0.0 },
+            color: Color { r: i, g: 0, b: 0, a: 255 },
+            mass: i as f32,
+        });
+    }
+
+    let selected = particle_vec.as_slice().gather(&[3, 1]);
+    assert_eq!(selected.color.r, [3, 1]);
+    assert_eq!(selected.mass, [3.0, 1.0]);
+
+    particle_vec.as_mut_slice().scatter_from(&[0, 2], selected.as_slice());
+    assert_eq!(particle_vec.color.r, [3, 1, 1, 3]);
+    assert_eq!(particle_vec.mass, [3.0, 1.0, 1.0, 3.0]);
+    assert_eq!(particle_vec.point[2], Point { x: 1.0, y: 0.0 });
+}
+
+#[test]
+fn mask() {
+    let mut particle_vec = ParticleVec::new();
+    for i in 0..4 {
+        particle_vec.push(Particle {
+            point: Point { x: i as f32, y: 0.0 },
+            color: Color { r: i, g: 0, b: 0, a: 255 },
+            mass: i as f32,
+        });
+    }
+
+    let even = particle_vec.color.r.iter().map(|r| r % 2 == 0).collect::<Vec<_>>();
+    let filtered = particle_vec.filter_by_mask(&even);
+    assert_eq!(filtered.color.r, [0, 2]);
+    assert_eq!(filtered.mass, [0.0, 2.0]);
+
+    let selection = particle_vec.select_by_mask(&even);
+    assert_eq!(*selection.index(1).color.r, 2);
+
+    particle_vec.retain_by_mask(&even);
+    assert_eq!(particle_vec.color.r, [0, 2]);
+    assert_eq!(particle_vec.point[1], Point { x: 2.0, y: 0.0 });
+}
@@ -0,0 +1,107 @@
+#![cfg(feature = "polars")]
+#![allow(clippy::float_cmp)]
+
+use soa_derive::polars::polars_core::prelude::{Column, DataFrame, DataType, NamedFrom, Series};
+use soa_derive::StructOfArray;
+
+#[derive(Debug, Clone, PartialEq, StructOfArray)]
+#[soa_derive(Debug)]
+#[soa(polars)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, StructOfArray)]
+#[soa_derive(Debug)]
+#[soa(polars)]
+pub struct Atom {
+    pub name: String,
+    pub charge: i8,
+    pub charged: bool,
+    pub mass: Option<f32>,
+    pub symbol: Option<String>,
+    #[nested_soa]
+    pub position: Point,
+}
+
+fn atoms() -> AtomVec {
+    let mut atoms = AtomVec::new();
+    atoms.push(Atom {
+        name: "H1".into(), charge: 1, charged: true, mass: Some(1.0),
+        symbol: Some("H".into()), position: Point { x: 0.0, y: 1.0 },
+    });
+    atoms.push(Atom {
+        name: "X".into(), charge: 0, charged: false, mass: None,
+        symbol: None, position: Point { x: 2.0, y: 3.0 },
+    });
+    atoms
+}
+
+#[test]
+fn round_trip() {
+    let dataframe = atoms().into_dataframe();
+    assert_eq!(dataframe.shape(), (2, 6));
+    assert_eq!(dataframe.get_column_names(), ["name", "charge", "charged", "mass", "symbol", "position"]);
+    assert_eq!(dataframe.column("charge").unwrap().dtype(), &DataType::Int8);
+    assert_eq!(dataframe.column("mass").unwrap().null_count(), 1);
+    assert!(matches!(dataframe.column("position").unwrap().dtype(), DataType::Struct(_)));
+
+    let atoms = AtomVec::try_from_dataframe(&dataframe).unwrap();
+    assert_eq!(atoms.name, ["H1", "X"]);
+    assert_eq!(atoms.charge, [1, 0]);
+    assert_eq!(atoms.charged, [true, false]);
+    assert_eq!(atoms.mass, [Some(1.0), None]);
+    assert_eq!(atoms.symbol, [Some("H".into()), None]);
+    assert_eq!(atoms.position.x, [0.0, 2.0]);
+    assert_eq!(atoms.position.y, [1.0, 3.0]);
+}
+
+#[test]
+fn from_external_dataframe() {
+    // columns are matched by name, and additional columns are ignored
+    let dataframe = DataFrame::new(vec![
+        Column::new("y".into(), [1.0, 2.0]),
+        Column::new("label".into(), ["a", "b"]),
+        Column::new("x".into(), [3.0, 4.0]),
+    ]).unwrap();
+
+    let points = PointVec::try_from_dataframe(&dataframe).unwrap();
+    assert_eq!(points.x, [3.0, 4.0]);
+    assert_eq!(points.y, [1.0, 2.0]);
+
+    let points = PointVec::try_from_dataframe(&dataframe.slice(1, 1)).unwrap();
+    assert_eq!(points.x, [4.0]);
+}
+
+#[test]
+fn errors() {
+    let dataframe = DataFrame::new(vec![Column::new("x".into(), [1.0])]).unwrap();
+    let error = PointVec::try_from_dataframe(&dataframe).unwrap_err();
+    assert!(error.to_string().contains("\"y\" not found"), "{}", error);
+
+    let dataframe = DataFrame::new(vec![
+        Column::new("x".into(), [1.0]),
+        Column::new("y".into(), [1i32]),
+    ]).unwrap();
+    let error = PointVec::try_from_dataframe(&dataframe).unwrap_err();
+    assert_eq!(error.to_string(), "in column `y`: expected a series of f64, got a series of i32");
+
+    let dataframe = DataFrame::new(vec![
+        Series::new("x".into(), [Some(1.0), None]).into(),
+        Column::new("y".into(), [1.0, 2.0]),
+    ]).unwrap();
+    let error = PointVec::try_from_dataframe(&dataframe).unwrap_err();
+    assert_eq!(error.to_string(), "in column `x`: expected a series without nulls, got 1 null values");
+
+    let dataframe = DataFrame::new(vec![
+        Column::new("name".into(), ["H"]),
+        Column::new("charge".into(), [1i8]),
+        Column::new("charged".into(), [true]),
+        Column::new("mass".into(), [1.0f32]),
+        Column::new("symbol".into(), ["H"]),
+        Column::new("position".into(), [1.0]),
+    ]).unwrap();
+    let error = AtomVec::try_from_dataframe(&dataframe).unwrap_err();
+    assert_eq!(error.to_string(), "in column `position`: expected a struct series, got a series of f64");
+}
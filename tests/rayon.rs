@@ -0,0 +1,165 @@
+#![cfg(feature = "rayon")]
+#![allow(clippy::float_cmp)]
+
+use rayon::prelude::*;
+use soa_derive::{par_soa_zip, StructOfArray};
+
+mod particles;
+use self::particles::{Particle, ParticleVec};
+
+#[derive(Debug, Clone, PartialEq, StructOfArray)]
+#[soa_derive(Debug, Clone, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, StructOfArray)]
+#[soa_derive(Debug, Clone, PartialEq)]
+pub struct Body {
+    #[nested_soa]
+    pub position: Point,
+    pub mass: f64,
+}
+
+fn particles(size: usize) -> ParticleVec {
+    let mut particles = ParticleVec::new();
+    for i in 0..size {
+        particles.push(Particle::new(format!("Na{}", i), i as f64));
+    }
+    particles
+}
+
+#[test]
+fn par_iter() {
+    let particles = particles(1000);
+
+    let total: f64 = particles.par_iter().map(|particle| *particle.mass).sum();
+    assert_eq!(total, 499_500.0);
+
+    let names = particles.as_slice().par_iter().map(|particle| particle.name.clone()).collect::<Vec<_>>();
+    assert_eq!(names[42], "Na42");
+    assert_eq!(particles.as_slice().par_iter().len(), 1000);
+
+    let position = particles.par_iter().position_any(|particle| *particle.mass == 27.0);
+    assert_eq!(position, Some(27));
+
+    let count = (&particles).into_par_iter().filter(|particle| *particle.mass > 100.0).count();
+    assert_eq!(count, 899);
+}
+
+#[test]
+fn par_iter_mut() {
+    let mut particles = particles(1000);
+
+    particles.par_iter_mut().for_each(|particle| *particle.mass *= 2.0);
+    assert_eq!(particles.mass[500], 1000.0);
+
+    particles.as_mut_slice().par_iter_mut().enumerate().for_each(|(i, particle)| {
+        particle.name.push_str(if i % 2 == 0 { "-even" } else { "-odd" });
+    });
+    assert_eq!(particles.name[3], "Na3-odd");
+
+    let total: f64 = particles.as_mut_slice().par_iter().map(|particle| *particle.mass).sum();
+    assert_eq!(total, 999_000.0);
+}
+
+#[test]
+fn nested() {
+    let mut bodies = BodyVec::new();
+    for i in 0..100 {
+        bodies.push(Body { position: Point { x: i as f64, y: 0.0 }, mass: 1.0 });
+    }
+
+    bodies.par_iter_mut().for_each(|body| *body.position.y = *body.position.x * *body.mass);
+    assert_eq!(bodies.position.y[10], 10.0);
+
+    let total: f64 = bodies.par_iter().map(|body| *body.position.y).sum();
+    assert_eq!(total, 4950.0);
+}
+
+#[test]
+fn zip() {
+    let mut particles = particles(100);
+    let factors = vec![3.0; 100];
+
+    par_soa_zip!(&mut particles, [mut mass], &factors).for_each(|(mass, factor)| {
+        *mass *= factor;
+    });
+    assert_eq!(particles.mass[10], 30.0);
+
+    let total: f64 = par_soa_zip!(&particles, [name, mass])
+        .filter(|(name, _)| name.ends_with('0'))
+        .map(|(_, mass)| *mass)
+        .sum();
+    assert_eq!(total, 3.0 * 450.0);
+
+    let slice = particles.as_slice();
+    assert_eq!(par_soa_zip!(&slice, [mass]).len(), 100);
+}
+
+#[test]
+fn par_sort() {
+    let mut particles = particles(1000);
+    particles.par_sort_by(|a, b| b.mass.partial_cmp(a.mass).unwrap());
+    assert_eq!(particles.mass[0], 999.0);
+    assert_eq!(particles.name[0], "Na999");
+    assert_eq!(particles.name[999], "Na0");
+
+    particles.as_mut_slice().par_sort_unstable_by_key(|particle| particle.name.len());
+    assert!(particles.name.windows(2).all(|names| names[0].len() <= names[1].len()));
+    for (name, mass) in particles.name.iter().zip(&particles.mass) {
+        assert_eq!(*name, format!("Na{}", mass));
+    }
+}
+
+#[test]
+fn par_retain() {
+    let mut particles = particles(1000);
+    particles.par_retain(|particle| *particle.mass % 3.0 == 0.0);
+    assert_eq!(particles.len(), 334);
+    assert_eq!(particles.mass[1], 3.0);
+    assert_eq!(particles.name[333], "Na999");
+}
+
+#[test]
+fn nested_sort_and_retain() {
+    let mut bodies = BodyVec::new();
+    for i in 0..100 {
+        bodies.push(Body { position: Point { x: i as f64, y: -i as f64 }, mass: (i % 10) as f64 });
+    }
+
+    bodies.par_sort_by(|a, b| a.position.y.partial_cmp(b.position.y).unwrap());
+    assert_eq!(bodies.position.x[0], 99.0);
+    assert_eq!(bodies.mass[0], 9.0);
+
+    bodies.par_retain(|body| *body.mass == 0.0);
+    assert_eq!(bodies.len(), 10);
+    assert_eq!(bodies.position.x[0], 90.0);
+    assert_eq!(bodies.position.y[9], 0.0);
+}
+
+// `C` and `CB` are used by the generated parallel iterators
+#[derive(Debug, Clone, PartialEq, StructOfArray)]
+pub struct Generic<C: Send + Sync, CB: Send + Sync> {
+    pub first: C,
+    pub second: CB,
+}
+
+#[test]
+fn generic() {
+    let mut values = GenericVec::new();
+    for i in 0..100 {
+        values.push(Generic { first: i, second: i as f64 });
+    }
+
+    let total: i32 = values.par_iter().map(|value| *value.first).sum();
+    assert_eq!(total, 4950);
+
+    values.par_iter_mut().for_each(|value| *value.second *= 2.0);
+    let total: f64 = values.as_slice().par_iter().map(|value| *value.second).sum();
+    assert_eq!(total, 9900.0);
+
+    let firsts = values.as_slice().into_par_iter().with_min_len(10).map(|value| *value.first).collect::<Vec<_>>();
+    assert_eq!(firsts, (0..100).collect::<Vec<_>>());
+}
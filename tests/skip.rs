@@ -0,0 +1,141 @@
+use std::cell::Cell;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+use soa_derive::StructOfArray;
+
+#[derive(Debug, Clone, PartialEq, StructOfArray)]
+#[soa_derive(Debug, Clone, PartialEq)]
+pub struct Particle {
+    pub name: String,
+    pub mass: f64,
+    #[soa(skip)]
+    pub cached_hash: Option<u64>,
+}
+
+#[test]
+fn skipped_fields() {
+    let mut particles = ParticleVec::new();
+    particles.push(Particle { name: "Na".into(), mass: 22.99, cached_hash: Some(42) });
+    particles.push(Particle { name: "Cl".into(), mass: 35.45, cached_hash: Some(12) });
+
+    assert_eq!(particles.name, ["Na", "Cl"]);
+    assert_eq!(particles.mass, [22.99, 35.45]);
+
+    let particle = particles.index(0).to_owned();
+    assert_eq!(particle, Particle { name: "Na".into(), mass: 22.99, cached_hash: None });
+
+    let particle = particles.pop().unwrap();
+    assert_eq!(particle, Particle { name: "Cl".into(), mass: 35.45, cached_hash: None });
+
+    particles.insert(0, Particle { name: "H".into(), mass: 1.008, cached_hash: Some(3) });
+    let replaced = particles.replace(1, Particle { name: "O".into(), mass: 15.999, cached_hash: Some(5) });
+    assert_eq!(replaced, Particle { name: "Na".into(), mass: 22.99, cached_hash: None });
+
+    let replaced = particles.index_mut(0).replace(Particle { name: "He".into(), mass: 4.0026, cached_hash: Some(7) });
+    assert_eq!(replaced, Particle { name: "H".into(), mass: 1.008, cached_hash: None });
+
+    let removed = particles.remove(0);
+    assert_eq!(removed, Particle { name: "He".into(), mass: 4.0026, cached_hash: None });
+    let removed = particles.swap_remove(0);
+    assert_eq!(removed, Particle { name: "O".into(), mass: 15.999, cached_hash: None });
+    assert!(particles.is_empty());
+}
+
+fn unknown_id() -> usize {
+    usize::MAX
+}
+
+#[derive(Debug, Clone, PartialEq, StructOfArray)]
+#[soa_derive(Debug, Clone, PartialEq)]
+pub struct Node(pub f32, #[soa(skip, default = "unknown_id")] pub usize, pub bool);
+
+#[test]
+fn tuple_struct() {
+    let mut nodes = NodeVec::new();
+    nodes.push(Node(1.0, 3, true));
+    nodes.push(Node(2.0, 4, false));
+
+    // the generated types only contain the stored fields
+    assert_eq!(nodes.0, [1.0, 2.0]);
+    assert_eq!(nodes.1, [true, false]);
+    assert_eq!(nodes.index(1).1, &false);
+
+    assert_eq!(nodes.index(0).to_owned(), Node(1.0, usize::MAX, true));
+    assert_eq!(nodes.pop(), Some(Node(2.0, usize::MAX, false)));
+
+    let ptr = nodes.as_ptr();
+    assert_eq!(unsafe { ptr.read() }, Node(1.0, usize::MAX, true));
+}
+
+/// Marker for the unit of a `Length`
+pub trait Unit: Copy + std::fmt::Debug + PartialEq {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Meter;
+impl Unit for Meter {}
+
+#[derive(Debug, Clone, Copy, PartialEq, StructOfArray)]
+#[soa_derive(Debug, Clone, PartialEq)]
+pub struct Length<U: Unit> {
+    pub value: f64,
+    #[soa(skip)]
+    pub _unit: PhantomData<U>,
+}
+
+#[derive(Debug, Clone, PartialEq, StructOfArray)]
+pub struct Borrowed<'s, T>(pub usize, #[soa(skip)] pub PhantomData<&'s T>);
+
+#[test]
+fn phantom_data() {
+    let mut lengths = LengthVec::<Meter>::new();
+    lengths.push(Length { value: 3.0, _unit: PhantomData });
+    lengths.push(Length { value: 1.0, _unit: PhantomData });
+    assert_eq!(lengths.value, [3.0, 1.0]);
+    assert_eq!(lengths.iter().map(|length| *length.value).sum::<f64>(), 4.0);
+
+    lengths.as_mut_slice().sort_by(|a, b| a.value.total_cmp(b.value));
+    assert_eq!(lengths.as_slice().first().unwrap().to_owned(), Length { value: 1.0, _unit: PhantomData });
+    assert_eq!(lengths.pop(), Some(Length { value: 3.0, _unit: PhantomData }));
+
+    let mut borrowed = BorrowedVec::<String>::new();
+    borrowed.push(Borrowed(4, PhantomData));
+    borrowed.extend(vec![Borrowed(2, PhantomData)]);
+    assert_eq!(borrowed.0, [4, 2]);
+    assert_eq!(borrowed.drain(..).collect::<Vec<_>>(), [Borrowed(4, PhantomData), Borrowed(2, PhantomData)]);
+}
+
+#[derive(Debug, Default)]
+pub struct DropCounter(Option<Rc<Cell<usize>>>);
+
+impl Drop for DropCounter {
+    fn drop(&mut self) {
+        if let Some(counter) = &self.0 {
+            counter.set(counter.get() + 1);
+        }
+    }
+}
+
+#[derive(Debug, StructOfArray)]
+pub struct WithCounter {
+    pub value: u32,
+    #[soa(skip)]
+    pub counter: DropCounter,
+}
+
+#[test]
+fn skipped_fields_are_dropped() {
+    let counter = Rc::new(Cell::new(0));
+    let mut vec = WithCounterVec::new();
+    vec.push(WithCounter { value: 1, counter: DropCounter(Some(counter.clone())) });
+    assert_eq!(counter.get(), 1);
+    vec.insert(0, WithCounter { value: 2, counter: DropCounter(Some(counter.clone())) });
+    assert_eq!(counter.get(), 2);
+    vec.replace(0, WithCounter { value: 3, counter: DropCounter(Some(counter.clone())) });
+    assert_eq!(counter.get(), 3);
+    vec.index_mut(0).replace(WithCounter { value: 4, counter: DropCounter(Some(counter.clone())) });
+    assert_eq!(counter.get(), 4);
+
+    assert_eq!(vec.value, [4, 1]);
+    assert_eq!(Rc::strong_count(&counter), 1);
+}
@@ -1,7 +1,16 @@
 #![allow(clippy::float_cmp)]
 
+use soa_derive::StructOfArray;
+
 mod particles;
-use self::particles::{Particle, ParticleVec};
+use self::particles::{Particle, ParticleSlice, ParticleVec};
+
+#[derive(Debug, Clone, PartialEq, StructOfArray)]
+#[soa_attr(Ref, derive(PartialEq, Eq, PartialOrd, Ord))]
+pub struct Version {
+    pub major: u32,
+    pub minor: u32,
+}
 
 #[test]
 fn len() {
@@ -97,3 +106,116 @@ fn get() {
         assert_eq!(particles.as_slice().get_unchecked(0).name, "Cl");
     }
 }
+
+fn names(slice: ParticleSlice<'_>) -> Vec<&str> {
+    slice.name.iter().map(|name| name.as_str()).collect()
+}
+
+#[test]
+fn chunks() {
+    let mut particles = ParticleVec::new();
+    for name in ["H", "He", "Li", "Be", "B"] {
+        particles.push(Particle::new(String::from(name), 0.0));
+    }
+    let slice = particles.as_slice();
+
+    let chunks = slice.chunks(2).map(names).collect::<Vec<_>>();
+    assert_eq!(chunks, [vec!["H", "He"], vec!["Li", "Be"], vec!["B"]]);
+    assert_eq!(slice.chunks(2).len(), 3);
+    let chunks = slice.chunks(2).rev().map(names).collect::<Vec<_>>();
+    assert_eq!(chunks, [vec!["B"], vec!["Li", "Be"], vec!["H", "He"]]);
+
+    let mut chunks = slice.chunks_exact(2);
+    assert_eq!(chunks.len(), 2);
+    assert_eq!(names(chunks.remainder()), ["B"]);
+    assert_eq!(chunks.next_back().map(names), Some(vec!["Li", "Be"]));
+    assert_eq!(chunks.next().map(names), Some(vec!["H", "He"]));
+    assert!(chunks.next().is_none());
+
+    let chunks = particles.rchunks(2).map(names).collect::<Vec<_>>();
+    assert_eq!(chunks, [vec!["Be", "B"], vec!["He", "Li"], vec!["H"]]);
+    let chunks = particles.rchunks(2).rev().map(names).collect::<Vec<_>>();
+    assert_eq!(chunks, [vec!["H"], vec!["He", "Li"], vec!["Be", "B"]]);
+}
+
+#[test]
+fn windows() {
+    let mut particles = ParticleVec::new();
+    for name in ["H", "He", "Li", "Be"] {
+        particles.push(Particle::new(String::from(name), 0.0));
+    }
+
+    let windows = particles.windows(3).map(names).collect::<Vec<_>>();
+    assert_eq!(windows, [vec!["H", "He", "Li"], vec!["He", "Li", "Be"]]);
+    assert_eq!(particles.windows(3).len(), 2);
+    assert_eq!(particles.windows(5).len(), 0);
+
+    let windows = particles.as_slice().windows(2).rev().map(names).collect::<Vec<_>>();
+    assert_eq!(windows, [vec!["Li", "Be"], vec!["He", "Li"], vec!["H", "He"]]);
+}
+
+#[test]
+#[should_panic(expected = "chunk size must be non-zero")]
+fn empty_chunks() {
+    let particles = ParticleVec::new();
+    particles.chunks(0);
+}
+
+#[test]
+fn binary_search() {
+    let mut particles = ParticleVec::new();
+    for (name, mass) in [("H", 1.0), ("He", 4.0), ("Li", 7.0), ("Be", 9.0), ("B", 11.0)] {
+        particles.push(Particle::new(String::from(name), mass));
+    }
+    let slice = particles.as_slice();
+
+    assert_eq!(slice.binary_search_by(|particle| particle.mass.total_cmp(&7.0)), Ok(2));
+    assert_eq!(slice.binary_search_by(|particle| particle.mass.total_cmp(&5.0)), Err(2));
+    assert_eq!(slice.binary_search_by(|particle| particle.mass.total_cmp(&12.0)), Err(5));
+
+    assert_eq!(slice.binary_search_by_key(&9, |particle| *particle.mass as u32), Ok(3));
+    assert_eq!(particles.binary_search_by_key(&0, |particle| *particle.mass as u32), Err(0));
+
+    assert_eq!(slice.partition_point(|particle| *particle.mass < 8.0), 3);
+    assert_eq!(particles.partition_point(|particle| particle.name != "B"), 4);
+    assert_eq!(particles.as_mut_slice().partition_point(|_| true), 5);
+    assert_eq!(ParticleVec::new().partition_point(|_| true), 0);
+
+    let mut versions = VersionVec::new();
+    versions.push(Version { major: 0, minor: 9 });
+    versions.push(Version { major: 1, minor: 0 });
+    versions.push(Version { major: 1, minor: 2 });
+    versions.push(Version { major: 2, minor: 0 });
+
+    assert_eq!(versions.binary_search(&Version { major: 1, minor: 2 }), Ok(2));
+    assert_eq!(versions.as_slice().binary_search(&Version { major: 1, minor: 1 }), Err(2));
+    assert_eq!(versions.as_mut_slice().binary_search(&Version { major: 3, minor: 0 }), Err(4));
+}
+
+#[test]
+fn gather() {
+    let mut particles = ParticleVec::new();
+    particles.push(Particle::new(String::from("Cl"), 35.0));
+    particles.push(Particle::new(String::from("Na"), 23.0));
+    particles.push(Particle::new(String::from("Br"), 80.0));
+
+    let selected = particles.as_slice().gather(&[2, 0, 2]);
+    assert_eq!(selected.name, ["Br", "Cl", "Br"]);
+    assert_eq!(selected.mass, [80.0, 35.0, 80.0]);
+
+    let selected = unsafe { particles.as_slice().gather_unchecked(&[1]) };
+    assert_eq!(selected.name, ["Na"]);
+    assert_eq!(selected.mass, [23.0]);
+
+    assert!(particles.as_slice().gather(&[]).is_empty());
+}
+
+#[test]
+#[should_panic(expected = "gather index 3 is out of bounds for length 3")]
+fn gather_out_of_bounds() {
+    let mut particles = ParticleVec::new();
+    particles.push(Particle::new(String::from("Cl"), 35.0));
+    particles.push(Particle::new(String::from("Na"), 23.0));
+    particles.push(Particle::new(String::from("Br"), 80.0));
+    particles.as_slice().gather(&[0, 3]);
+}
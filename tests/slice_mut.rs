@@ -1,5 +1,5 @@
 mod particles;
-use self::particles::{Particle, ParticleVec};
+use self::particles::{Particle, ParticleColumn, ParticleVec};
 
 #[test]
 fn len() {
@@ -147,3 +147,237 @@ fn sort() {
 
     assert_eq!(particles, ordered_particles);
 }
+
+#[test]
+fn sort_unstable() {
+    let mut particles = ParticleVec::new();
+    for (name, mass) in [("Na3", 168.0), ("Na", 56.0), ("Na5", 280.0), ("Na4", 224.0), ("Na2", 112.0)] {
+        particles.push(Particle::new(String::from(name), mass));
+    }
+
+    particles.as_mut_slice().sort_unstable_by(|a, b| a.mass.total_cmp(b.mass));
+    assert_eq!(particles.name, ["Na", "Na2", "Na3", "Na4", "Na5"]);
+    assert_eq!(particles.mass, [56.0, 112.0, 168.0, 224.0, 280.0]);
+
+    particles.as_mut_slice().sort_unstable_by_key(|particle| std::cmp::Reverse(particle.name.clone()));
+    assert_eq!(particles.name, ["Na5", "Na4", "Na3", "Na2", "Na"]);
+    assert_eq!(particles.mass, [280.0, 224.0, 168.0, 112.0, 56.0]);
+
+    particles.as_mut_slice().sort_by_cached_key(|particle| particle.name.len());
+    assert_eq!(particles.name, ["Na", "Na5", "Na4", "Na3", "Na2"]);
+    assert_eq!(particles.mass, [56.0, 280.0, 224.0, 168.0, 112.0]);
+
+    let mut slice = particles.as_mut_slice();
+    let (left, nth, right) = slice.select_nth_unstable_by(2, |a, b| a.mass.total_cmp(b.mass));
+    assert_eq!(*nth.mass, 168.0);
+    assert_eq!(nth.name, "Na3");
+    assert!(left.mass.iter().all(|&mass| mass < 168.0));
+    assert!(right.mass.iter().all(|&mass| mass > 168.0));
+    for (name, mass) in particles.name.iter().zip(&particles.mass) {
+        assert_eq!(name[2..].parse::<f64>().unwrap_or(1.0) * 56.0, *mass);
+    }
+}
+
+#[test]
+fn sort_by_columns() {
+    let mut particles = ParticleVec::new();
+    for (name, mass) in [("Na", 56.0), ("Cl", 35.0), ("Na", 23.0), ("Br", 80.0), ("Cl", 37.0)] {
+        particles.push(Particle::new(String::from(name), mass));
+    }
+
+    particles.as_mut_slice().sort_by_columns(&[ParticleColumn::Name.asc(), ParticleColumn::Mass.desc()]);
+    assert_eq!(particles.name, ["Br", "Cl", "Cl", "Na", "Na"]);
+    assert_eq!(particles.mass, [80.0, 37.0, 35.0, 56.0, 23.0]);
+
+    particles.sort_by_columns(&[ParticleColumn::Mass.asc()]);
+    assert_eq!(particles.name, ["Na", "Cl", "Cl", "Na", "Br"]);
+    assert_eq!(particles.mass, [23.0, 35.0, 37.0, 56.0, 80.0]);
+
+    // sorting is stable
+    particles.sort_by_columns(&[ParticleColumn::Name.desc()]);
+    assert_eq!(particles.name, ["Na", "Na", "Cl", "Cl", "Br"]);
+    assert_eq!(particles.mass, [23.0, 56.0, 35.0, 37.0, 80.0]);
+}
+
+#[test]
+fn sort_by_columns_nan() {
+    let mut particles = ParticleVec::new();
+    for (name, mass) in [("A", f64::NAN), ("B", 3.0), ("C", 1.0), ("D", f64::NAN), ("E", 2.0)] {
+        particles.push(Particle::new(String::from(name), mass));
+    }
+
+    // NaN values are placed last, keeping their relative order
+    particles.sort_by_columns(&[ParticleColumn::Mass.asc()]);
+    assert_eq!(particles.name, ["C", "E", "B", "A", "D"]);
+
+    particles.sort_by_columns(&[ParticleColumn::Mass.desc(), ParticleColumn::Name.desc()]);
+    assert_eq!(particles.name, ["B", "E", "C", "D", "A"]);
+
+    // many NaN values mixed with other values must not break the sort
+    let mut particles = ParticleVec::new();
+    for i in 0..1000 {
+        let mass = if i % 3 == 0 { f64::NAN } else { ((i * 7919) % 1000) as f64 };
+        particles.push(Particle::new(format!("{}", i), mass));
+    }
+    particles.sort_by_columns(&[ParticleColumn::Mass.asc()]);
+    let (ordered, nan) = particles.mass.split_at(666);
+    assert!(ordered.windows(2).all(|w| w[0] <= w[1]));
+    assert!(nan.iter().all(|mass| mass.is_nan()));
+}
+
+#[test]
+fn argsort_permute() {
+    let mut particles = ParticleVec::new();
+    let mut others = ParticleVec::new();
+    for (name, mass) in [("Na3", 168.0), ("Na", 56.0), ("Na4", 224.0), ("Na2", 112.0)] {
+        particles.push(Particle::new(String::from(name), mass));
+        others.push(Particle::new(name.to_lowercase(), -mass));
+    }
+
+    let order = particles.as_slice().argsort_by(|a, b| a.mass.total_cmp(b.mass));
+    assert_eq!(order, [1, 3, 0, 2]);
+    assert_eq!(particles.argsort_by_key(|particle| particle.name.clone()), order);
+
+    particles.as_mut_slice().permute(&order);
+    others.permute(&order);
+    assert_eq!(particles.name, ["Na", "Na2", "Na3", "Na4"]);
+    assert_eq!(others.name, ["na", "na2", "na3", "na4"]);
+    assert_eq!(others.mass, [-56.0, -112.0, -168.0, -224.0]);
+
+    particles.as_mut_slice().permute_inverse(&order);
+    assert_eq!(particles.name, ["Na3", "Na", "Na4", "Na2"]);
+    assert_eq!(particles.mass, [168.0, 56.0, 224.0, 112.0]);
+}
+
+#[test]
+#[should_panic(expected = "permutation index 1 is repeated")]
+fn permute_repeated_index() {
+    let mut particles = ParticleVec::new();
+    particles.push(Particle::new(String::from("Na"), 56.0));
+    particles.push(Particle::new(String::from("Cl"), 35.0));
+    particles.as_mut_slice().permute(&[1, 1]);
+}
+
+#[test]
+#[should_panic(expected = "does not match slice length")]
+fn permute_wrong_length() {
+    let mut particles = ParticleVec::new();
+    particles.push(Particle::new(String::from("Na"), 56.0));
+    particles.as_mut_slice().permute_inverse(&[0, 1]);
+}
+
+#[test]
+fn scatter_from() {
+    let mut particles = ParticleVec::new();
+    for name in ["Cl", "Na", "Br", "Zn"] {
+        particles.push(Particle::new(String::from(name), 0.0));
+    }
+
+    let mut values = ParticleVec::new();
+    values.push(Particle::new(String::from("Fe"), 56.0));
+    values.push(Particle::new(String::from("Ca"), 40.0));
+
+    particles.as_mut_slice().scatter_from(&[3, 1], values.as_slice());
+    assert_eq!(particles.name, ["Cl", "Ca", "Br", "Fe"]);
+    assert_eq!(particles.mass, [0.0, 40.0, 0.0, 56.0]);
+
+    unsafe {
+        particles.as_mut_slice().scatter_from_unchecked(&[0, 0], values.as_slice());
+    }
+    assert_eq!(particles.name, ["Ca", "Ca", "Br", "Fe"]);
+    assert_eq!(particles.mass, [40.0, 40.0, 0.0, 56.0]);
+
+    let gathered = particles.as_mut_slice().gather(&[3, 2]);
+    assert_eq!(gathered.name, ["Fe", "Br"]);
+}
+
+#[test]
+#[should_panic(expected = "scatter index 4 is out of bounds for length 4")]
+fn scatter_from_out_of_bounds() {
+    let mut particles = ParticleVec::new();
+    for name in ["Cl", "Na", "Br", "Zn"] {
+        particles.push(Particle::new(String::from(name), 0.0));
+    }
+    let mut values = ParticleVec::new();
+    values.push(Particle::new(String::from("Fe"), 56.0));
+    particles.as_mut_slice().scatter_from(&[4], values.as_slice());
+}
+
+#[test]
+#[should_panic(expected = "does not match values length")]
+fn scatter_from_wrong_length() {
+    let mut particles = ParticleVec::new();
+    particles.push(Particle::new(String::from("Cl"), 0.0));
+    let mut values = ParticleVec::new();
+    values.push(Particle::new(String::from("Fe"), 56.0));
+    particles.as_mut_slice().scatter_from(&[0, 0], values.as_slice());
+}
+
+#[test]
+fn chunks_mut() {
+    let mut particles = ParticleVec::new();
+    for i in 0..5 {
+        particles.push(Particle::new(format!("Na{}", i), 0.0));
+    }
+
+    for (i, chunk) in particles.chunks_mut(2).enumerate() {
+        for mass in chunk.mass.iter_mut() {
+            *mass = i as f64;
+        }
+    }
+    assert_eq!(particles.mass, [0.0, 0.0, 1.0, 1.0, 2.0]);
+
+    let mut slice = particles.as_mut_slice();
+    let mut chunks = slice.chunks_mut(3);
+    assert_eq!(chunks.len(), 2);
+    let last = chunks.next_back().unwrap();
+    assert_eq!(last.name, ["Na3", "Na4"]);
+    let first = chunks.next().unwrap();
+    assert_eq!(first.name, ["Na0", "Na1", "Na2"]);
+    assert!(chunks.next().is_none());
+}
+
+#[test]
+fn reorder() {
+    let mut particles = ParticleVec::new();
+    for (name, mass) in [("H", 1.0), ("He", 4.0), ("Li", 7.0), ("Be", 9.0)] {
+        particles.push(Particle::new(String::from(name), mass));
+    }
+
+    particles.as_mut_slice().reverse();
+    assert_eq!(particles.name, ["Be", "Li", "He", "H"]);
+    assert_eq!(particles.mass, [9.0, 7.0, 4.0, 1.0]);
+
+    particles.as_mut_slice().rotate_left(1);
+    assert_eq!(particles.name, ["Li", "He", "H", "Be"]);
+    assert_eq!(particles.mass, [7.0, 4.0, 1.0, 9.0]);
+
+    particles.as_mut_slice().rotate_right(3);
+    assert_eq!(particles.name, ["He", "H", "Be", "Li"]);
+    assert_eq!(particles.mass, [4.0, 1.0, 9.0, 7.0]);
+
+    let (mut start, end) = particles.as_mut_slice().split_at_mut(2);
+    start.swap_with_slice(end);
+    assert_eq!(particles.name, ["Be", "Li", "He", "H"]);
+    assert_eq!(particles.mass, [9.0, 7.0, 4.0, 1.0]);
+
+    let mut i = 0.0;
+    particles.slice_mut(1..3).fill_with(|| {
+        i += 1.0;
+        Particle::new(String::from("C"), i)
+    });
+    assert_eq!(particles.name, ["Be", "C", "C", "H"]);
+    assert_eq!(particles.mass, [9.0, 1.0, 2.0, 1.0]);
+}
+
+#[test]
+#[should_panic(expected = "destination and source slices have different lengths")]
+fn swap_with_slice_different_lengths() {
+    let mut particles = ParticleVec::new();
+    particles.push(Particle::new(String::from("H"), 1.0));
+    particles.push(Particle::new(String::from("He"), 4.0));
+    particles.push(Particle::new(String::from("Li"), 7.0));
+
+    let (mut start, end) = particles.as_mut_slice().split_at_mut(1);
+    start.swap_with_slice(end);
+}
@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use soa_derive::StructOfArray;
+
+#[derive(Debug, Clone, PartialEq, StructOfArray)]
+#[soa_derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Particle {
+    #[soa_field_attr(Vec, serde(rename = "n"))]
+    pub name: String,
+    #[soa_field_attr(Vec, serde(rename = "m", alias = "weight"))]
+    #[soa_field_attr(Vec, doc = "Masses of the particles, in atomic units")]
+    pub mass: f64,
+}
+
+#[test]
+fn serde_rename() -> Result<(), serde_json::Error> {
+    let mut soa = ParticleVec::new();
+    soa.push(Particle { name: String::from("Na"), mass: 56.0 });
+    soa.push(Particle { name: String::from("Cl"), mass: 35.0 });
+
+    let json = serde_json::to_string(&soa)?;
+    assert_eq!(json, r#"{"n":["Na","Cl"],"m":[56.0,35.0]}"#);
+
+    let soa2: ParticleVec = serde_json::from_str(&json)?;
+    assert_eq!(soa, soa2);
+
+    let soa3: ParticleVec = serde_json::from_str(r#"{"n":["Na","Cl"],"weight":[56.0,35.0]}"#)?;
+    assert_eq!(soa, soa3);
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, StructOfArray)]
+pub struct Point(
+    #[soa_field_attr(Ref, allow(missing_docs))]
+    #[soa_field_attr(PtrMut, allow(missing_docs))]
+    pub f32,
+    pub f32,
+);
+
+#[test]
+fn tuple_struct() {
+    let mut points = PointVec::new();
+    points.push(Point(1.0, 2.0));
+    assert_eq!(points.0, [1.0]);
+    assert_eq!(points.index(0).1, &2.0);
+}
+
+#[derive(Debug, Clone, PartialEq, StructOfArray)]
+#[soa_derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    pub id: u32,
+    // derive macros receive their input after `#[cfg]` is evaluated, so
+    // disabled fields are removed from all the generated types
+    #[cfg(any())]
+    pub disabled: String,
+    #[cfg(test)]
+    pub enabled: bool,
+}
+
+#[test]
+fn cfg_fields() {
+    let mut configs = ConfigVec::new();
+    configs.push(Config { id: 3, enabled: true });
+
+    assert_eq!(configs.id, [3]);
+    assert_eq!(configs.enabled, [true]);
+    assert_eq!(configs.index(0).to_owned(), Config { id: 3, enabled: true });
+    assert_eq!(configs.as_slice().enabled, [true]);
+}
@@ -0,0 +1,115 @@
+use soa_derive::{soa_zip, StructOfArray};
+
+#[derive(Debug, Clone, PartialEq, StructOfArray)]
+#[soa_derive(Debug, Clone, PartialEq)]
+pub struct Vec3(pub f32, pub f32, pub f32);
+
+#[derive(Debug, Clone, PartialEq, StructOfArray)]
+#[soa_derive(Debug, Clone, PartialEq)]
+pub struct Mass(pub f64);
+
+#[derive(Debug, Clone, PartialEq, StructOfArray)]
+#[soa_derive(Debug, Clone, PartialEq)]
+pub struct Particle(#[nested_soa] pub Vec3, pub String);
+
+#[test]
+fn tuple_struct() {
+    let mut vec = Vec3Vec::new();
+    vec.push(Vec3(1.0, 2.0, 3.0));
+    vec.push(Vec3(4.0, 5.0, 6.0));
+
+    assert_eq!(vec.len(), 2);
+    assert_eq!(vec.0, [1.0, 4.0]);
+    assert_eq!(vec.1, [2.0, 5.0]);
+    assert_eq!(vec.2, [3.0, 6.0]);
+
+    assert_eq!(vec.index(1).0, &4.0);
+    assert_eq!(vec.get(0).unwrap().to_owned(), Vec3(1.0, 2.0, 3.0));
+
+    *vec.index_mut(0).2 = 10.0;
+    assert_eq!(vec.2, [10.0, 6.0]);
+
+    let slice = vec.slice(1..2);
+    assert_eq!(slice.0, [4.0]);
+    assert_eq!(slice.first().unwrap().1, &5.0);
+
+    let mut slice_mut = vec.slice_mut(0..1);
+    *slice_mut.first_mut().unwrap().1 = 0.0;
+    assert_eq!(vec.1, [0.0, 5.0]);
+
+    let replaced = vec.replace(1, Vec3(7.0, 8.0, 9.0));
+    assert_eq!(replaced, Vec3(4.0, 5.0, 6.0));
+    assert_eq!(vec.pop(), Some(Vec3(7.0, 8.0, 9.0)));
+    assert_eq!(vec.swap_remove(0), Vec3(1.0, 0.0, 10.0));
+    assert!(vec.is_empty());
+}
+
+#[test]
+fn newtype() {
+    let masses = vec![Mass(1.0), Mass(2.0), Mass(3.0)].into_iter().collect::<MassVec>();
+    assert_eq!(masses.0, [1.0, 2.0, 3.0]);
+
+    let total = masses.iter().map(|mass| *mass.0).sum::<f64>();
+    assert_eq!(total, 6.0);
+
+    let ptr = masses.as_ptr();
+    assert_eq!(unsafe { ptr.offset(1).read() }, Mass(2.0));
+}
+
+#[test]
+fn iter() {
+    let mut vec = Vec3Vec::new();
+    vec.push(Vec3(1.0, 2.0, 3.0));
+    vec.push(Vec3(4.0, 5.0, 6.0));
+
+    for item in vec.iter_mut() {
+        *item.0 += *item.1;
+    }
+    assert_eq!(vec.0, [3.0, 9.0]);
+
+    let collected = vec.iter().map(|item| item.to_owned()).collect::<Vec<_>>();
+    assert_eq!(collected, [Vec3(3.0, 2.0, 3.0), Vec3(9.0, 5.0, 6.0)]);
+}
+
+#[test]
+fn nested() {
+    let mut particles = ParticleVec::new();
+    particles.push(Particle(Vec3(1.0, 2.0, 3.0), "first".into()));
+    particles.push(Particle(Vec3(4.0, 5.0, 6.0), "second".into()));
+
+    assert_eq!(particles.0.1, [2.0, 5.0]);
+    assert_eq!(particles.1, ["first", "second"]);
+    assert_eq!(particles.index(1).0.2, &6.0);
+    assert_eq!(particles.remove(0), Particle(Vec3(1.0, 2.0, 3.0), "first".into()));
+}
+
+#[test]
+fn zip() {
+    let mut vec = Vec3Vec::new();
+    vec.push(Vec3(1.0, 2.0, 3.0));
+    vec.push(Vec3(4.0, 5.0, 6.0));
+
+    for (x, y, z) in soa_zip!(&mut vec, [mut 0, 1, mut 2]) {
+        *x += y;
+        *z -= y;
+    }
+    assert_eq!(vec.0, [3.0, 9.0]);
+    assert_eq!(vec.2, [1.0, 1.0]);
+
+    let labels = ["a", "b"];
+    let zipped = soa_zip!(&vec, [1, 0], &labels).collect::<Vec<_>>();
+    assert_eq!(zipped, [(&2.0, &3.0, &"a"), (&5.0, &9.0, &"b")]);
+}
+
+#[derive(Debug, Clone, PartialEq, StructOfArray)]
+#[soa_derive(Debug, Clone, PartialEq)]
+pub struct Pair<T>(pub T, pub T) where T: Clone + std::fmt::Debug + PartialEq;
+
+#[test]
+fn generic() {
+    let mut pairs = PairVec::new();
+    pairs.push(Pair(1, 2));
+    pairs.push(Pair(3, 4));
+    assert_eq!(pairs.1, [2, 4]);
+    assert_eq!(pairs.as_slice().index(0).to_owned(), Pair(1, 2));
+}
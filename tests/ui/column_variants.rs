@@ -0,0 +1,12 @@
+use soa_derive::StructOfArray;
+
+#[derive(StructOfArray)]
+#[allow(non_snake_case)]
+pub struct Conflicts {
+    foo_bar: f64,
+    fooBar: f64,
+    r#type: u32,
+    type_: u32,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use soa_derive::StructOfArray;
+
+#[derive(StructOfArray)]
+pub struct Empty {}
+
+#[derive(StructOfArray)]
+pub struct Unit;
+
+fn main() {}
@@ -0,0 +1,38 @@
+use soa_derive::StructOfArray;
+
+#[derive(StructOfArray)]
+pub enum Empty {}
+
+#[derive(StructOfArray)]
+pub enum Units {
+    First,
+    Second,
+}
+
+#[derive(StructOfArray)]
+pub enum Generic<T> {
+    Value(T),
+}
+
+#[derive(StructOfArray)]
+pub enum Reserved {
+    Kinds(u32),
+    Indices { count: usize },
+}
+
+#[derive(StructOfArray)]
+pub enum Conflicts {
+    Kind(u32),
+    Vec { count: usize },
+    Point(f32),
+    PointRef(f32),
+    Unit,
+}
+
+#[derive(StructOfArray)]
+#[soa(slice = "StructOptionsView", column = "StructOptionsField")]
+pub enum StructOptions {
+    Value(u32),
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use soa_derive::StructOfArray;
+
+#[derive(StructOfArray)]
+#[soa_derive(Copy)]
+#[soa_attr(Vec)]
+#[soa_attr(Reference, derive(Debug))]
+pub struct Particle {
+    #[nested_soa]
+    pub name: &'static str,
+    pub mass: f64,
+}
+
+fn main() {}
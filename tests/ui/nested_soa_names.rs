@@ -0,0 +1,17 @@
+use soa_derive::StructOfArray;
+
+#[derive(StructOfArray)]
+#[soa(vec = "Points")]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(StructOfArray)]
+pub struct Particle {
+    #[nested_soa]
+    pub position: Point,
+    pub mass: f64,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use soa_derive::StructOfArray;
+
+#[derive(StructOfArray)]
+pub struct Particle {
+    #[nested_soa]
+    pub position: (f64, f64, f64),
+    pub mass: f64,
+}
+
+fn main() {}
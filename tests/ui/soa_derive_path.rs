@@ -0,0 +1,9 @@
+use soa_derive::StructOfArray;
+
+#[derive(StructOfArray)]
+#[soa_derive(std::fmt::Debug)]
+pub struct Particle {
+    pub mass: f64,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use soa_derive::StructOfArray;
+
+#[derive(StructOfArray)]
+pub struct Particle {
+    #[soa_field_attr(Vec)]
+    pub name: String,
+    #[soa_field_attr(Array, serde(skip))]
+    pub mass: f64,
+}
+
+fn main() {}
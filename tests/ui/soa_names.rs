@@ -0,0 +1,21 @@
+use soa_derive::StructOfArray;
+
+#[derive(StructOfArray)]
+#[soa(vector = "ParticleColumns")]
+pub struct UnknownOption {
+    foo: f64,
+}
+
+#[derive(StructOfArray)]
+#[soa(vec = "not an ident")]
+pub struct InvalidName {
+    foo: f64,
+}
+
+#[derive(StructOfArray)]
+#[soa(crate = 3)]
+pub struct InvalidCrate {
+    foo: f64,
+}
+
+fn main() {}
@@ -0,0 +1,30 @@
+use soa_derive::StructOfArray;
+
+#[derive(StructOfArray)]
+pub struct UnknownKey {
+    foo: f64,
+    #[soa(skipped)]
+    bar: f64,
+}
+
+#[derive(StructOfArray)]
+pub struct DefaultWithoutSkip {
+    foo: f64,
+    #[soa(default = "Default::default")]
+    bar: f64,
+}
+
+#[derive(StructOfArray)]
+pub struct AllSkipped {
+    #[soa(skip)]
+    foo: f64,
+}
+
+#[derive(StructOfArray)]
+pub struct NotDefault {
+    foo: f64,
+    #[soa(skip)]
+    bar: std::fs::File,
+}
+
+fn main() {}
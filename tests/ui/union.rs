@@ -0,0 +1,9 @@
+use soa_derive::StructOfArray;
+
+#[derive(StructOfArray)]
+pub union Number {
+    pub int: i64,
+    pub float: f64,
+}
+
+fn main() {}
@@ -151,6 +151,155 @@ fn split_off() {
     assert_eq!(other.index(1).name, "Mg");
 }
 
+#[test]
+fn drain() {
+    let mut particles = ParticleVec::new();
+    particles.push(Particle::new(String::from("Cl"), 0.0));
+    particles.push(Particle::new(String::from("Na"), 1.0));
+    particles.push(Particle::new(String::from("Zn"), 2.0));
+    particles.push(Particle::new(String::from("Mg"), 3.0));
+    particles.push(Particle::new(String::from("C"), 4.0));
+
+    let mut drain = particles.drain(1..=3);
+    assert_eq!(drain.len(), 3);
+    assert_eq!(drain.next(), Some(Particle::new(String::from("Na"), 1.0)));
+    assert_eq!(drain.next_back(), Some(Particle::new(String::from("Mg"), 3.0)));
+    assert_eq!(drain.len(), 1);
+    drop(drain);
+
+    assert_eq!(particles.len(), 2);
+    assert_eq!(particles.name, ["Cl", "C"]);
+    assert_eq!(particles.mass, [0.0, 4.0]);
+
+    let drained = particles.drain(..).collect::<Vec<_>>();
+    assert_eq!(drained, [Particle::new(String::from("Cl"), 0.0), Particle::new(String::from("C"), 4.0)]);
+    assert!(particles.is_empty());
+}
+
+#[test]
+#[should_panic]
+fn drain_out_of_bounds() {
+    let mut particles = ParticleVec::new();
+    particles.push(Particle::new(String::from("Cl"), 0.0));
+    particles.drain(0..2);
+}
+
+#[test]
+fn splice() {
+    let mut particles = ParticleVec::new();
+    particles.push(Particle::new(String::from("Cl"), 0.0));
+    particles.push(Particle::new(String::from("Na"), 1.0));
+    particles.push(Particle::new(String::from("Zn"), 2.0));
+    particles.push(Particle::new(String::from("Mg"), 3.0));
+
+    let new = vec![
+        Particle::new(String::from("H"), 4.0),
+        Particle::new(String::from("He"), 5.0),
+        Particle::new(String::from("Li"), 6.0),
+    ];
+    let removed = particles.splice(1..3, new).collect::<Vec<_>>();
+    assert_eq!(removed, [Particle::new(String::from("Na"), 1.0), Particle::new(String::from("Zn"), 2.0)]);
+
+    assert_eq!(particles.len(), 5);
+    assert_eq!(particles.name, ["Cl", "H", "He", "Li", "Mg"]);
+    assert_eq!(particles.mass, [0.0, 4.0, 5.0, 6.0, 3.0]);
+
+    // the replacement happens even if the removed values are not consumed
+    particles.splice(..2, None);
+    assert_eq!(particles.name, ["He", "Li", "Mg"]);
+    assert_eq!(particles.mass, [5.0, 6.0, 3.0]);
+}
+
+#[test]
+fn extend_from_within() {
+    let mut particles = ParticleVec::new();
+    particles.push(Particle::new(String::from("Cl"), 0.0));
+    particles.push(Particle::new(String::from("Na"), 1.0));
+    particles.push(Particle::new(String::from("Zn"), 2.0));
+
+    particles.extend_from_within(1..);
+    assert_eq!(particles.name, ["Cl", "Na", "Zn", "Na", "Zn"]);
+    assert_eq!(particles.mass, [0.0, 1.0, 2.0, 1.0, 2.0]);
+
+    particles.extend_from_within(..=0);
+    assert_eq!(particles.name, ["Cl", "Na", "Zn", "Na", "Zn", "Cl"]);
+    assert_eq!(particles.mass, [0.0, 1.0, 2.0, 1.0, 2.0, 0.0]);
+}
+
+#[test]
+fn dedup() {
+    let mut particles = ParticleVec::new();
+    particles.push(Particle::new(String::from("Cl"), 0.0));
+    particles.push(Particle::new(String::from("Cl"), 0.0));
+    particles.push(Particle::new(String::from("Cl"), 1.0));
+    particles.push(Particle::new(String::from("Na"), 1.0));
+    particles.push(Particle::new(String::from("Na"), 1.0));
+    particles.push(Particle::new(String::from("Cl"), 0.0));
+
+    particles.dedup();
+    assert_eq!(particles.name, ["Cl", "Cl", "Na", "Cl"]);
+    assert_eq!(particles.mass, [0.0, 1.0, 1.0, 0.0]);
+
+    particles.dedup_by_key(|particle| *particle.mass);
+    assert_eq!(particles.name, ["Cl", "Cl", "Cl"]);
+    assert_eq!(particles.mass, [0.0, 1.0, 0.0]);
+
+    particles.dedup_by(|a, b| {
+        // merge the duplicated values in the one that is kept
+        *b.mass += *a.mass;
+        a.name == b.name
+    });
+    assert_eq!(particles.name, ["Cl"]);
+    assert_eq!(particles.mass, [1.0]);
+}
+
+#[test]
+fn extract_if() {
+    let mut particles = ParticleVec::new();
+    particles.push(Particle::new(String::from("Cl"), 0.0));
+    particles.push(Particle::new(String::from("Na"), 1.0));
+    particles.push(Particle::new(String::from("Zn"), 2.0));
+    particles.push(Particle::new(String::from("Mg"), 3.0));
+    particles.push(Particle::new(String::from("C"), 4.0));
+
+    let mut graveyard = ParticleVec::new();
+    Extend::extend(&mut graveyard, particles.extract_if(.., |particle| *particle.mass < 0.5 || *particle.mass > 2.5));
+    assert_eq!(graveyard.name, ["Cl", "Mg", "C"]);
+    assert_eq!(particles.name, ["Na", "Zn"]);
+    assert_eq!(particles.mass, [1.0, 2.0]);
+
+    // values outside of the range are kept
+    Extend::extend(&mut particles, graveyard.drain(..));
+    let extracted = particles.extract_if(2.., |particle| particle.name.starts_with('C')).collect::<Vec<_>>();
+    assert_eq!(extracted, [Particle::new(String::from("Cl"), 0.0), Particle::new(String::from("C"), 4.0)]);
+    assert_eq!(particles.name, ["Na", "Zn", "Mg"]);
+
+    // the filter can modify the values which are kept
+    let extracted = particles.extract_if(.., |particle| {
+        *particle.mass *= 2.0;
+        particle.name == "Zn"
+    }).count();
+    assert_eq!(extracted, 1);
+    assert_eq!(particles.name, ["Na", "Mg"]);
+    assert_eq!(particles.mass, [2.0, 6.0]);
+}
+
+#[test]
+fn extract_if_dropped_early() {
+    let mut particles = ParticleVec::new();
+    particles.push(Particle::new(String::from("Cl"), 0.0));
+    particles.push(Particle::new(String::from("Na"), 1.0));
+    particles.push(Particle::new(String::from("Zn"), 2.0));
+    particles.push(Particle::new(String::from("Mg"), 3.0));
+
+    let mut iter = particles.extract_if(.., |particle| *particle.mass > 0.5);
+    assert_eq!(iter.next(), Some(Particle::new(String::from("Na"), 1.0)));
+    drop(iter);
+
+    assert_eq!(particles.name, ["Cl", "Zn", "Mg"]);
+    assert_eq!(particles.mass, [0.0, 2.0, 3.0]);
+}
+
 #[test]
 fn retain() {
     let mut particles = ParticleVec::new();
@@ -192,6 +341,55 @@ impl Drop for IncrOnDrop {
     }
 }
 
+#[test]
+fn mask() {
+    let mut particles = ParticleVec::new();
+    particles.push(Particle::new(String::from("Cl"), 35.0));
+    particles.push(Particle::new(String::from("Na"), 23.0));
+    particles.push(Particle::new(String::from("Br"), 80.0));
+    particles.push(Particle::new(String::from("Zn"), 65.0));
+
+    let heavy = particles.mass.iter().map(|&mass| mass > 50.0).collect::<Vec<_>>();
+    let filtered = particles.filter_by_mask(&heavy);
+    assert_eq!(filtered.name, ["Br", "Zn"]);
+    assert_eq!(filtered.mass, [80.0, 65.0]);
+    assert_eq!(particles.len(), 4);
+
+    let selection = particles.select_by_mask(&heavy);
+    assert_eq!(selection.len(), 2);
+    assert_eq!(selection.indices(), [2, 3]);
+    assert_eq!(selection.index(1).name, "Zn");
+    assert_eq!(selection.get(2), None);
+    let names = selection.iter().map(|particle| particle.name.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, ["Br", "Zn"]);
+    assert_eq!(selection.to_vec(), filtered);
+
+    let selection = particles.select(vec![3, 0, 3]);
+    let masses = selection.iter().rev().map(|particle| *particle.mass).collect::<Vec<_>>();
+    assert_eq!(masses, [65.0, 35.0, 65.0]);
+
+    particles.retain_by_mask(&heavy);
+    assert_eq!(particles, filtered);
+}
+
+#[test]
+#[should_panic(expected = "mask length (1) does not match vector length (2)")]
+fn retain_by_mask_wrong_length() {
+    let mut particles = ParticleVec::new();
+    particles.push(Particle::new(String::from("Cl"), 35.0));
+    particles.push(Particle::new(String::from("Na"), 23.0));
+    particles.retain_by_mask(&[true]);
+}
+
+#[test]
+#[should_panic(expected = "selection index 2 is out of bounds for length 2")]
+fn select_out_of_bounds() {
+    let mut particles = ParticleVec::new();
+    particles.push(Particle::new(String::from("Cl"), 35.0));
+    particles.push(Particle::new(String::from("Na"), 23.0));
+    particles.select(vec![2]);
+}
+
 #[test]
 fn drop_vec() {
     let counter = Rc::new(Cell::default());
@@ -206,3 +404,91 @@ fn drop_vec() {
     drop(vec);
     assert_eq!(counter.get(), 5);
 }
+
+#[test]
+fn drop_permute() {
+    let counter = Rc::new(Cell::default());
+    let mut vec = IncrOnDropVec::new();
+    for _ in 0..5 {
+        vec.push(IncrOnDrop {
+            cell: counter.clone(),
+        });
+    }
+
+    vec.as_mut_slice().permute(&[3, 0, 4, 1, 2]);
+    vec.as_mut_slice().sort_unstable_by_key(|value| Rc::as_ptr(value.cell));
+    assert_eq!(counter.get(), 0);
+    assert_eq!(Rc::strong_count(&counter), 6);
+    drop(vec);
+    assert_eq!(counter.get(), 5);
+}
+
+#[test]
+fn drop_drain() {
+    let counter = Rc::new(Cell::default());
+    let mut vec = IncrOnDropVec::new();
+    for _ in 0..5 {
+        vec.push(IncrOnDrop {
+            cell: counter.clone(),
+        });
+    }
+
+    let mut drain = vec.drain(1..4);
+    drop(drain.next());
+    assert_eq!(counter.get(), 1);
+    // the values left in the drained range are dropped with the iterator
+    drop(drain);
+    assert_eq!(counter.get(), 3);
+    assert_eq!(vec.len(), 2);
+
+    drop(vec);
+    assert_eq!(counter.get(), 5);
+}
+
+#[test]
+fn drop_splice() {
+    let counter = Rc::new(Cell::default());
+    let mut vec = IncrOnDropVec::new();
+    for _ in 0..5 {
+        vec.push(IncrOnDrop {
+            cell: counter.clone(),
+        });
+    }
+
+    let new = (0..2).map(|_| IncrOnDrop { cell: counter.clone() });
+    let mut splice = vec.splice(1..4, new);
+    drop(splice.next());
+    assert_eq!(counter.get(), 1);
+    drop(splice);
+    assert_eq!(counter.get(), 3);
+    assert_eq!(vec.len(), 4);
+
+    drop(vec);
+    assert_eq!(counter.get(), 7);
+}
+
+#[test]
+fn drop_extract_if() {
+    let counter = Rc::new(Cell::default());
+    let mut vec = IncrOnDropVec::new();
+    for _ in 0..6 {
+        vec.push(IncrOnDrop {
+            cell: counter.clone(),
+        });
+    }
+
+    let mut i = 0;
+    let extracted = vec.extract_if(.., |_| {
+        i += 1;
+        i % 2 == 0
+    }).count();
+    assert_eq!(extracted, 3);
+    assert_eq!(counter.get(), 3);
+    assert_eq!(vec.len(), 3);
+
+    // leaking the iterator leaks the values, but does not drop them twice
+    std::mem::forget(vec.extract_if(.., |_| true));
+    assert_eq!(vec.len(), 0);
+    drop(vec);
+    assert_eq!(counter.get(), 3);
+}